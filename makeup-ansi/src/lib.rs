@@ -1,8 +1,13 @@
-use eyre::Result;
+use eyre::{eyre, Result};
+
+mod parse;
+
+pub use parse::AnsiParseError;
 
 pub mod prelude {
     pub use crate::{
-        Ansi, Colour, CursorStyle, CursorVisibility, DisplayEraseMode, LineEraseMode, SgrParameter,
+        parse_hex_colour, Ansi, AnsiParseError, Colour, CursorStyle, CursorVisibility,
+        DisplayEraseMode, LineEraseMode, SgrParameter,
     };
 }
 
@@ -69,6 +74,24 @@ pub enum Ansi {
     /// This is not supported on Windows.
     /// See: <https://en.wikipedia.org/wiki/ANSI_escape_code#SGR_(Select_Graphic_Rendition)_parameters>
     Sgr(Vec<SgrParameter>),
+    /// Begin a synchronized update. The terminal holds off on presenting
+    /// output until the matching `EndSynchronizedUpdate`.
+    /// Terminals that don't support this will ignore it.
+    /// See: <https://gist.github.com/christianparpart/d8a62cc1ab659194337d73e399004036>
+    BeginSynchronizedUpdate,
+    /// End a synchronized update, presenting everything written since the
+    /// matching `BeginSynchronizedUpdate`.
+    EndSynchronizedUpdate,
+    /// Enter (`true`) or leave (`false`) the alternate screen. Leaving
+    /// restores whatever was on the normal screen before it was entered.
+    AlternateScreen(bool),
+    /// Turn bracketed paste on or off. While it's on, pasted text is wrapped
+    /// in `ESC [ 200 ~` and `ESC [ 201 ~`, so that it can be told apart from
+    /// typing.
+    BracketedPaste(bool),
+    /// Turn mouse reporting on or off, with clicks and the wheel reported in
+    /// the SGR extended format.
+    MouseReporting(bool),
 }
 
 impl Ansi {
@@ -87,7 +110,10 @@ impl Ansi {
                     write!(f, ansi!("5 q"))
                 }
                 CursorStyle::HollowBlock => {
-                    write!(f, ansi!("2 q"))
+                    write!(f, ansi!("0 q"))
+                }
+                CursorStyle::Underline => {
+                    write!(f, ansi!("4 q"))
                 }
             },
             Self::CursorVisibility(visibility) => match visibility {
@@ -195,6 +221,15 @@ impl Ansi {
                         SgrParameter::Underline => {
                             write!(f, "4")
                         }
+                        SgrParameter::CurlyUnderline => {
+                            write!(f, "4:3")
+                        }
+                        SgrParameter::DottedUnderline => {
+                            write!(f, "4:4")
+                        }
+                        SgrParameter::DashedUnderline => {
+                            write!(f, "4:5")
+                        }
                         SgrParameter::Blink => {
                             write!(f, "5")
                         }
@@ -280,10 +315,10 @@ impl Ansi {
                             write!(f, "65")
                         }
                         SgrParameter::ForegroundColour(colour) => {
-                            write!(f, "38;5;{}", colour.index())
+                            write!(f, "{}", colour.fg_code())
                         }
                         SgrParameter::BackgroundColour(colour) => {
-                            write!(f, "48;5;{}", colour.index())
+                            write!(f, "{}", colour.bg_code())
                         }
                         SgrParameter::HexForegroundColour(hex) => {
                             let (r, g, b) = Self::rgb(hex);
@@ -321,10 +356,46 @@ impl Ansi {
                         SgrParameter::NotSuperscriptOrSubscript => {
                             write!(f, "75")
                         }
+                        SgrParameter::IndexedForegroundColour(index) => {
+                            write!(f, "38;5;{index}")
+                        }
+                        SgrParameter::IndexedBackgroundColour(index) => {
+                            write!(f, "48;5;{index}")
+                        }
+                        SgrParameter::IndexedUnderlineColour(index) => {
+                            write!(f, "58;5;{index}")
+                        }
+                        SgrParameter::Unknown(code) => {
+                            write!(f, "{code}")
+                        }
                     }?;
                 }
                 write!(f, "m")
             }
+            Self::BeginSynchronizedUpdate => {
+                write!(f, ansi!("?2026h"))
+            }
+            Self::EndSynchronizedUpdate => {
+                write!(f, ansi!("?2026l"))
+            }
+            Self::AlternateScreen(true) => {
+                write!(f, ansi!("?1049h"))
+            }
+            Self::AlternateScreen(false) => {
+                write!(f, ansi!("?1049l"))
+            }
+            Self::BracketedPaste(true) => {
+                write!(f, ansi!("?2004h"))
+            }
+            Self::BracketedPaste(false) => {
+                write!(f, ansi!("?2004l"))
+            }
+            Self::MouseReporting(true) => {
+                write!(f, ansi!("?1000;1006h"))
+            }
+            Self::MouseReporting(false) => {
+                write!(f, ansi!("?1000;1006l"))
+            }
         }
         .map_err(|e| e.into())
     }
@@ -354,7 +425,13 @@ pub enum CursorStyle {
     Bar,
 
     /// The cursor is a hollow block.
+    /// Note: DECSCUSR has no dedicated hollow block, so this selects the
+    /// terminal's default cursor style, which is commonly drawn as a hollow
+    /// block.
     HollowBlock,
+
+    /// The cursor is an underline.
+    Underline,
 }
 
 /// Terminal cursor visibility.
@@ -424,6 +501,91 @@ impl Colour {
     pub fn index(&self) -> u64 {
         *self as u64
     }
+
+    /// The SGR code that sets the foreground to this colour, ie. 30-37 for
+    /// the standard colours and 90-97 for the bright colours.
+    pub fn fg_code(&self) -> u64 {
+        match self.index() {
+            index @ 0..=7 => 30 + index,
+            index => 90 + index - 8,
+        }
+    }
+
+    /// The SGR code that sets the background to this colour, ie. 40-47 for
+    /// the standard colours and 100-107 for the bright colours.
+    pub fn bg_code(&self) -> u64 {
+        self.fg_code() + 10
+    }
+
+    /// The colour at the given index in the enum, if any.
+    pub fn from_index(index: u64) -> Option<Self> {
+        match index {
+            0 => Some(Self::Black),
+            1 => Some(Self::Red),
+            2 => Some(Self::Green),
+            3 => Some(Self::Yellow),
+            4 => Some(Self::Blue),
+            5 => Some(Self::Magenta),
+            6 => Some(Self::Cyan),
+            7 => Some(Self::White),
+            8 => Some(Self::BrightBlack),
+            9 => Some(Self::BrightRed),
+            10 => Some(Self::BrightGreen),
+            11 => Some(Self::BrightYellow),
+            12 => Some(Self::BrightBlue),
+            13 => Some(Self::BrightMagenta),
+            14 => Some(Self::BrightCyan),
+            15 => Some(Self::BrightWhite),
+            _ => None,
+        }
+    }
+
+    /// The canonical `0xRRGGBB` value of this colour, taken from the VGA
+    /// palette.
+    pub fn rgb(&self) -> u32 {
+        match self {
+            Self::Black => 0x000000,
+            Self::Red => 0xAA0000,
+            Self::Green => 0x00AA00,
+            Self::Yellow => 0xAA5500,
+            Self::Blue => 0x0000AA,
+            Self::Magenta => 0xAA00AA,
+            Self::Cyan => 0x00AAAA,
+            Self::White => 0xAAAAAA,
+            Self::BrightBlack => 0x555555,
+            Self::BrightRed => 0xFF5555,
+            Self::BrightGreen => 0x55FF55,
+            Self::BrightYellow => 0xFFFF55,
+            Self::BrightBlue => 0x5555FF,
+            Self::BrightMagenta => 0xFF55FF,
+            Self::BrightCyan => 0x55FFFF,
+            Self::BrightWhite => 0xFFFFFF,
+        }
+    }
+
+    /// The palette colour closest to the given `0xRRGGBB` value, by squared
+    /// RGB distance to [`Colour::rgb`]. Ties go to the lower index.
+    pub fn nearest(hex: u32) -> Self {
+        let channels = |hex: u32| {
+            [
+                ((hex >> 16) & 0xFF) as i32,
+                ((hex >> 8) & 0xFF) as i32,
+                (hex & 0xFF) as i32,
+            ]
+        };
+        let target = channels(hex);
+
+        (0..16)
+            .filter_map(Self::from_index)
+            .min_by_key(|colour| {
+                channels(colour.rgb())
+                    .iter()
+                    .zip(target.iter())
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum::<i32>()
+            })
+            .unwrap_or(Self::Black)
+    }
 }
 
 /// Erase part or all of the current display.
@@ -473,6 +635,18 @@ pub enum SgrParameter {
     /// Underline.
     Underline,
 
+    /// Curly underline.
+    /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
+    CurlyUnderline,
+
+    /// Dotted underline.
+    /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
+    DottedUnderline,
+
+    /// Dashed underline.
+    /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
+    DashedUnderline,
+
     /// Blink.
     Blink,
 
@@ -536,6 +710,12 @@ pub enum SgrParameter {
     /// Set background colour to the given colour.
     BackgroundColour(Colour),
 
+    /// Set the foreground colour to the given index in the 256-colour palette.
+    IndexedForegroundColour(u8),
+
+    /// Set the background colour to the given index in the 256-colour palette.
+    IndexedBackgroundColour(u8),
+
     /// Set the foreground colour to the given hex colour.
     HexForegroundColour(u32),
 
@@ -572,6 +752,10 @@ pub enum SgrParameter {
     /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
     UnderlineColour(Colour),
 
+    /// Set the underline colour to the given index in the 256-colour palette.
+    /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
+    IndexedUnderlineColour(u8),
+
     /// Set the underline colour to the given hex colour.
     /// Note: Not in standard, implemented in Kitty, VTE, mintty, iTerm2.
     HexUnderlineColour(u32),
@@ -612,13 +796,57 @@ pub enum SgrParameter {
 
     /// Implemented only in mintty.
     NotSuperscriptOrSubscript,
+
+    /// A parameter that makeup doesn't know about. Rendered as-is.
+    Unknown(u16),
+}
+
+impl SgrParameter {
+    /// Set the foreground colour to the given hex colour string. See
+    /// [`parse_hex_colour`] for the accepted formats.
+    pub fn hex_fg(colour: &str) -> Result<Self> {
+        parse_hex_colour(colour).map(Self::HexForegroundColour)
+    }
+
+    /// Set the background colour to the given hex colour string. See
+    /// [`parse_hex_colour`] for the accepted formats.
+    pub fn hex_bg(colour: &str) -> Result<Self> {
+        parse_hex_colour(colour).map(Self::HexBackgroundColour)
+    }
+
+    /// Set the foreground colour to the 16-colour palette entry nearest to
+    /// the given hex colour, for terminals without truecolour support.
+    pub fn foreground_from_hex_downsampled(hex: u32) -> Self {
+        Self::ForegroundColour(Colour::nearest(hex))
+    }
+}
+
+/// Parse a hex colour string into a `0xRRGGBB` value. Accepts `#RRGGBB`,
+/// `RRGGBB`, and `#RGB`.
+pub fn parse_hex_colour(colour: &str) -> Result<u32> {
+    let invalid = || eyre!("invalid hex colour {colour:?}, expected #RRGGBB, RRGGBB, or #RGB");
+
+    let digits = colour.strip_prefix('#');
+    let hex = match digits {
+        Some(digits) if digits.len() == 3 => digits.chars().flat_map(|c| [c, c]).collect(),
+        Some(digits) if digits.len() == 6 => digits.to_string(),
+        None if colour.len() == 6 => colour.to_string(),
+        _ => return Err(invalid()),
+    };
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    u32::from_str_radix(&hex, 16).map_err(|_| invalid())
 }
 
 #[cfg(test)]
 mod tests {
     use eyre::Result;
 
-    use super::{Ansi, DisplayEraseMode, SgrParameter};
+    use std::collections::HashSet;
+
+    use super::{parse_hex_colour, Ansi, Colour, CursorStyle, DisplayEraseMode, SgrParameter};
 
     #[test]
     fn test_works_as_expected() -> Result<()> {
@@ -671,6 +899,145 @@ mod tests {
         assert_eq!("\u{1b}[48;2;219;50;92m", buffer);
         buffer.clear();
 
+        Ansi::BeginSynchronizedUpdate.render(&mut buffer)?;
+        assert_eq!("\u{1b}[?2026h", buffer);
+        buffer.clear();
+
+        Ansi::EndSynchronizedUpdate.render(&mut buffer)?;
+        assert_eq!("\u{1b}[?2026l", buffer);
+        buffer.clear();
+
+        Ansi::AlternateScreen(true).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?1049h", buffer);
+        buffer.clear();
+
+        Ansi::AlternateScreen(false).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?1049l", buffer);
+        buffer.clear();
+
+        Ansi::BracketedPaste(true).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?2004h", buffer);
+        buffer.clear();
+
+        Ansi::BracketedPaste(false).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?2004l", buffer);
+        buffer.clear();
+
+        Ansi::MouseReporting(true).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?1000;1006h", buffer);
+        buffer.clear();
+
+        Ansi::MouseReporting(false).render(&mut buffer)?;
+        assert_eq!("\u{1b}[?1000;1006l", buffer);
+        buffer.clear();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cursor_styles_are_distinct() -> Result<()> {
+        let styles = [
+            CursorStyle::Block,
+            CursorStyle::Bar,
+            CursorStyle::HollowBlock,
+            CursorStyle::Underline,
+        ];
+
+        let rendered: HashSet<String> = styles
+            .iter()
+            .map(|style| Ansi::CursorStyle(style.clone()).to_string())
+            .collect();
+        assert_eq!(styles.len(), rendered.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_hex_colour() -> Result<()> {
+        assert_eq!(0xDB325C, parse_hex_colour("#DB325C")?);
+        assert_eq!(0xDB325C, parse_hex_colour("db325c")?);
+        assert_eq!(0xFF00AA, parse_hex_colour("#F0A")?);
+        assert_eq!(
+            SgrParameter::HexForegroundColour(0xDB325C),
+            SgrParameter::hex_fg("#DB325C")?
+        );
+        assert_eq!(
+            SgrParameter::HexBackgroundColour(0xFF00AA),
+            SgrParameter::hex_bg("#F0A")?
+        );
+
+        for invalid in ["", "#", "F0A", "#DB325", "#GGGGGG", "#+FFFFF", "#DB325CFF"] {
+            assert!(parse_hex_colour(invalid).is_err(), "{invalid:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_underline_styles() -> Result<()> {
+        let mut buffer = String::new();
+
+        Ansi::Sgr(vec![SgrParameter::Underline]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[4m", buffer);
+        buffer.clear();
+
+        Ansi::Sgr(vec![SgrParameter::CurlyUnderline]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[4:3m", buffer);
+        buffer.clear();
+
+        Ansi::Sgr(vec![SgrParameter::DottedUnderline]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[4:4m", buffer);
+        buffer.clear();
+
+        Ansi::Sgr(vec![SgrParameter::DashedUnderline]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[4:5m", buffer);
+        buffer.clear();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_colour_codes() -> Result<()> {
+        assert_eq!(30, Colour::Black.fg_code());
+        assert_eq!(37, Colour::White.fg_code());
+        assert_eq!(90, Colour::BrightBlack.fg_code());
+        assert_eq!(91, Colour::BrightRed.fg_code());
+        assert_eq!(97, Colour::BrightWhite.fg_code());
+        assert_eq!(40, Colour::Black.bg_code());
+        assert_eq!(41, Colour::Red.bg_code());
+        assert_eq!(101, Colour::BrightRed.bg_code());
+        assert_eq!(107, Colour::BrightWhite.bg_code());
+
+        let mut buffer = String::new();
+        Ansi::Sgr(vec![SgrParameter::ForegroundColour(Colour::BrightRed)]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[91m", buffer);
+        buffer.clear();
+
+        Ansi::Sgr(vec![SgrParameter::BackgroundColour(Colour::Blue)]).render(&mut buffer)?;
+        assert_eq!("\u{1b}[44m", buffer);
+        buffer.clear();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_nearest_colour() -> Result<()> {
+        assert_eq!(Colour::Red, Colour::nearest(0xFF0000));
+        assert_eq!(Colour::Black, Colour::nearest(0x000000));
+        assert_eq!(Colour::BrightWhite, Colour::nearest(0xFFFFFF));
+        assert_eq!(Colour::BrightBlack, Colour::nearest(0x606060));
+        assert_eq!(Colour::Cyan, Colour::nearest(0x10A0B0));
+
+        for index in 0..16 {
+            let colour = Colour::from_index(index).unwrap();
+            assert_eq!(colour, Colour::nearest(colour.rgb()));
+        }
+
+        assert_eq!(
+            SgrParameter::ForegroundColour(Colour::Red),
+            SgrParameter::foreground_from_hex_downsampled(0xFF0000)
+        );
+
         Ok(())
     }
 }
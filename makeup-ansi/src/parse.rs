@@ -0,0 +1,520 @@
+use crate::{
+    Ansi, Colour, CursorStyle, CursorVisibility, DisplayEraseMode, LineEraseMode, SgrParameter,
+};
+
+/// An error that occurred while parsing an ANSI escape sequence.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AnsiParseError {
+    /// The input does not start with an escape sequence.
+    #[error("input does not start with an escape sequence")]
+    NotAnEscapeSequence,
+
+    /// The input ends partway through an escape sequence. Streaming callers
+    /// should wait for more input and try again.
+    #[error("incomplete escape sequence")]
+    Incomplete,
+
+    /// The escape sequence is well-formed, but isn't one that [`Ansi`] knows
+    /// how to represent.
+    #[error("unrecognized escape sequence: {0:?}")]
+    Unrecognized(String),
+}
+
+impl Ansi {
+    /// Parse one escape sequence from the front of `input`, returning the
+    /// parsed sequence and the remaining input.
+    pub fn parse(input: &str) -> Result<(Ansi, &str), AnsiParseError> {
+        match input.as_bytes() {
+            [] | [0x1b] => Err(AnsiParseError::Incomplete),
+            [0x1b, b'[', ..] => parse_csi(input),
+            [0x1b, b']', ..] => parse_osc(input),
+            [0x1b, ..] => Err(AnsiParseError::Unrecognized(
+                input.chars().take(2).collect(),
+            )),
+            _ => Err(AnsiParseError::NotAnEscapeSequence),
+        }
+    }
+}
+
+/// Parse a Control Sequence Introducer sequence, ie. `ESC [ ...`.
+fn parse_csi(input: &str) -> Result<(Ansi, &str), AnsiParseError> {
+    let body = &input[2..];
+    // The final byte of a CSI sequence is in the range 0x40-0x7E.
+    let end = body
+        .bytes()
+        .position(|b| (0x40..=0x7e).contains(&b))
+        .ok_or(AnsiParseError::Incomplete)?;
+    let sequence = &input[..2 + end + 1];
+    let rest = &input[2 + end + 1..];
+    let unrecognized = || AnsiParseError::Unrecognized(sequence.to_string());
+
+    let head = &body[..end];
+    let final_byte = body.as_bytes()[end] as char;
+    // Parameter bytes are 0x30-0x3F, intermediate bytes are 0x20-0x2F.
+    if head.bytes().any(|b| !(0x20..=0x3f).contains(&b)) {
+        return Err(unrecognized());
+    }
+    let split = head
+        .bytes()
+        .position(|b| (0x20..=0x2f).contains(&b))
+        .unwrap_or(head.len());
+    let (params, intermediates) = head.split_at(split);
+    let (private, params) = match params.strip_prefix('?') {
+        Some(params) => (true, params),
+        None => (false, params),
+    };
+    // SGR parameters may use `:` subparameters, so they're handled separately.
+    if (private, intermediates, final_byte) == (false, "", 'm') {
+        return Ok((Ansi::Sgr(parse_sgr(params).ok_or_else(unrecognized)?), rest));
+    }
+    let params = parse_params(params).ok_or_else(unrecognized)?;
+
+    // Sequences that take a single, optional count.
+    let count = |default: u64| match params.as_slice() {
+        [] => Some(default),
+        [count] => Some(count.unwrap_or(default)),
+        _ => None,
+    };
+
+    let ansi = match (private, intermediates, final_byte) {
+        (false, "", 'H') => match params.as_slice() {
+            [] => Ansi::CursorPosition(0, 0),
+            [y] => Ansi::CursorPosition(0, y.unwrap_or(1).saturating_sub(1)),
+            [y, x] => Ansi::CursorPosition(
+                x.unwrap_or(1).saturating_sub(1),
+                y.unwrap_or(1).saturating_sub(1),
+            ),
+            _ => return Err(unrecognized()),
+        },
+        (false, " ", 'q') => match count(0).ok_or_else(unrecognized)? {
+            0 => Ansi::CursorStyle(CursorStyle::HollowBlock),
+            2 => Ansi::CursorStyle(CursorStyle::Block),
+            4 => Ansi::CursorStyle(CursorStyle::Underline),
+            5 => Ansi::CursorStyle(CursorStyle::Bar),
+            _ => return Err(unrecognized()),
+        },
+        (true, "", 'h' | 'l') => {
+            let enable = final_byte == 'h';
+            match params.as_slice() {
+                [Some(25)] if enable => Ansi::CursorVisibility(CursorVisibility::Visible),
+                [Some(25)] => Ansi::CursorVisibility(CursorVisibility::Invisible),
+                [Some(2026)] if enable => Ansi::BeginSynchronizedUpdate,
+                [Some(2026)] => Ansi::EndSynchronizedUpdate,
+                [Some(1049)] => Ansi::AlternateScreen(enable),
+                [Some(2004)] => Ansi::BracketedPaste(enable),
+                [Some(1000), Some(1006)] => Ansi::MouseReporting(enable),
+                _ => return Err(unrecognized()),
+            }
+        }
+        (false, "", 'A') => Ansi::CursorUp(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'B') => Ansi::CursorDown(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'C') => Ansi::CursorRight(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'D') => Ansi::CursorLeft(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'E') => Ansi::CursorNextLine(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'F') => Ansi::CursorPreviousLine(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'G') => {
+            Ansi::CursorHorizontalAbsolute(count(1).ok_or_else(unrecognized)?.saturating_sub(1))
+        }
+        (false, "", 's') if params.is_empty() => Ansi::SaveCursorPosition,
+        (false, "", 'u') if params.is_empty() => Ansi::RestoreCursorPosition,
+        (false, "", 'J') => match count(0).ok_or_else(unrecognized)? {
+            0 => Ansi::EraseInDisplay(DisplayEraseMode::FromCursorToEnd),
+            1 => Ansi::EraseInDisplay(DisplayEraseMode::FromCursorToStart),
+            2 => Ansi::EraseInDisplay(DisplayEraseMode::All),
+            3 => Ansi::EraseInDisplay(DisplayEraseMode::ScrollbackBuffer),
+            _ => return Err(unrecognized()),
+        },
+        (false, "", 'K') => match count(0).ok_or_else(unrecognized)? {
+            0 => Ansi::EraseInLine(LineEraseMode::FromCursorToEnd),
+            1 => Ansi::EraseInLine(LineEraseMode::FromCursorToStart),
+            2 => Ansi::EraseInLine(LineEraseMode::All),
+            _ => return Err(unrecognized()),
+        },
+        (false, "", 'S') => Ansi::ScrollUp(count(1).ok_or_else(unrecognized)?),
+        (false, "", 'T') => Ansi::ScrollDown(count(1).ok_or_else(unrecognized)?),
+        (false, "", 't') => match params.as_slice() {
+            [Some(8), Some(height), Some(width)] => Ansi::TerminalSize(*width, *height),
+            _ => return Err(unrecognized()),
+        },
+        _ => return Err(unrecognized()),
+    };
+
+    Ok((ansi, rest))
+}
+
+/// Parse an Operating System Command sequence, ie. `ESC ] ...`, terminated by
+/// either BEL or ST.
+fn parse_osc(input: &str) -> Result<(Ansi, &str), AnsiParseError> {
+    let body = &input[2..];
+    let (end, terminator_len) = match (body.find('\x07'), body.find("\x1b\\")) {
+        (Some(bel), Some(st)) if st < bel => (st, 2),
+        (Some(bel), _) => (bel, 1),
+        (None, Some(st)) => (st, 2),
+        (None, None) => return Err(AnsiParseError::Incomplete),
+    };
+    let sequence = &input[..2 + end + terminator_len];
+    let rest = &input[2 + end + terminator_len..];
+
+    match body[..end].split_once(';') {
+        Some(("0", title)) => Ok((Ansi::TerminalTitle(title.to_string()), rest)),
+        _ => Err(AnsiParseError::Unrecognized(sequence.to_string())),
+    }
+}
+
+/// Parse `;`-separated numeric parameters. Empty parameters are `None`.
+fn parse_params(params: &str) -> Option<Vec<Option<u64>>> {
+    if params.is_empty() {
+        return Some(vec![]);
+    }
+
+    params
+        .split(';')
+        .map(|param| {
+            if param.is_empty() {
+                Some(None)
+            } else {
+                param.parse().ok().map(Some)
+            }
+        })
+        .collect()
+}
+
+/// Parse the parameters of an SGR sequence, including `4:n` underline styles.
+fn parse_sgr(params: &str) -> Option<Vec<SgrParameter>> {
+    if params.is_empty() {
+        return Some(vec![SgrParameter::Reset]);
+    }
+
+    let mut out = vec![];
+    let mut numeric = vec![];
+    for param in params.split(';') {
+        if let Some(subparams) = param.split_once(':') {
+            let parameter = match subparams {
+                ("4", "0") => SgrParameter::NotUnderlined,
+                ("4", "1") => SgrParameter::Underline,
+                ("4", "2") => SgrParameter::DoubleUnderline,
+                ("4", "3") => SgrParameter::CurlyUnderline,
+                ("4", "4") => SgrParameter::DottedUnderline,
+                ("4", "5") => SgrParameter::DashedUnderline,
+                _ => return None,
+            };
+            if !numeric.is_empty() {
+                out.extend(SgrParameter::parse_params(&numeric));
+                numeric.clear();
+            }
+            out.push(parameter);
+        } else if param.is_empty() {
+            numeric.push(0);
+        } else {
+            numeric.push(param.parse().ok()?);
+        }
+    }
+    if !numeric.is_empty() {
+        out.extend(SgrParameter::parse_params(&numeric));
+    }
+
+    Some(out)
+}
+
+impl SgrParameter {
+    /// Reconstruct SGR parameters from the numeric parameters between `ESC [`
+    /// and `m`, handling the multi-number colour forms (ex. `38;2;r;g;b` and
+    /// `38;5;n`). An empty parameter list is a [`SgrParameter::Reset`].
+    ///
+    /// Nothing is dropped: unknown codes, and the parts of any malformed
+    /// colour forms, are preserved as [`SgrParameter::Unknown`].
+    pub fn parse_params(params: &[u16]) -> Vec<SgrParameter> {
+        if params.is_empty() {
+            return vec![SgrParameter::Reset];
+        }
+
+        let mut out = vec![];
+        let mut i = 0;
+        while i < params.len() {
+            let param = params[i];
+            i += 1;
+            let parameter = match param {
+                0 => SgrParameter::Reset,
+                1 => SgrParameter::Bold,
+                2 => SgrParameter::Faint,
+                3 => SgrParameter::Italic,
+                4 => SgrParameter::Underline,
+                5 => SgrParameter::Blink,
+                6 => SgrParameter::RapidBlink,
+                7 => SgrParameter::ReverseVideo,
+                8 => SgrParameter::Conceal,
+                9 => SgrParameter::CrossedOut,
+                10 => SgrParameter::PrimaryFont,
+                11..=19 => SgrParameter::AlternativeFont(param as u64 - 10),
+                20 => SgrParameter::Fraktur,
+                21 => SgrParameter::DoubleUnderline,
+                22 => SgrParameter::NormalIntensity,
+                23 => SgrParameter::NotItalicOrBlackletter,
+                24 => SgrParameter::NotUnderlined,
+                25 => SgrParameter::SteadyCursor,
+                26 => SgrParameter::ProportionalSpacing,
+                27 => SgrParameter::NotReversed,
+                28 => SgrParameter::Reveal,
+                29 => SgrParameter::NotCrossedOut,
+                30..=37 => SgrParameter::ForegroundColour(Self::colour(param - 30)),
+                40..=47 => SgrParameter::BackgroundColour(Self::colour(param - 40)),
+                90..=97 => SgrParameter::ForegroundColour(Self::colour(param - 90 + 8)),
+                100..=107 => SgrParameter::BackgroundColour(Self::colour(param - 100 + 8)),
+                38 | 48 | 58 => {
+                    let (parameter, consumed) = Self::parse_extended_colour(param, &params[i..]);
+                    i += consumed;
+                    match parameter {
+                        Some(parameter) => parameter,
+                        None => {
+                            out.push(SgrParameter::Unknown(param));
+                            out.extend(
+                                params[i - consumed..i]
+                                    .iter()
+                                    .map(|param| SgrParameter::Unknown(*param)),
+                            );
+                            continue;
+                        }
+                    }
+                }
+                39 => SgrParameter::DefaultForegroundColour,
+                49 => SgrParameter::DefaultBackgroundColour,
+                50 => SgrParameter::DisableProportionalSpacing,
+                51 => SgrParameter::Framed,
+                52 => SgrParameter::Encircled,
+                53 => SgrParameter::Overlined,
+                54 => SgrParameter::NotFramedOrEncircled,
+                55 => SgrParameter::NotOverlined,
+                59 => SgrParameter::DefaultUnderlineColour,
+                60 => SgrParameter::IdeogramUnderlineOrRightSideLine,
+                61 => SgrParameter::IdeogramDoubleUnderlineOrDoubleLineOnTheRightSide,
+                62 => SgrParameter::IdeogramOverlineOrLeftSideLine,
+                63 => SgrParameter::IdeogramDoubleOverlineOrDoubleLineOnTheLeftSide,
+                64 => SgrParameter::IdeogramStressMarking,
+                65 => SgrParameter::IdeogramAttributesOff,
+                73 => SgrParameter::Superscript,
+                74 => SgrParameter::Subscript,
+                75 => SgrParameter::NotSuperscriptOrSubscript,
+                _ => SgrParameter::Unknown(param),
+            };
+            out.push(parameter);
+        }
+
+        out
+    }
+
+    /// The palette colour at `index`, which must be in `0..16`.
+    fn colour(index: u16) -> Colour {
+        Colour::from_index(index as u64).expect("palette index out of range")
+    }
+
+    /// Parse the rest of a `38`/`48`/`58` colour form from `params`. Returns
+    /// the parsed parameter, if valid, and how many parameters were consumed.
+    fn parse_extended_colour(code: u16, params: &[u16]) -> (Option<SgrParameter>, usize) {
+        match params {
+            [5, index, ..] => {
+                let Ok(index) = u8::try_from(*index) else {
+                    return (None, 2);
+                };
+                let parameter = match (code, Colour::from_index(index as u64)) {
+                    (38, _) => SgrParameter::IndexedForegroundColour(index),
+                    (48, _) => SgrParameter::IndexedBackgroundColour(index),
+                    (_, Some(colour)) => SgrParameter::UnderlineColour(colour),
+                    (_, None) => SgrParameter::IndexedUnderlineColour(index),
+                };
+                (Some(parameter), 2)
+            }
+            [2, r, g, b, ..] => {
+                if *r > 0xFF || *g > 0xFF || *b > 0xFF {
+                    return (None, 4);
+                }
+                let hex = ((*r as u32) << 16) | ((*g as u32) << 8) | *b as u32;
+                let parameter = match code {
+                    38 => SgrParameter::HexForegroundColour(hex),
+                    48 => SgrParameter::HexBackgroundColour(hex),
+                    _ => SgrParameter::HexUnderlineColour(hex),
+                };
+                (Some(parameter), 4)
+            }
+            // Malformed: consume whatever's left of the colour form so that
+            // its parts aren't misread as other parameters.
+            [5, ..] | [2, ..] => (None, params.len()),
+            [_, ..] => (None, 1),
+            [] => (None, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AnsiParseError;
+    use crate::{
+        Ansi, Colour, CursorStyle, CursorVisibility, DisplayEraseMode, LineEraseMode, SgrParameter,
+    };
+
+    #[test]
+    fn test_round_trips() {
+        let sequences = vec![
+            Ansi::CursorPosition(0, 0),
+            Ansi::CursorPosition(12, 34),
+            Ansi::CursorStyle(CursorStyle::Block),
+            Ansi::CursorStyle(CursorStyle::Bar),
+            Ansi::CursorStyle(CursorStyle::HollowBlock),
+            Ansi::CursorStyle(CursorStyle::Underline),
+            Ansi::CursorVisibility(CursorVisibility::Visible),
+            Ansi::CursorVisibility(CursorVisibility::Invisible),
+            Ansi::CursorUp(3),
+            Ansi::CursorDown(3),
+            Ansi::CursorLeft(3),
+            Ansi::CursorRight(3),
+            Ansi::CursorNextLine(3),
+            Ansi::CursorPreviousLine(3),
+            Ansi::CursorHorizontalAbsolute(7),
+            Ansi::SaveCursorPosition,
+            Ansi::RestoreCursorPosition,
+            Ansi::EraseInDisplay(DisplayEraseMode::FromCursorToEnd),
+            Ansi::EraseInDisplay(DisplayEraseMode::FromCursorToStart),
+            Ansi::EraseInDisplay(DisplayEraseMode::All),
+            Ansi::EraseInDisplay(DisplayEraseMode::ScrollbackBuffer),
+            Ansi::EraseInLine(LineEraseMode::FromCursorToEnd),
+            Ansi::EraseInLine(LineEraseMode::FromCursorToStart),
+            Ansi::EraseInLine(LineEraseMode::All),
+            Ansi::ScrollUp(2),
+            Ansi::ScrollDown(2),
+            Ansi::TerminalSize(80, 24),
+            Ansi::TerminalTitle("henol world".into()),
+            Ansi::BeginSynchronizedUpdate,
+            Ansi::EndSynchronizedUpdate,
+            Ansi::AlternateScreen(true),
+            Ansi::AlternateScreen(false),
+            Ansi::BracketedPaste(true),
+            Ansi::BracketedPaste(false),
+            Ansi::MouseReporting(true),
+            Ansi::MouseReporting(false),
+            Ansi::Sgr(vec![SgrParameter::Reset]),
+            Ansi::Sgr(vec![SgrParameter::DottedUnderline]),
+            Ansi::Sgr(vec![SgrParameter::DashedUnderline, SgrParameter::Bold]),
+            Ansi::Sgr(vec![
+                SgrParameter::Bold,
+                SgrParameter::CurlyUnderline,
+                SgrParameter::AlternativeFont(3),
+                SgrParameter::ForegroundColour(Colour::Red),
+                SgrParameter::BackgroundColour(Colour::BrightCyan),
+                SgrParameter::IndexedForegroundColour(3),
+                SgrParameter::UnderlineColour(Colour::Green),
+                SgrParameter::HexBackgroundColour(0xDB325C),
+                SgrParameter::HexUnderlineColour(0x123456),
+                SgrParameter::IndexedBackgroundColour(200),
+                SgrParameter::NotSuperscriptOrSubscript,
+                SgrParameter::Unknown(99),
+            ]),
+        ];
+
+        for sequence in sequences {
+            let rendered = sequence.to_string();
+            assert_eq!(Ok((sequence, "")), Ansi::parse(&rendered), "{rendered:?}");
+        }
+    }
+
+    #[test]
+    fn test_returns_remaining_input() {
+        assert_eq!(
+            Ok((Ansi::CursorUp(1), "henol\x1b[2J")),
+            Ansi::parse("\x1b[1Ahenol\x1b[2J")
+        );
+        assert_eq!(Ok((Ansi::CursorUp(1), "")), Ansi::parse("\x1b[A"));
+        assert_eq!(
+            Ok((Ansi::Sgr(vec![SgrParameter::Reset]), "")),
+            Ansi::parse("\x1b[m")
+        );
+    }
+
+    #[test]
+    fn test_incomplete_sequences() {
+        for input in [
+            "",
+            "\x1b",
+            "\x1b[",
+            "\x1b[12",
+            "\x1b[38;2;1",
+            "\x1b]0;title",
+        ] {
+            assert_eq!(
+                Err(AnsiParseError::Incomplete),
+                Ansi::parse(input),
+                "{input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_unrecognized_sequences() {
+        assert_eq!(
+            Err(AnsiParseError::Unrecognized("\x1b[5z".into())),
+            Ansi::parse("\x1b[5zhenol")
+        );
+        assert_eq!(
+            Err(AnsiParseError::Unrecognized("\x1b[99999m".into())),
+            Ansi::parse("\x1b[99999m")
+        );
+        assert_eq!(
+            Err(AnsiParseError::Unrecognized("\x1bP".into())),
+            Ansi::parse("\x1bP")
+        );
+        assert_eq!(
+            Err(AnsiParseError::NotAnEscapeSequence),
+            Ansi::parse("henol")
+        );
+    }
+
+    #[test]
+    fn test_sgr_parse_params() {
+        assert_eq!(vec![SgrParameter::Reset], SgrParameter::parse_params(&[]));
+        assert_eq!(
+            vec![SgrParameter::HexForegroundColour(0xDB325C)],
+            SgrParameter::parse_params(&[38, 2, 219, 50, 92])
+        );
+        assert_eq!(
+            vec![
+                SgrParameter::IndexedForegroundColour(200),
+                SgrParameter::IndexedBackgroundColour(1),
+                SgrParameter::HexUnderlineColour(0x010203),
+            ],
+            SgrParameter::parse_params(&[38, 5, 200, 48, 5, 1, 58, 2, 1, 2, 3])
+        );
+        assert_eq!(
+            vec![
+                SgrParameter::Bold,
+                SgrParameter::BackgroundColour(Colour::Green),
+                SgrParameter::Unknown(80)
+            ],
+            SgrParameter::parse_params(&[1, 42, 80])
+        );
+    }
+
+    #[test]
+    fn test_sgr_parse_params_preserves_malformed_colours() {
+        assert_eq!(
+            vec![
+                SgrParameter::Unknown(38),
+                SgrParameter::Unknown(2),
+                SgrParameter::Unknown(300),
+                SgrParameter::Unknown(0),
+                SgrParameter::Unknown(0),
+                SgrParameter::Bold,
+            ],
+            SgrParameter::parse_params(&[38, 2, 300, 0, 0, 1])
+        );
+        assert_eq!(
+            vec![SgrParameter::Unknown(48), SgrParameter::Unknown(5)],
+            SgrParameter::parse_params(&[48, 5])
+        );
+        assert_eq!(
+            vec![
+                SgrParameter::Unknown(38),
+                SgrParameter::Unknown(7),
+                SgrParameter::Bold
+            ],
+            SgrParameter::parse_params(&[38, 7, 1])
+        );
+    }
+}
@@ -1,29 +1,121 @@
 use std::os::fd::{BorrowedFd, RawFd};
 use std::os::unix::prelude::AsRawFd;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_recursion::async_recursion;
 use eyre::{eyre, Result};
+use nix::errno::Errno;
 use nix::poll::{poll, PollFd, PollFlags};
 use nix::sys::select::FdSet;
 use nix::sys::signal::Signal;
 use nix::sys::signalfd::SigSet;
 use nix::sys::termios;
-use nix::sys::termios::InputFlags;
+use nix::sys::termios::{InputFlags, LocalFlags};
 use nix::sys::time::TimeSpec;
+use tokio::io::unix::AsyncFd;
+use tokio::io::Interest;
+
+/// How long to wait for each byte of input by default. See
+/// [`ConsoleState::set_read_timeout`].
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_millis(50);
+
+/// How long to wait for a `\n` after a `\r`, when coalescing them. Pasted
+/// line endings arrive together, so this only needs to be long enough to
+/// cover the two bytes being delivered separately.
+const CRLF_WINDOW: Duration = Duration::from_millis(5);
 
 #[derive(Debug, Clone)] // TODO: Are clone bounds safe here?
-pub struct ConsoleState<'a>(#[doc(hidden)] BorrowedFd<'a>);
+pub struct ConsoleState<'a> {
+    fd: BorrowedFd<'a>,
+    /// How long to wait for each byte of input.
+    read_timeout: Duration,
+    /// Whether `\r\n` is read as a single Return.
+    coalesce_crlf: bool,
+    /// A byte that was read ahead and not used, to be read again next.
+    pushback: Arc<Mutex<Option<u8>>>,
+}
+
+impl<'a> ConsoleState<'a> {
+    /// How long to wait for each byte of input. Multi-byte keypresses, ex.
+    /// escape sequences, are only assembled if every byte arrives within this
+    /// window of the last.
+    pub fn read_timeout(&self) -> Duration {
+        self.read_timeout
+    }
+
+    /// Set how long to wait for each byte of input. High-latency terminals
+    /// may need longer than [`DEFAULT_READ_TIMEOUT`] to reliably assemble
+    /// escape sequences; fast local ones can get away with less.
+    pub fn set_read_timeout(&mut self, timeout: Duration) {
+        self.read_timeout = timeout;
+    }
+
+    /// Whether a `\r` immediately followed by a `\n` is read as one
+    /// [`Keypress::Return`], rather than two. See
+    /// [`ConsoleState::set_coalesce_crlf`].
+    pub fn coalesce_crlf(&self) -> bool {
+        self.coalesce_crlf
+    }
+
+    /// Set whether a `\r` immediately followed by a `\n` is read as one
+    /// [`Keypress::Return`]. In raw mode the terminal doesn't translate
+    /// `\r` into `\n`, so a pasted Windows line ending arrives as both
+    /// bytes, and would otherwise be read as two Returns. A lone `\r` or
+    /// `\n` is always one Return. On by default.
+    pub fn set_coalesce_crlf(&mut self, coalesce: bool) {
+        self.coalesce_crlf = coalesce;
+    }
+}
 
 pub async fn init(fd: Option<RawFd>) -> Result<ConsoleState<'static>> {
     // Safety: It's impossible for these to not be valid fds
-    Ok(ConsoleState(unsafe {
-        BorrowedFd::borrow_raw(if let Some(fd) = fd {
-            fd
-        } else {
-            std::io::stderr().as_raw_fd()
+    Ok(ConsoleState {
+        fd: unsafe {
+            BorrowedFd::borrow_raw(if let Some(fd) = fd {
+                fd
+            } else {
+                std::io::stderr().as_raw_fd()
+            })
+        },
+        read_timeout: DEFAULT_READ_TIMEOUT,
+        coalesce_crlf: true,
+        pushback: Arc::new(Mutex::new(None)),
+    })
+}
+
+/// The terminal's attributes, ex. whether it's in raw mode, as they were when
+/// saved, so that they can be put back later, ex. after a panic.
+#[derive(Debug, Clone)]
+pub struct SavedAttributes {
+    fd: BorrowedFd<'static>,
+    termios: termios::Termios,
+}
+
+impl SavedAttributes {
+    /// Save the attributes of the terminal on `fd`, or stderr if not given,
+    /// as [`init`] does.
+    pub fn save(fd: Option<RawFd>) -> Result<Self> {
+        // Safety: It's impossible for these to not be valid fds
+        let fd = unsafe {
+            BorrowedFd::borrow_raw(if let Some(fd) = fd {
+                fd
+            } else {
+                std::io::stderr().as_raw_fd()
+            })
+        };
+
+        Ok(Self {
+            fd,
+            termios: termios::tcgetattr(fd)?,
         })
-    }))
+    }
+
+    /// Put the terminal's attributes back as they were when saved.
+    pub fn restore(&self) -> Result<()> {
+        termios::tcsetattr(self.fd, termios::SetArg::TCSADRAIN, &self.termios)?;
+        Ok(())
+    }
 }
 
 /// - Check if stdin is a terminal (libc::isatty == 1)
@@ -41,27 +133,35 @@ pub async fn init(fd: Option<RawFd>) -> Result<ConsoleState<'static>> {
 ///         - H => home
 ///         - F => end
 ///         - Z => shift-tab
-///         - _ =>
-///           - Match next byte
-///             - ~ =>
-///               - Match next byte
-///                 - 1 => home
-///                 - 2 => insert
-///                 - 3 => delete
-///                 - 4 => end
-///                 - 5 => page up
-///                 - 6 => page down
-///                 - 7 => home
-///                 - 8 => end
-///                 - Else, the escape sequence was unknown
-///             - Else, the escape sequence was unknown
+///         - I => terminal gained focus
+///         - O => terminal lost focus
+///         - < => SGR mouse event, read up to the closing M or m
+///         - digit => read `;`-separated parameters up to the final byte
+///           - 200~ => bracketed paste, read up to the closing \x1b[201~
+///           - n~ or n;mod~ =>
+///             - 1 => home
+///             - 2 => insert
+///             - 3 => delete
+///             - 4 => end
+///             - 5 => page up
+///             - 6 => page down
+///             - 7 => home
+///             - 8 => end
+///           - 1;mod and A-D/H/F => the arrow/home/end key above
+///           - mod - 1 is a bitmask of shift (1), alt (2), and ctrl (4)
+///           - Else, the escape sequence was unknown
+///     - Else, if next byte is printable, alt + that character
 ///     - Else, if next byte is not [, bail out on unknown control sequence
 ///     - Else, if there was no next byte, input was <ESC>
 ///   - Else, if byte & 224u8 == 192u8, Unicode 2-byte
 ///   - Else, if byte & 240u8 == 224u8, Unicode 3-byte
 ///   - Else, if byte & 248u8 == 240u8, Unicode 4-byte
+///     - Continuation bytes are waited for, and if they never arrive, the
+///       bytes read so far are an unknown sequence
 ///   - Else:
 ///     - If byte == \r || byte == \n, <RETURN>
+///       - If byte == \r and the next byte is \n, read that too, unless
+///         CRLF coalescing is disabled
 ///     - If byte == \t, <TAB>
 ///     - If byte == \x7f, <BACKSPACE>
 ///     - If byte == \x1b, <ESC>
@@ -73,8 +173,78 @@ pub async fn init(fd: Option<RawFd>) -> Result<ConsoleState<'static>> {
 ///     - If stdin is a terminal, return None
 /// - Disable TCSADRAIN
 pub async fn next_keypress(state: &ConsoleState<'static>) -> Result<Option<Keypress>> {
-    let original_termios = termios::tcgetattr(state.0)?;
-    let mut termios = original_termios.clone();
+    next_keypress_with_timeout(state, state.read_timeout()).await
+}
+
+/// [`next_keypress`], but waiting `timeout` for each byte instead of the
+/// state's [`ConsoleState::read_timeout`].
+pub async fn next_keypress_with_timeout(
+    state: &ConsoleState<'static>,
+    timeout: Duration,
+) -> Result<Option<Keypress>> {
+    let mut state = state.clone();
+    state.read_timeout = timeout;
+    let _raw = RawMode::enter(state.fd)?;
+
+    // Wait for the first byte without blocking the runtime, so that a read
+    // that's waiting for input can be cancelled. Fds that can't be waited on
+    // this way, ex. regular files, are waited on by the read itself.
+    if state.pushback.lock().unwrap().is_none() {
+        if let Ok(fd) = AsyncFd::with_interest(state.fd, Interest::READABLE) {
+            match tokio::time::timeout(timeout, fd.readable()).await {
+                Ok(ready) => drop(ready?),
+                Err(_) => return Ok(None),
+            }
+        }
+    }
+
+    read_next_key(&state).await
+}
+
+/// Input switched to raw mode for as long as this is alive. The previous
+/// attributes are put back on drop, so that a read that's cancelled or panics
+/// part-way through doesn't leave the terminal in raw mode.
+///
+/// Only input is affected, so the attributes are switched immediately rather
+/// than waiting for output to drain.
+struct RawMode(SavedAttributes);
+
+impl RawMode {
+    fn enter(fd: BorrowedFd<'static>) -> Result<Self> {
+        let saved = SavedAttributes {
+            fd,
+            termios: termios::tcgetattr(fd)?,
+        };
+        termios::tcsetattr(
+            fd,
+            termios::SetArg::TCSANOW,
+            &raw_input_termios(&saved.termios),
+        )?;
+
+        Ok(Self(saved))
+    }
+
+    /// [`RawMode::enter`], unless input is already raw, ex. because the
+    /// caller is holding it raw, in which case the attributes are left alone.
+    fn enter_if_needed(fd: BorrowedFd<'static>) -> Result<Option<Self>> {
+        let termios = termios::tcgetattr(fd)?;
+        if raw_input_termios(&termios).local_flags == termios.local_flags {
+            return Ok(None);
+        }
+
+        Self::enter(fd).map(Some)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = termios::tcsetattr(self.0.fd, termios::SetArg::TCSANOW, &self.0.termios);
+    }
+}
+
+/// `termios` with input switched to raw mode.
+fn raw_input_termios(termios: &termios::Termios) -> termios::Termios {
+    let mut termios = termios.clone();
 
     // Note: This is ONLY what termios::cfmakeraw does to input
     termios.input_flags &= !(InputFlags::IGNBRK
@@ -90,20 +260,148 @@ pub async fn next_keypress(state: &ConsoleState<'static>) -> Result<Option<Keypr
         | termios::LocalFlags::ICANON
         | termios::LocalFlags::ISIG
         | termios::LocalFlags::IEXTEN);
-    termios::tcsetattr(state.0, termios::SetArg::TCSADRAIN, &termios)?;
 
-    let out = read_next_key(&state.0).await;
+    termios
+}
+
+/// Whether or not there's input waiting to be read right now, without
+/// consuming any of it or waiting for more.
+///
+/// Wakeups may be spurious: a `true` doesn't guarantee that
+/// [`next_keypress`] will return a keypress, ex. if the waiting input is
+/// consumed elsewhere first.
+pub async fn has_input(state: &ConsoleState<'static>) -> Result<bool> {
+    if state.pushback.lock().unwrap().is_some() {
+        return Ok(true);
+    }
+
+    // In canonical mode, input isn't readable until a whole line is, so poll
+    // the way `next_keypress` would read.
+    let _raw = RawMode::enter_if_needed(state.fd)?;
+
+    let mut fds = [PollFd::new(&state.fd, PollFlags::POLLIN)];
+    match poll(&mut fds, 0) {
+        Ok(0) => Ok(false),
+        Ok(_) => Ok(fds[0]
+            .revents()
+            .is_some_and(|events| events.contains(PollFlags::POLLIN))),
+        Err(Errno::EINTR) => Err(ConsoleError::Interrupted.into()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Print `prompt` and read a single line of input using the terminal's normal
+/// line editing, ie. canonical mode with echo. The terminal's previous mode is
+/// restored afterwards. Useful for simple prompts that don't need a full UI.
+///
+/// Returns `None` if input ends (ex. ^D on an empty line) before anything is
+/// read. The trailing line ending is not included in the returned line.
+pub async fn read_line(state: &ConsoleState<'static>, prompt: &str) -> Result<Option<String>> {
+    let fd = state.fd;
+    let prompt = prompt.to_string();
+    tokio::task::spawn_blocking(move || read_line_blocking(fd, &prompt)).await?
+}
+
+fn read_line_blocking(fd: BorrowedFd<'static>, prompt: &str) -> Result<Option<String>> {
+    // If this isn't a terminal, there's no line discipline to configure, and
+    // we just read the line as-is.
+    let original_termios = termios::tcgetattr(fd).ok();
+    if let Some(original_termios) = &original_termios {
+        let mut termios = original_termios.clone();
+        termios.input_flags |= InputFlags::ICRNL;
+        termios.local_flags |=
+            LocalFlags::ICANON | LocalFlags::ECHO | LocalFlags::ECHOE | LocalFlags::ECHOK;
+        termios::tcsetattr(fd, termios::SetArg::TCSADRAIN, &termios)?;
+    }
+
+    let out = write_all(&fd, prompt.as_bytes()).and_then(|_| read_line_bytes(&fd));
 
-    termios::tcsetattr(state.0, termios::SetArg::TCSADRAIN, &original_termios)?;
+    if let Some(original_termios) = &original_termios {
+        termios::tcsetattr(fd, termios::SetArg::TCSADRAIN, original_termios)?;
+    }
 
     out
 }
 
+/// Enable mouse reporting. Presses, releases, drags, and the wheel are
+/// reported in the SGR format, and show up as [`Keypress::Mouse`].
+pub async fn enable_mouse(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?1000h\x1b[?1002h\x1b[?1006h")
+}
+
+/// Disable mouse reporting enabled by [`enable_mouse`].
+pub async fn disable_mouse(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?1006l\x1b[?1002l\x1b[?1000l")
+}
+
+/// Enable bracketed paste. Pasted text is reported as a single
+/// [`Keypress::Paste`] instead of one keypress per character.
+pub async fn enable_bracketed_paste(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?2004h")
+}
+
+/// Disable bracketed paste enabled by [`enable_bracketed_paste`].
+pub async fn disable_bracketed_paste(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?2004l")
+}
+
+/// Enable focus reporting. The terminal gaining and losing focus is reported
+/// as [`Keypress::FocusGained`] and [`Keypress::FocusLost`].
+pub async fn enable_focus_reporting(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?1004h")
+}
+
+/// Disable focus reporting enabled by [`enable_focus_reporting`].
+pub async fn disable_focus_reporting(state: &ConsoleState<'static>) -> Result<()> {
+    write_all(&state.fd, b"\x1b[?1004l")
+}
+
+fn write_all(fd: &BorrowedFd<'_>, mut bytes: &[u8]) -> Result<()> {
+    while !bytes.is_empty() {
+        match nix::unistd::write(fd.as_raw_fd(), bytes) {
+            Ok(written) => bytes = &bytes[written..],
+            Err(Errno::EINTR) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    Ok(())
+}
+
+fn read_line_bytes(fd: &BorrowedFd<'_>) -> Result<Option<String>> {
+    let mut line = vec![];
+    let mut buf = [0u8; 1];
+    loop {
+        match nix::unistd::read(fd.as_raw_fd(), &mut buf) {
+            Ok(0) => {
+                if line.is_empty() {
+                    return Ok(None);
+                }
+                break;
+            }
+            Ok(_) => {
+                if buf[0] == b'\n' {
+                    break;
+                }
+                line.push(buf[0]);
+            }
+            Err(Errno::EINTR) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+
+    if line.last() == Some(&b'\r') {
+        line.pop();
+    }
+
+    Ok(Some(String::from_utf8(line)?))
+}
+
 #[async_recursion]
-async fn read_next_key(fd: &BorrowedFd<'_>) -> Result<Option<Keypress>> {
-    match read_char(fd)? {
-        Some('\x1b') => match read_char(fd)? {
-            Some('[') => match read_char(fd)? {
+async fn read_next_key(state: &ConsoleState<'_>) -> Result<Option<Keypress>> {
+    match read_char(state)? {
+        Some('\x1b') => match read_char(state)? {
+            Some('[') => match read_char(state)? {
                 Some('A') => Ok(Some(Keypress::Up)),
                 Some('B') => Ok(Some(Keypress::Down)),
                 Some('C') => Ok(Some(Keypress::Right)),
@@ -111,34 +409,26 @@ async fn read_next_key(fd: &BorrowedFd<'_>) -> Result<Option<Keypress>> {
                 Some('H') => Ok(Some(Keypress::Home)),
                 Some('F') => Ok(Some(Keypress::End)),
                 Some('Z') => Ok(Some(Keypress::ShiftTab)),
-                Some(byte3) => match read_char(fd)? {
-                    Some('~') => match read_char(fd)? {
-                        Some('1') => Ok(Some(Keypress::Home)),
-                        Some('2') => Ok(Some(Keypress::Insert)),
-                        Some('3') => Ok(Some(Keypress::Delete)),
-                        Some('4') => Ok(Some(Keypress::End)),
-                        Some('5') => Ok(Some(Keypress::PageUp)),
-                        Some('6') => Ok(Some(Keypress::PageDown)),
-                        Some('7') => Ok(Some(Keypress::Home)),
-                        Some('8') => Ok(Some(Keypress::End)),
-                        Some(byte5) => Ok(Some(Keypress::UnknownSequence(vec![
-                            '\x1b', '[', byte3, '~', byte5,
-                        ]))),
-                        None => Ok(Some(Keypress::UnknownSequence(vec![
-                            '\x1b', '[', byte3, '~',
-                        ]))),
-                    },
-                    Some(byte4) => Ok(Some(Keypress::UnknownSequence(vec![
-                        '\x1b', '[', byte3, byte4,
-                    ]))),
-                    None => Ok(Some(Keypress::UnknownSequence(vec!['\x1b', '[', byte3]))),
-                },
+                Some('I') => Ok(Some(Keypress::FocusGained)),
+                Some('O') => Ok(Some(Keypress::FocusLost)),
+                Some('<') => read_mouse_event(state),
+                Some(byte3 @ '0'..='9') => read_csi_parameters(state, byte3),
+                Some(byte3) => Ok(Some(Keypress::UnknownSequence(vec!['\x1b', '[', byte3]))),
                 None => Ok(Some(Keypress::Escape)),
             },
+            Some(byte) if !byte.is_ascii_control() && byte.is_ascii() => Ok(Some(
+                Keypress::Modified(Modifiers::ALT, Box::new(Keypress::Char(byte))),
+            )),
             Some(byte) => Ok(Some(Keypress::UnknownSequence(vec!['\x1b', byte]))),
             None => Ok(Some(Keypress::Escape)),
         },
-        Some('\r') | Some('\n') => Ok(Some(Keypress::Return)),
+        Some('\r') => {
+            if state.coalesce_crlf {
+                skip_newline(state)?;
+            }
+            Ok(Some(Keypress::Return))
+        }
+        Some('\n') => Ok(Some(Keypress::Return)),
         Some('\t') => Ok(Some(Keypress::Tab)),
         Some('\x7f') => Ok(Some(Keypress::Backspace)),
         Some('\x01') => Ok(Some(Keypress::Home)),
@@ -147,28 +437,25 @@ async fn read_next_key(fd: &BorrowedFd<'_>) -> Result<Option<Keypress>> {
         Some('\x05') => Ok(Some(Keypress::End)),
         Some('\x08') => Ok(Some(Keypress::Backspace)),
         Some(byte) => {
-            if (byte as u8) & 224u8 == 192u8 {
-                let bytes = vec![byte as u8, read_byte(fd)?.unwrap()];
-                Ok(Some(Keypress::Char(char_from_utf8(&bytes)?)))
+            let len = if (byte as u8) & 224u8 == 192u8 {
+                2
             } else if (byte as u8) & 240u8 == 224u8 {
-                let bytes: Vec<u8> =
-                    vec![byte as u8, read_byte(fd)?.unwrap(), read_byte(fd)?.unwrap()];
-                Ok(Some(Keypress::Char(char_from_utf8(&bytes)?)))
+                3
             } else if (byte as u8) & 248u8 == 240u8 {
-                let bytes: Vec<u8> = vec![
-                    byte as u8,
-                    read_byte(fd)?.unwrap(),
-                    read_byte(fd)?.unwrap(),
-                    read_byte(fd)?.unwrap(),
-                ];
-                Ok(Some(Keypress::Char(char_from_utf8(&bytes)?)))
+                4
             } else {
+                1
+            };
+
+            if len == 1 {
                 Ok(Some(Keypress::Char(byte)))
+            } else {
+                read_utf8_char(state, byte as u8, len)
             }
         }
         None => {
             // there is no subsequent byte ready to be read, block and wait for input
-            let pollfd = PollFd::new(&fd, PollFlags::POLLIN);
+            let pollfd = PollFd::new(&state.fd, PollFlags::POLLIN);
             let ret = poll(&mut [pollfd], 0)?;
 
             if ret < 0 {
@@ -186,39 +473,232 @@ async fn read_next_key(fd: &BorrowedFd<'_>) -> Result<Option<Keypress>> {
     }
 }
 
-fn read_byte(fd: &BorrowedFd<'_>) -> Result<Option<u8>> {
+/// Read the rest of a CSI sequence that starts with a numeric parameter,
+/// ie. `n~`, `n;mod~`, `1;mod<letter>`, or the start of a bracketed paste.
+/// `\x1b[` and the first digit have already been read.
+fn read_csi_parameters(state: &ConsoleState<'_>, first: char) -> Result<Option<Keypress>> {
+    let mut params = String::from(first);
+    let terminator = loop {
+        match read_char(state)? {
+            Some(c @ ('0'..='9' | ';')) => params.push(c),
+            terminator => break terminator,
+        }
+    };
+    let unknown = || {
+        Keypress::UnknownSequence(
+            "\x1b["
+                .chars()
+                .chain(params.chars())
+                .chain(terminator)
+                .collect(),
+        )
+    };
+
+    let Some(terminator) = terminator else {
+        return Ok(Some(unknown()));
+    };
+    if terminator == '~' && params == "200" {
+        return read_paste(state);
+    }
+
+    let (code, modifiers) = match params.split_once(';') {
+        Some((code, modifiers)) => match modifiers.parse().ok().and_then(Modifiers::from_xterm) {
+            Some(modifiers) => (code, modifiers),
+            None => return Ok(Some(unknown())),
+        },
+        None => (params.as_str(), Modifiers::default()),
+    };
+
+    let key = match (code, terminator) {
+        ("1" | "7", '~') => Keypress::Home,
+        ("2", '~') => Keypress::Insert,
+        ("3", '~') => Keypress::Delete,
+        ("4" | "8", '~') => Keypress::End,
+        ("5", '~') => Keypress::PageUp,
+        ("6", '~') => Keypress::PageDown,
+        ("1", 'A') => Keypress::Up,
+        ("1", 'B') => Keypress::Down,
+        ("1", 'C') => Keypress::Right,
+        ("1", 'D') => Keypress::Left,
+        ("1", 'H') => Keypress::Home,
+        ("1", 'F') => Keypress::End,
+        _ => return Ok(Some(unknown())),
+    };
+
+    Ok(Some(key.with_modifiers(modifiers)))
+}
+
+/// Read the rest of a bracketed paste. `\x1b[200~` has already been read.
+/// The body is read verbatim, escapes and newlines included, up to the
+/// closing `\x1b[201~`. If input ends first, whatever was read is returned.
+fn read_paste(state: &ConsoleState<'_>) -> Result<Option<Keypress>> {
+    const END: &[u8] = b"\x1b[201~";
+
+    let mut body = vec![];
+    while let Some(byte) = read_byte(state)? {
+        body.push(byte);
+        if body.ends_with(END) {
+            body.truncate(body.len() - END.len());
+            break;
+        }
+    }
+
+    Ok(Some(Keypress::Paste(
+        String::from_utf8_lossy(&body).into_owned(),
+    )))
+}
+
+/// Read the rest of an SGR mouse sequence, ie. `b;x;y` followed by `M` for a
+/// press or `m` for a release. The leading `\x1b[<` has already been read.
+fn read_mouse_event(state: &ConsoleState<'_>) -> Result<Option<Keypress>> {
+    let mut params = String::new();
+    loop {
+        match read_char(state)? {
+            Some(c @ ('M' | 'm')) => {
+                return Ok(Some(match parse_mouse_event(&params, c) {
+                    Some(event) => Keypress::Mouse(event),
+                    None => Keypress::UnknownSequence(
+                        "\x1b[<".chars().chain(params.chars()).chain([c]).collect(),
+                    ),
+                }));
+            }
+            Some(c @ ('0'..='9' | ';')) => params.push(c),
+            Some(c) => {
+                params.push(c);
+                return Ok(Some(Keypress::UnknownSequence(
+                    "\x1b[<".chars().chain(params.chars()).collect(),
+                )));
+            }
+            None => {
+                return Ok(Some(Keypress::UnknownSequence(
+                    "\x1b[<".chars().chain(params.chars()).collect(),
+                )))
+            }
+        }
+    }
+}
+
+fn parse_mouse_event(params: &str, terminator: char) -> Option<MouseEvent> {
+    let mut params = params.split(';').map(|param| param.parse::<u16>().ok());
+    let (button, x, y) = (params.next()??, params.next()??, params.next()??);
+    if params.next().is_some() || x == 0 || y == 0 {
+        return None;
+    }
+
+    // Bits 2-4 are the shift, meta, and control modifiers.
+    let button = button & !0b11100;
+    let kind = match (button, terminator) {
+        (64, _) => MouseEventKind::WheelUp,
+        (65, _) => MouseEventKind::WheelDown,
+        (66, _) => MouseEventKind::WheelLeft,
+        (67, _) => MouseEventKind::WheelRight,
+        // Motion with no buttons held.
+        (35, _) => MouseEventKind::Move,
+        (32..=34, _) => MouseEventKind::Drag(MouseButton::from_code(button - 32)?),
+        (_, 'M') => MouseEventKind::Press(MouseButton::from_code(button)?),
+        (_, _) => MouseEventKind::Release(MouseButton::from_code(button)?),
+    };
+
+    Some(MouseEvent {
+        kind,
+        x: x - 1,
+        y: y - 1,
+    })
+}
+
+/// How many times to wait for each continuation byte of a multibyte UTF-8
+/// character before giving up on it.
+const UTF8_CONTINUATION_RETRIES: usize = 5;
+
+/// Read the continuation bytes of a `len`-byte UTF-8 character whose leading
+/// byte has already been read. The bytes may trickle in, ex. when a read is
+/// split across a slow pipe, so each one is waited for a few times. If they
+/// never arrive, the bytes read so far are returned as an unknown sequence.
+fn read_utf8_char(state: &ConsoleState<'_>, first: u8, len: usize) -> Result<Option<Keypress>> {
+    let mut bytes = vec![first];
+    while bytes.len() < len {
+        let mut next = None;
+        for _ in 0..UTF8_CONTINUATION_RETRIES {
+            next = read_byte(state)?;
+            if next.is_some() {
+                break;
+            }
+        }
+
+        match next {
+            Some(byte) => bytes.push(byte),
+            None => {
+                return Ok(Some(Keypress::UnknownSequence(
+                    bytes.into_iter().map(|byte| byte as char).collect(),
+                )))
+            }
+        }
+    }
+
+    Ok(Some(Keypress::Char(char_from_utf8(&bytes)?)))
+}
+
+/// Read the `\n` of a `\r\n`, if it arrives within [`CRLF_WINDOW`]. Any
+/// other byte is kept to be read next.
+fn skip_newline(state: &ConsoleState<'_>) -> Result<()> {
+    let mut peek = state.clone();
+    peek.read_timeout = CRLF_WINDOW.min(state.read_timeout);
+    match read_byte(&peek)? {
+        Some(b'\n') | None => {}
+        Some(byte) => *state.pushback.lock().unwrap() = Some(byte),
+    }
+
+    Ok(())
+}
+
+fn read_byte(state: &ConsoleState<'_>) -> Result<Option<u8>> {
+    if let Some(byte) = state.pushback.lock().unwrap().take() {
+        return Ok(Some(byte));
+    }
+
+    let fd = &state.fd;
     let mut buf = [0u8; 1];
     let mut read_fds = FdSet::new();
     read_fds.insert(fd);
 
-    let mut signals = SigSet::empty();
-    signals.add(Signal::SIGINT);
-    signals.add(Signal::SIGTERM);
-    signals.add(Signal::SIGKILL);
-
     match nix::sys::select::pselect(
         fd.as_raw_fd() + 1,
         Some(&mut read_fds),
         Some(&mut FdSet::new()),
         Some(&mut FdSet::new()),
-        Some(&TimeSpec::new(
-            0,
-            Duration::from_millis(50).as_nanos() as i64,
-        )),
-        Some(&signals),
+        Some(&TimeSpec::from_duration(state.read_timeout)),
+        Some(&read_sigmask()?),
     ) {
         Ok(0) => Ok(None),
         Ok(_) => match nix::unistd::read(fd.as_raw_fd(), &mut buf) {
             Ok(0) => Ok(None),
             Ok(_) => Ok(Some(buf[0])),
+            Err(Errno::EINTR) => Err(ConsoleError::Interrupted.into()),
             Err(err) => Err(err.into()),
         },
+        Err(Errno::EINTR) => Err(ConsoleError::Interrupted.into()),
         Err(err) => Err(err.into()),
     }
 }
 
-fn read_char(fd: &BorrowedFd<'_>) -> Result<Option<char>> {
-    read_byte(fd).map(|byte| byte.map(|byte| byte as char))
+/// The signal mask to wait for input with. `pselect` *blocks* the signals in
+/// its mask while it waits, so this is the thread's current mask with SIGINT
+/// and SIGTERM removed: even if the thread blocks them, they interrupt a
+/// blocking read, which then fails with [`ConsoleError::Interrupted`]. Every
+/// other signal keeps the thread's disposition. SIGKILL and SIGSTOP can't be
+/// blocked at all, so they're never part of a mask.
+///
+/// Note that in raw mode, ^C is read as `\x03` instead of raising SIGINT.
+fn read_sigmask() -> Result<SigSet> {
+    let mut mask = SigSet::thread_get_mask()?;
+    mask.remove(Signal::SIGINT);
+    mask.remove(Signal::SIGTERM);
+
+    Ok(mask)
+}
+
+fn read_char(state: &ConsoleState<'_>) -> Result<Option<char>> {
+    read_byte(state).map(|byte| byte.map(|byte| byte as char))
 }
 
 fn char_from_utf8(buf: &[u8]) -> Result<char> {
@@ -248,9 +728,112 @@ pub enum Keypress {
     Backspace,
     Escape,
     Char(char),
+    Mouse(MouseEvent),
+    /// Text pasted while bracketed paste is enabled. See
+    /// [`enable_bracketed_paste`].
+    Paste(String),
+    /// A key pressed while holding modifier keys, ex. Ctrl+Left. Never
+    /// constructed with empty [`Modifiers`].
+    Modified(Modifiers, Box<Keypress>),
+    /// The terminal gained focus while focus reporting is enabled. See
+    /// [`enable_focus_reporting`].
+    FocusGained,
+    /// The terminal lost focus while focus reporting is enabled.
+    FocusLost,
     UnknownSequence(Vec<char>),
 }
 
+impl Keypress {
+    /// Wrap this key in [`Keypress::Modified`], unless `modifiers` is empty.
+    pub fn with_modifiers(self, modifiers: Modifiers) -> Self {
+        if modifiers == Modifiers::default() {
+            self
+        } else {
+            Self::Modified(modifiers, Box::new(self))
+        }
+    }
+}
+
+/// The modifier keys held during a [`Keypress::Modified`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+impl Modifiers {
+    pub const SHIFT: Self = Self {
+        ctrl: false,
+        alt: false,
+        shift: true,
+    };
+    pub const ALT: Self = Self {
+        ctrl: false,
+        alt: true,
+        shift: false,
+    };
+    pub const CTRL: Self = Self {
+        ctrl: true,
+        alt: false,
+        shift: false,
+    };
+
+    /// Decode an xterm modifier parameter, ie. 1 + a bitmask of shift (1),
+    /// alt (2), and ctrl (4). Other bits, like meta, are ignored.
+    pub fn from_xterm(param: u16) -> Option<Self> {
+        let mask = param.checked_sub(1)?;
+        Some(Self {
+            shift: mask & 1 != 0,
+            alt: mask & 2 != 0,
+            ctrl: mask & 4 != 0,
+        })
+    }
+}
+
+/// A mouse event reported while mouse reporting is enabled. See
+/// [`enable_mouse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MouseEvent {
+    pub kind: MouseEventKind,
+    /// The column of the event, starting at 0.
+    pub x: u16,
+    /// The row of the event, starting at 0.
+    pub y: u16,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseEventKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    /// The mouse moved while the given button was held.
+    Drag(MouseButton),
+    /// The mouse moved with no buttons held.
+    Move,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+impl MouseButton {
+    fn from_code(code: u16) -> Option<Self> {
+        match code {
+            0 => Some(Self::Left),
+            1 => Some(Self::Middle),
+            2 => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ConsoleError {
     #[error("Interrupted!")]
@@ -258,3 +841,445 @@ pub enum ConsoleError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::os::fd::AsRawFd;
+    use std::time::Duration;
+
+    use eyre::Result;
+    use nix::pty::openpty;
+
+    use crate::{Keypress, Modifiers, MouseButton, MouseEvent, MouseEventKind};
+
+    #[tokio::test]
+    async fn test_read_line() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        nix::unistd::write(pty.master.as_raw_fd(), b"henol world\n")?;
+        let line = crate::read_line(&state, "name? ").await?;
+        assert_eq!(Some("henol world".to_string()), line);
+
+        let mut buf = [0u8; 64];
+        let read = nix::unistd::read(pty.master.as_raw_fd(), &mut buf)?;
+        assert!(String::from_utf8_lossy(&buf[..read]).contains("name? "));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_line_eof() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        // ^D on an empty line
+        nix::unistd::write(pty.master.as_raw_fd(), b"\x04")?;
+        assert_eq!(None, crate::read_line(&state, "").await?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_mouse_event() {
+        let event = |kind, x, y| Some(MouseEvent { kind, x, y });
+
+        assert_eq!(
+            event(MouseEventKind::Press(MouseButton::Left), 9, 4),
+            crate::parse_mouse_event("0;10;5", 'M')
+        );
+        assert_eq!(
+            event(MouseEventKind::Release(MouseButton::Right), 0, 0),
+            crate::parse_mouse_event("2;1;1", 'm')
+        );
+        assert_eq!(
+            event(MouseEventKind::WheelUp, 2, 3),
+            crate::parse_mouse_event("64;3;4", 'M')
+        );
+        assert_eq!(
+            event(MouseEventKind::WheelDown, 2, 3),
+            crate::parse_mouse_event("65;3;4", 'M')
+        );
+        assert_eq!(
+            event(MouseEventKind::Drag(MouseButton::Left), 4, 4),
+            crate::parse_mouse_event("32;5;5", 'M')
+        );
+        assert_eq!(
+            event(MouseEventKind::Move, 4, 4),
+            crate::parse_mouse_event("35;5;5", 'M')
+        );
+        // Ctrl-click.
+        assert_eq!(
+            event(MouseEventKind::Press(MouseButton::Middle), 0, 0),
+            crate::parse_mouse_event("17;1;1", 'M')
+        );
+
+        assert_eq!(None, crate::parse_mouse_event("0;10", 'M'));
+        assert_eq!(None, crate::parse_mouse_event("0;0;1", 'M'));
+        assert_eq!(None, crate::parse_mouse_event("3;1;1", 'M'));
+    }
+
+    #[tokio::test]
+    async fn test_next_keypress_reads_mouse_events() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        nix::unistd::write(pty.master.as_raw_fd(), b"\x1b[<0;10;5M\x1b[<0;10;5ma")?;
+        assert_eq!(
+            Some(Keypress::Mouse(MouseEvent {
+                kind: MouseEventKind::Press(MouseButton::Left),
+                x: 9,
+                y: 4,
+            })),
+            crate::next_keypress(&state).await?
+        );
+        assert_eq!(
+            Some(Keypress::Mouse(MouseEvent {
+                kind: MouseEventKind::Release(MouseButton::Left),
+                x: 9,
+                y: 4,
+            })),
+            crate::next_keypress(&state).await?
+        );
+        assert_eq!(
+            Some(Keypress::Char('a')),
+            crate::next_keypress(&state).await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enable_and_disable_mouse() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        crate::enable_mouse(&state).await?;
+        crate::disable_mouse(&state).await?;
+
+        let expected = "\x1b[?1000h\x1b[?1002h\x1b[?1006h\x1b[?1006l\x1b[?1002l\x1b[?1000l";
+        // Each write may be read separately.
+        let mut output = vec![];
+        let mut buf = [0u8; 64];
+        while output.len() < expected.len() {
+            let read = nix::unistd::read(pty.master.as_raw_fd(), &mut buf)?;
+            output.extend_from_slice(&buf[..read]);
+        }
+        assert_eq!(expected, String::from_utf8_lossy(&output));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_next_keypress_reads_bracketed_paste() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        nix::unistd::write(
+            pty.master.as_raw_fd(),
+            "\x1b[200~a\x1b[Ab\nc 💄\x1b[201~x".as_bytes(),
+        )?;
+        assert_eq!(
+            Some(Keypress::Paste("a\x1b[Ab\nc 💄".into())),
+            crate::next_keypress(&state).await?
+        );
+        assert_eq!(
+            Some(Keypress::Char('x')),
+            crate::next_keypress(&state).await?
+        );
+
+        // An unterminated paste returns what was read.
+        nix::unistd::write(pty.master.as_raw_fd(), b"\x1b[200~abc")?;
+        assert_eq!(
+            Some(Keypress::Paste("abc".into())),
+            crate::next_keypress(&state).await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_next_keypress_assembles_split_utf8() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        // 💄 is 4 bytes and ☃ is 3; trickle both in a byte at a time.
+        let master = pty.master.as_raw_fd();
+        let writer = std::thread::spawn(move || -> nix::Result<()> {
+            for byte in "☃💄".bytes() {
+                nix::unistd::write(master, &[byte])?;
+                std::thread::sleep(std::time::Duration::from_millis(30));
+            }
+            Ok(())
+        });
+
+        assert_eq!(
+            Some(Keypress::Char('☃')),
+            crate::next_keypress(&state).await?
+        );
+        assert_eq!(
+            Some(Keypress::Char('💄')),
+            crate::next_keypress(&state).await?
+        );
+        writer.join().unwrap()?;
+
+        // A character whose continuation bytes never arrive.
+        nix::unistd::write(master, &"☃".as_bytes()[..2])?;
+        assert_eq!(
+            Some(Keypress::UnknownSequence(vec!['\u{e2}', '\u{98}'])),
+            crate::next_keypress(&state).await?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_sigmask_unblocks_interrupts() -> Result<()> {
+        use nix::sys::signal::Signal;
+        use nix::sys::signalfd::SigSet;
+
+        // Tests run on their own threads, so this doesn't leak.
+        let mut blocked = SigSet::empty();
+        blocked.add(Signal::SIGINT);
+        blocked.add(Signal::SIGTERM);
+        blocked.add(Signal::SIGUSR1);
+        blocked.thread_block()?;
+
+        let mask = crate::read_sigmask()?;
+        blocked.thread_unblock()?;
+
+        assert!(!mask.contains(Signal::SIGINT));
+        assert!(!mask.contains(Signal::SIGTERM));
+        assert!(mask.contains(Signal::SIGUSR1));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_is_configurable() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let mut state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+        assert_eq!(crate::DEFAULT_READ_TIMEOUT, state.read_timeout());
+
+        // An arrow key whose last byte lags behind the default timeout.
+        let master = pty.master.as_raw_fd();
+        let write_lagging_up = move || {
+            std::thread::spawn(move || -> nix::Result<()> {
+                nix::unistd::write(master, b"\x1b[")?;
+                std::thread::sleep(std::time::Duration::from_millis(150));
+                nix::unistd::write(master, b"A")?;
+                Ok(())
+            })
+        };
+
+        let writer = write_lagging_up();
+        assert_eq!(Some(Keypress::Escape), crate::next_keypress(&state).await?);
+        writer.join().unwrap()?;
+        assert_eq!(
+            Some(Keypress::Char('A')),
+            crate::next_keypress(&state).await?
+        );
+
+        state.set_read_timeout(std::time::Duration::from_millis(500));
+        let writer = write_lagging_up();
+        assert_eq!(Some(Keypress::Up), crate::next_keypress(&state).await?);
+        writer.join().unwrap()?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_focus_reporting() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        crate::enable_focus_reporting(&state).await?;
+        crate::disable_focus_reporting(&state).await?;
+        let mut buf = [0u8; 64];
+        let read = nix::unistd::read(pty.master.as_raw_fd(), &mut buf)?;
+        assert_eq!(
+            "\x1b[?1004h\x1b[?1004l",
+            String::from_utf8_lossy(&buf[..read])
+        );
+
+        nix::unistd::write(pty.master.as_raw_fd(), b"\x1b[O\x1b[I")?;
+        assert_eq!(
+            Some(Keypress::FocusLost),
+            crate::next_keypress(&state).await?
+        );
+        assert_eq!(
+            Some(Keypress::FocusGained),
+            crate::next_keypress(&state).await?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_crlf_is_one_return() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let mut state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+        // As in a UI, so the terminal doesn't translate the `\r`s itself
+        // before they're read.
+        let termios = nix::sys::termios::tcgetattr(&pty.slave)?;
+        nix::sys::termios::tcsetattr(
+            &pty.slave,
+            nix::sys::termios::SetArg::TCSANOW,
+            &crate::raw_input_termios(&termios),
+        )?;
+
+        nix::unistd::write(pty.master.as_raw_fd(), b"a\r\nb\rc\n\r")?;
+        let mut keypresses = vec![];
+        while let Some(keypress) = crate::next_keypress(&state).await? {
+            keypresses.push(keypress);
+        }
+        assert_eq!(
+            vec![
+                Keypress::Char('a'),
+                Keypress::Return,
+                Keypress::Char('b'),
+                Keypress::Return,
+                Keypress::Char('c'),
+                Keypress::Return,
+                Keypress::Return,
+            ],
+            keypresses
+        );
+
+        state.set_coalesce_crlf(false);
+        nix::unistd::write(pty.master.as_raw_fd(), b"\r\n")?;
+        assert_eq!(Some(Keypress::Return), crate::next_keypress(&state).await?);
+        assert_eq!(Some(Keypress::Return), crate::next_keypress(&state).await?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_saved_attributes_are_restored() -> Result<()> {
+        use nix::sys::termios::{tcgetattr, tcsetattr, LocalFlags, SetArg};
+
+        let pty = openpty(None, None)?;
+        let saved = crate::SavedAttributes::save(Some(pty.slave.as_raw_fd()))?;
+        let original = tcgetattr(&pty.slave)?;
+        assert!(original.local_flags.contains(LocalFlags::ICANON));
+
+        tcsetattr(
+            &pty.slave,
+            SetArg::TCSANOW,
+            &crate::raw_input_termios(&original),
+        )?;
+        assert!(!tcgetattr(&pty.slave)?
+            .local_flags
+            .contains(LocalFlags::ICANON));
+
+        saved.restore()?;
+        assert!(tcgetattr(&pty.slave)?
+            .local_flags
+            .contains(LocalFlags::ICANON));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_reads_restore_the_terminal() -> Result<()> {
+        use nix::sys::termios::{tcgetattr, LocalFlags};
+
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+        let original = tcgetattr(&pty.slave)?;
+        assert!(original.local_flags.contains(LocalFlags::ICANON));
+
+        // Nothing is ever typed, so the read waits until it's cancelled.
+        let read = crate::next_keypress_with_timeout(&state, Duration::from_secs(60));
+        let cancel = async {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tcgetattr(&pty.slave)
+        };
+        let during = tokio::select! {
+            _ = read => unreachable!(),
+            termios = cancel => termios?,
+        };
+        assert!(!during.local_flags.contains(LocalFlags::ICANON));
+        assert_eq!(original.local_flags, tcgetattr(&pty.slave)?.local_flags);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_raw_mode_is_only_entered_when_needed() -> Result<()> {
+        use std::os::fd::BorrowedFd;
+
+        let pty = openpty(None, None)?;
+        // Safety: The pty outlives every use of the fd in this test.
+        let fd = unsafe { BorrowedFd::borrow_raw(pty.slave.as_raw_fd()) };
+
+        let raw = crate::RawMode::enter_if_needed(fd)?;
+        assert!(raw.is_some());
+        // Already raw, so there's nothing to switch, or to switch back.
+        assert!(crate::RawMode::enter_if_needed(fd)?.is_none());
+        drop(raw);
+        assert!(crate::RawMode::enter_if_needed(fd)?.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_has_input() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        assert!(!crate::has_input(&state).await?);
+        nix::unistd::write(pty.master.as_raw_fd(), b"a")?;
+        // Give the pty a moment to pass the byte along.
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(crate::has_input(&state).await?);
+        // Polling doesn't consume anything.
+        assert!(crate::has_input(&state).await?);
+        assert_eq!(
+            Some(Keypress::Char('a')),
+            crate::next_keypress(&state).await?
+        );
+        assert!(!crate::has_input(&state).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_next_keypress_decodes_modifiers() -> Result<()> {
+        let pty = openpty(None, None)?;
+        let state = crate::init(Some(pty.slave.as_raw_fd())).await?;
+
+        let modified = |modifiers, key| Some(Keypress::Modified(modifiers, Box::new(key)));
+        let cases = [
+            (&b"\x1b[A"[..], Some(Keypress::Up)),
+            (b"\x1b[1;5D", modified(Modifiers::CTRL, Keypress::Left)),
+            (b"\x1b[1;3A", modified(Modifiers::ALT, Keypress::Up)),
+            (b"\x1b[1;2H", modified(Modifiers::SHIFT, Keypress::Home)),
+            (
+                b"\x1b[3;6~",
+                modified(
+                    Modifiers {
+                        ctrl: true,
+                        alt: false,
+                        shift: true,
+                    },
+                    Keypress::Delete,
+                ),
+            ),
+            (b"\x1b[1;1C", Some(Keypress::Right)),
+            (b"\x1b[5~", Some(Keypress::PageUp)),
+            (b"\x1bx", modified(Modifiers::ALT, Keypress::Char('x'))),
+            (
+                b"\x1b[9;2Q",
+                Some(Keypress::UnknownSequence("\x1b[9;2Q".chars().collect())),
+            ),
+        ];
+
+        for (input, expected) in cases {
+            nix::unistd::write(pty.master.as_raw_fd(), input)?;
+            assert_eq!(expected, crate::next_keypress(&state).await?);
+        }
+
+        Ok(())
+    }
+}
@@ -19,14 +19,20 @@ pub fn __do_check_mail_arms(input: proc_macro::TokenStream) -> proc_macro::Token
                 // If pattern is an ident starting with MakeupMessage::, put it in the right arm.
                 // Otherwise, put it in the left arm.
 
-                let is_makeup_message = match &pattern {
-                    syn::Pat::TupleStruct(ref pat_tuple_struct) => pat_tuple_struct.path.segments
-                        [0]
-                    .ident
-                    .to_string()
-                    .starts_with("MakeupMessage"),
-                    _ => false,
+                let path = match &pattern {
+                    syn::Pat::TupleStruct(ref pat_tuple_struct) => Some(&pat_tuple_struct.path),
+                    // Unit variants, ex. `MakeupMessage::Focus`.
+                    syn::Pat::Path(ref pat_path) => Some(&pat_path.path),
+                    _ => None,
                 };
+                let is_makeup_message = path
+                    .map(|path| {
+                        path.segments[0]
+                            .ident
+                            .to_string()
+                            .starts_with("MakeupMessage")
+                    })
+                    .unwrap_or(false);
 
                 if is_makeup_message {
                     right_patterns.push(pattern.clone());
@@ -38,11 +44,19 @@ pub fn __do_check_mail_arms(input: proc_macro::TokenStream) -> proc_macro::Token
             }
         }
     }
+    // Evaluates to whether any of the arms matched, so that unmatched
+    // keypresses can be passed on to the component's ancestors.
     let output = quote! {
         match message {
-            #(Either::Left(#left_patterns) => #left_arms)*
-            #(Either::Right(#right_patterns) => #right_arms)*
-            _ => {}
+            #(Either::Left(#left_patterns) => {
+                #left_arms;
+                true
+            })*
+            #(Either::Right(#right_patterns) => {
+                #right_arms;
+                true
+            })*
+            _ => false,
         }
     };
 
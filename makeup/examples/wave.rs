@@ -5,9 +5,7 @@ use colorgrad::Gradient;
 use makeup::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
 use makeup::input::TerminalInput;
 use makeup::render::terminal::TerminalRenderer;
-use makeup::{
-    check_mail, Ansi, Component, Dimensions, DrawCommand, LineEraseMode, SgrParameter, MUI,
-};
+use makeup::{check_mail, Component, Dimensions, DrawCommand, DrawStyle, LineEraseMode, MUI};
 
 use eyre::Result;
 
@@ -61,10 +59,9 @@ impl Component for Wave {
     }
 
     async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
-        let sender = ctx.sender.clone();
         if !self.started {
             self.started = true;
-            sender.send_makeup_message(self.key(), MakeupMessage::TimerTick(DURATION))?;
+            ctx.sender.subscribe_interval(self.key(), DURATION);
         }
 
         check_mail!(
@@ -73,11 +70,6 @@ impl Component for Wave {
             match _ {
                 MakeupMessage::TimerTick(_) => {
                     self.step += 1;
-                    ctx.sender.send_makeup_message_after(
-                        self.key(),
-                        MakeupMessage::TimerTick(DURATION),
-                        DURATION,
-                    )?;
                 }
             }
         );
@@ -94,23 +86,16 @@ impl Component for Wave {
         let step = self.step % colours.len() as u64;
         colours.rotate_right(step as usize);
 
-        let mut output = String::new();
         for colour in colours.iter() {
             let [r, g, b, _] = colour.to_rgba8();
             let r = r as u32;
             let g = g as u32;
             let b = b as u32;
-            output += format!(
-                "{}{}\n",
-                Ansi::Sgr(vec![SgrParameter::HexForegroundColour(
-                    r << 16 | g << 8 | b
-                )]),
-                "█".repeat(ctx.dimensions.0 as usize)
-            )
-            .as_str();
+            commands.extend(DrawCommand::styled_text(
+                "█".repeat(ctx.dimensions.0 as usize) + "\n",
+                DrawStyle::Foreground(r << 16 | g << 8 | b),
+            ));
         }
-
-        commands.push(DrawCommand::TextUnderCursor(output));
         commands.push(DrawCommand::EraseCurrentLine(
             LineEraseMode::FromCursorToEnd,
         ));
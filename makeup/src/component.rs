@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -65,8 +67,30 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UpdateContext<'a, M
     pub fn sender(&self) -> MessageSender<M> {
         self.sender.clone()
     }
+
+    /// Ask for the UI to be rendered again, ex. when something changed
+    /// without a message being sent. Only needed for
+    /// [`MUI::render_on_demand`](crate::MUI::render_on_demand), as otherwise
+    /// every frame is rendered.
+    pub fn request_render(&mut self) {
+        self.post_office
+            .send_control(crate::ui::UiControlMessage::RequestRender);
+    }
 }
 
+/// Identifies a timer subscription. See [`MessageSender::subscribe_interval`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+/// Timer subscriptions, shared between the UI and the senders it hands out.
+#[derive(Debug, Default)]
+pub(crate) struct TimerSubscriptions {
+    next_id: u64,
+    pub(crate) timers: HashMap<SubscriptionId, (Key, Duration)>,
+}
+
+pub(crate) type SharedSubscriptions = Arc<Mutex<TimerSubscriptions>>;
+
 // TODO: Figure out update propagation so that containers recalculate layout when children change
 /// A helper for components to use for message-sending during the update loop.
 /// These functions are not on the [`UpdateContext`] itself because the
@@ -76,11 +100,46 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UpdateContext<'a, M
 pub struct MessageSender<M: std::fmt::Debug + Send + Sync + Clone + 'static> {
     focus: Key,
     tx: ContextTx<M>,
+    subscriptions: SharedSubscriptions,
 }
 
 impl<M: std::fmt::Debug + Send + Sync + Clone + 'static> MessageSender<M> {
+    /// A sender whose timer subscriptions don't reach any UI. The UI makes
+    /// its own senders for components' updates.
     pub fn new(tx: ContextTx<M>, focus: Key) -> Self {
-        Self { tx, focus }
+        Self::with_subscriptions(tx, focus, SharedSubscriptions::default())
+    }
+
+    pub(crate) fn with_subscriptions(
+        tx: ContextTx<M>,
+        focus: Key,
+        subscriptions: SharedSubscriptions,
+    ) -> Self {
+        Self {
+            tx,
+            focus,
+            subscriptions,
+        }
+    }
+
+    /// Have the UI send the given component a [`MakeupMessage::TimerTick`]
+    /// every `interval`, until [unsubscribed](MessageSender::unsubscribe) or
+    /// the component leaves the tree. Like [`Component::tick_interval`],
+    /// ticks come from the UI's global ticker, so no task is spawned, and
+    /// the interval is rounded to multiples of the ticker's base interval.
+    /// Unsubscribing and subscribing again pauses and resumes the ticks.
+    pub fn subscribe_interval(&self, key: Key, interval: Duration) -> SubscriptionId {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let id = SubscriptionId(subscriptions.next_id);
+        subscriptions.next_id += 1;
+        subscriptions.timers.insert(id, (key, interval));
+        id
+    }
+
+    /// Stop the ticks from a [`MessageSender::subscribe_interval`]. Does
+    /// nothing if the subscription has already ended.
+    pub fn unsubscribe(&self, id: SubscriptionId) {
+        self.subscriptions.lock().unwrap().timers.remove(&id);
     }
 
     /// Send a message to the given component.
@@ -171,17 +230,55 @@ pub struct RenderContext {
     pub dimensions: Dimensions,
     /// The [`Key`] of the currently-focused component.
     pub focus: Key,
+    /// The phase of the UI's global blink clock. Blinking components should
+    /// only be visible while this is `true`.
+    pub blink_on: bool,
+    /// Whether motion should be reduced, see [`MUI::set_reduced_motion`].
+    /// Animated components should stand still while this is `true`.
+    ///
+    /// [`MUI::set_reduced_motion`]: crate::MUI::set_reduced_motion
+    pub reduced_motion: bool,
+    /// Whether or not the terminal itself has focus. Only tracked while focus
+    /// reporting is enabled, see [`makeup_console::enable_focus_reporting`];
+    /// otherwise always `true`. Components may dim themselves or pause
+    /// animations while it's `false`.
+    pub app_focused: bool,
+    /// Whether the character grid was resized since the previous frame, ie.
+    /// this is the first frame at the new `dimensions`.
+    pub resized: bool,
+    /// The keys of the component being rendered's ancestors, starting with
+    /// the root. Empty when rendering the root. Ex. nested components can
+    /// indent themselves by `path.len()`.
+    pub path: Vec<Key>,
+    /// The [`Component::key_hints`] of the currently-focused component.
+    pub key_hints: Vec<(String, String)>,
 }
 
 /// A default message that can be sent to a component. Contains a lot of the
 /// built-in functionality you would expect:
 /// - Timer ticks
 /// - Text updates
+/// - Focus changes
+/// - Resizes
 #[derive(Debug, Clone)]
 pub enum MakeupMessage {
     TimerTick(Duration),
     TextUpdate(String),
     Keypress(Keypress),
+    /// The component gained focus.
+    Focus,
+    /// The component lost focus.
+    Blur,
+    /// The character grid was resized to the given `(width, height)`. Sent
+    /// to every component, and delivered before the first frame rendered at
+    /// the new size, which sees [`RenderContext::resized`].
+    Resize(Dimensions),
+    /// Set the progress of ex. a [`ProgressBar`](crate::components::ProgressBar),
+    /// from `0.0` to `1.0`.
+    Progress(f64),
+    /// Finish ex. a [`Spinner`](crate::components::Spinner), drawing the
+    /// symbol in place of its animation, alongside the text.
+    Finish(char, String),
 }
 
 /// A component in a makeup UI.
@@ -206,9 +303,30 @@ pub trait Component: std::fmt::Debug + Send + Sync {
     /// Process any messages that have been sent to this component. Messages
     /// are expected to be process asynchronously, ie. any long-running
     /// operations should be [`tokio::spawn`]ed as a task.
+    ///
+    /// Within a frame, messages are delivered grouped by kind: by default,
+    /// focus changes and resizes, then keypresses, then timer ticks, then
+    /// everything else. See [`PostOffice::set_delivery_order`].
+    ///
+    /// Keypresses go to the focused component. Any that it doesn't match in
+    /// [`check_mail!`], or leaves unread in its mailbox, are passed up to its
+    /// ancestors, nearest first, so a component may be updated a second time
+    /// in a frame, with only those keypresses in its mailbox.
+    ///
+    /// [`check_mail!`]: crate::check_mail
+    /// [`PostOffice::set_delivery_order`]: crate::post_office::PostOffice::set_delivery_order
     async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()>;
 
     /// Render this component.
+    ///
+    /// The UI moves the cursor to the component's laid-out location before
+    /// drawing its commands, so [`DrawCommand::MoveCursorRelative`] moves
+    /// are relative to that origin, and the same commands draw in the same
+    /// place wherever the component is laid out. Anything drawn outside of
+    /// the component's [`Component::dimensions`] is clipped, so siblings
+    /// can't draw over each other.
+    ///
+    /// [`DrawCommand::MoveCursorRelative`]: crate::DrawCommand::MoveCursorRelative
     async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch>;
 
     /// A unique key for this component. See [`generate_key`].
@@ -221,21 +339,202 @@ pub trait Component: std::fmt::Debug + Send + Sync {
 
     /// The dimensions of this component. Coordinates are calculated
     /// automatically by the parent component that manages layout, or are
-    /// implied by render order.
+    /// implied by render order. Anything a component draws outside of its
+    /// dimensions is clipped; components without dimensions aren't clipped.
     fn dimensions(&self) -> Result<Option<Dimensions>>;
 
     fn style(&self) -> Option<taffy::style::Style> {
         None
     }
 
+    /// Whether or not this component's children are clipped to its resolved
+    /// layout size. Anything a descendant draws outside of it is dropped.
+    fn clips_children(&self) -> bool {
+        false
+    }
+
+    /// The order this component draws in relative to the rest of the UI.
+    /// Components draw in tree order, except that those with a higher
+    /// z-index draw later, ie. on top of those with a lower one. A
+    /// component's z-index is added to its parent's, so its descendants draw
+    /// along with it.
+    fn z_index(&self) -> i32 {
+        0
+    }
+
     /// Whether or not this component accepts focus. This is intended to help
-    /// differentiate between ex. text inputs and labels.
+    /// differentiate between ex. text inputs and labels. Tab and Shift-Tab
+    /// move focus between the components that accept it, in tree order.
     fn accepts_focus(&self) -> bool {
         false
     }
+
+    /// The keyboard shortcuts this component handles, as `(key, description)`
+    /// pairs, ex. `("^S", "Save")`. The shortcuts of the focused component are
+    /// passed to rendering in [`RenderContext::key_hints`], for ex.
+    /// [`KeyHintBar`](crate::components::KeyHintBar) to show.
+    fn key_hints(&self) -> Vec<(String, String)> {
+        vec![]
+    }
+
+    /// How often this component wants to receive
+    /// [`MakeupMessage::TimerTick`]s from the UI's global ticker. Ticks are
+    /// delivered on multiples of the ticker's base interval, so animated
+    /// components don't need to schedule their own timers. `None` means no
+    /// ticks are delivered.
+    fn tick_interval(&self) -> Option<Duration> {
+        None
+    }
+
+    /// A snapshot of this component's runtime state, ex. a scroll position,
+    /// selection, or input buffer, for [`Component::restore_state`] to
+    /// recover into a fresh instance after its code is hot-reloaded. Only the
+    /// state worth keeping across a reload needs to be in the snapshot.
+    /// `None`, the default, opts out. See [`crate::state`].
+    fn state_snapshot(&self) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Recover the runtime state from a [`Component::state_snapshot`] of a
+    /// previous instance of this component.
+    fn restore_state(&mut self, _state: serde_json::Value) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when this component gains focus, after
+    /// [`Component::on_blur`] is called on the component that lost it, and
+    /// before either is updated. A [`MakeupMessage::Focus`] is mailed too.
+    async fn on_focus(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called when this component loses focus, before
+    /// [`Component::on_focus`] is called on the component that gained it. A
+    /// [`MakeupMessage::Blur`] is mailed too.
+    async fn on_blur(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once on every component when the UI stops rendering, after the
+    /// final frame has been rendered. Components holding resources (open
+    /// files, spawned tasks, connections, ...) should clean them up here.
+    async fn on_stop(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Boxed components are components too. This lets a
+/// `Box<dyn Component<Message = M>>` be passed anywhere a component is
+/// expected, without `.as_ref()`/`.as_mut()`.
+#[async_trait]
+impl<M: std::fmt::Debug + Send + Sync + Clone + 'static> Component
+    for Box<dyn Component<Message = M>>
+{
+    type Message = M;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        (**self).children()
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        (**self).children_mut()
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        (**self).update(ctx).await
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        (**self).render(ctx).await
+    }
+
+    fn key(&self) -> Key {
+        (**self).key()
+    }
+
+    fn batch(&self, commands: Vec<DrawCommand>) -> Result<DrawCommandBatch> {
+        (**self).batch(commands)
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        (**self).dimensions()
+    }
+
+    fn style(&self) -> Option<taffy::style::Style> {
+        (**self).style()
+    }
+
+    fn clips_children(&self) -> bool {
+        (**self).clips_children()
+    }
+
+    fn z_index(&self) -> i32 {
+        (**self).z_index()
+    }
+
+    fn accepts_focus(&self) -> bool {
+        (**self).accepts_focus()
+    }
+
+    fn key_hints(&self) -> Vec<(String, String)> {
+        (**self).key_hints()
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        (**self).tick_interval()
+    }
+
+    fn state_snapshot(&self) -> Option<serde_json::Value> {
+        (**self).state_snapshot()
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<()> {
+        (**self).restore_state(state)
+    }
+
+    async fn on_focus(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        (**self).on_focus(ctx).await
+    }
+
+    async fn on_blur(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        (**self).on_blur(ctx).await
+    }
+
+    async fn on_stop(&mut self) -> Result<()> {
+        (**self).on_stop().await
+    }
 }
 
 /// Generate a most-likely-unique key for a component.
 pub fn generate_key() -> Key {
     rand::random::<Key>()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Component;
+    use crate::components::EchoText;
+    use crate::test::{assert_renders_one, fake_render_ctx, static_text};
+    use crate::DrawCommand;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_boxed_component_forwards() -> Result<()> {
+        let mut boxed: Box<dyn Component<Message = ()>> =
+            Box::new(EchoText::<()>::new("henol world"));
+
+        let (key, commands) =
+            <Box<dyn Component<Message = ()>> as Component>::render(&boxed, &fake_render_ctx())
+                .await?;
+        assert_eq!(boxed.key(), key);
+        assert_eq!(
+            vec![DrawCommand::TextUnderCursor("henol world".into())],
+            commands
+        );
+
+        assert_renders_one!(static_text!("henol world"), boxed);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// A component that renders text under the cursor, blinking it on the UI's
+/// global blink clock. Unlike [`crate::SgrParameter::Blink`], this doesn't
+/// rely on the terminal's own (often disabled) blink support.
+#[derive(Debug)]
+pub struct BlinkingText<Message: std::fmt::Debug + Send + Sync + Clone> {
+    text: String,
+    key: Key,
+    _phantom: PhantomData<Message>,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> BlinkingText<Message> {
+    pub fn new<S: Into<String>>(text: S) -> Self {
+        Self {
+            text: text.into(),
+            key: crate::component::generate_key(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for BlinkingText<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::TextUpdate(text) => {
+                    self.text = text.clone();
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        if ctx.blink_on {
+            self.batch(vec![DrawCommand::TextUnderCursor(self.text.clone())])
+        } else {
+            // Overwrite the text rather than skipping it, so that it doesn't
+            // linger from the previous frame.
+            self.batch(vec![DrawCommand::TextUnderCursor(
+                " ".repeat(self.text.chars().count()),
+            )])
+        }
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(Some((self.text.len() as u64, 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::BlinkingText;
+    use crate::test::make_test_ui;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_blinks_on_the_blink_clock() -> Result<()> {
+        let mut root = BlinkingText::<()>::new("blink");
+        let ui = make_test_ui!(&mut root);
+        ui.set_tick_interval(Duration::from_millis(250)).await;
+        ui.set_blink_interval(Duration::from_millis(500)).await;
+
+        let mut frames = vec![];
+        for _ in 0..5 {
+            ui.render_once().await?;
+            ui.move_cursor(0, 0).await?;
+            frames.push(ui.read_at_cursor(5).await?);
+            ui.tick().await;
+        }
+
+        assert_eq!(vec!["blink", "blink", "     ", "     ", "blink"], frames);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reduced_motion_disables_blinking() -> Result<()> {
+        let mut root = BlinkingText::<()>::new("blink");
+        let ui = make_test_ui!(&mut root);
+        ui.set_tick_interval(Duration::from_millis(500)).await;
+        ui.set_reduced_motion(true).await;
+
+        for _ in 0..3 {
+            ui.render_once().await?;
+            ui.move_cursor(0, 0).await?;
+            assert_eq!("blink", ui.read_at_cursor(5).await?);
+            ui.tick().await;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use async_trait::async_trait;
+use eyre::Result;
+use taffy::prelude::{LengthPercentage, Rect};
+use taffy::style::Style;
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::{Component, Dimensions, DrawCommand};
+
+/// The characters a [`Border`] is drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BorderStyle {
+    /// `┌─┐`
+    #[default]
+    Single,
+    /// `╔═╗`
+    Double,
+    /// `╭─╮`
+    Rounded,
+    /// `┏━┓`
+    Thick,
+}
+
+impl BorderStyle {
+    /// The top-left, top-right, bottom-left and bottom-right corners, and the
+    /// horizontal and vertical edges.
+    fn chars(&self) -> [char; 6] {
+        match self {
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            BorderStyle::Thick => ['┏', '┓', '┗', '┛', '━', '┃'],
+        }
+    }
+}
+
+/// Draws a box around a single child, with an optional title on the top
+/// edge. The border is one cell thick, so the child is inset by one cell on
+/// every side.
+#[derive(Debug)]
+pub struct Border<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Vec<Box<dyn Component<Message = Message>>>,
+    style: BorderStyle,
+    title: Option<String>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Border<Message> {
+    pub fn new(child: Box<dyn Component<Message = Message>>) -> Self {
+        Self {
+            child: vec![child],
+            style: BorderStyle::default(),
+            title: None,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    pub fn with_style(mut self, style: BorderStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Show `title` on the top edge. Titles too long to fit are truncated
+    /// with an ellipsis.
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+}
+
+/// The top edge of a border, between the corners, `width` cells wide.
+fn top_edge(title: Option<&str>, width: usize, horizontal: char) -> String {
+    let mut title: Vec<char> = title.unwrap_or_default().chars().collect();
+    if title.len() > width {
+        title.truncate(width.saturating_sub(1));
+        title.push('…');
+        title.truncate(width);
+    }
+
+    let padding = horizontal.to_string().repeat(width - title.len());
+    title.into_iter().collect::<String>() + &padding
+}
+
+/// Draw a `width`x`height` border starting at the cursor, leaving the inside
+/// untouched.
+pub(crate) fn draw_border(
+    style: BorderStyle,
+    title: Option<&str>,
+    width: u64,
+    height: u64,
+) -> Vec<DrawCommand> {
+    let [top_left, top_right, bottom_left, bottom_right, horizontal, vertical] = style.chars();
+    let inner_width = width.saturating_sub(2);
+    let next_line = DrawCommand::MoveCursorRelative {
+        x: -(width as i64),
+        y: 1,
+    };
+
+    let mut commands = vec![DrawCommand::TextUnderCursor(format!(
+        "{top_left}{}{top_right}",
+        top_edge(title, inner_width as usize, horizontal)
+    ))];
+    for _ in 0..height.saturating_sub(2) {
+        commands.push(next_line.clone());
+        commands.push(DrawCommand::CharUnderCursor(vertical));
+        commands.push(DrawCommand::MoveCursorRelative {
+            x: inner_width as i64,
+            y: 0,
+        });
+        commands.push(DrawCommand::CharUnderCursor(vertical));
+    }
+    commands.push(next_line);
+    commands.push(DrawCommand::TextUnderCursor(format!(
+        "{bottom_left}{}{bottom_right}",
+        horizontal.to_string().repeat(inner_width as usize)
+    )));
+
+    commands
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Border<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter_mut().collect())
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let (width, height) = self.dimensions()?.unwrap_or((2, 2));
+        self.batch(draw_border(
+            self.style,
+            self.title.as_deref(),
+            width,
+            height,
+        ))
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The child's dimensions, plus the border on each side.
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let (width, height) = self.child[0].as_ref().dimensions()?.unwrap_or((0, 0));
+        Ok(Some((width + 2, height + 2)))
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            padding: Rect {
+                left: LengthPercentage::Points(1.0),
+                right: LengthPercentage::Points(1.0),
+                top: LengthPercentage::Points(1.0),
+                bottom: LengthPercentage::Points(1.0),
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Border, BorderStyle};
+    use crate::components::{EchoText, Stack};
+    use crate::test::render_sync;
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[test]
+    fn test_border_surrounds_its_child() -> Result<()> {
+        let mut root = Border::new(Box::new(EchoText::<()>::new("henol"))).with_title("hi");
+        assert_eq!(Some((7, 3)), root.dimensions()?);
+        assert_eq!("┌hi───┐\n│henol│\n└─────┘", render_sync(&mut root, 16, 4)?);
+
+        let mut root = Border::new(Box::new(EchoText::<()>::new("hi")))
+            .with_style(BorderStyle::Rounded)
+            .with_title("title");
+        assert_eq!("╭t…╮\n│hi│\n╰──╯", render_sync(&mut root, 16, 4)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_child_draws_a_2x2_box() -> Result<()> {
+        let mut root = Border::new(Box::new(Stack::<()>::new(vec![])))
+            .with_style(BorderStyle::Double)
+            .with_title("title");
+        assert_eq!(Some((2, 2)), root.dimensions()?);
+        assert_eq!("╔╗\n╚╝", render_sync(&mut root, 16, 4)?);
+
+        Ok(())
+    }
+}
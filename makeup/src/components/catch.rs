@@ -0,0 +1,185 @@
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use eyre::Result;
+use futures_util::FutureExt;
+use taffy::style::Style;
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::{Component, Dimensions, DrawCommand};
+
+/// What a [`CatchPanic`] draws once its child has panicked, by default.
+pub const DEFAULT_FALLBACK: &str = "[panicked]";
+
+/// Isolates a component that might panic. If the wrapped child panics while
+/// updating or rendering, the panic is caught, and from then on the child is
+/// no longer updated or rendered; a fallback is drawn in its place instead.
+///
+/// The child is updated and rendered by the wrapper rather than by the UI, so
+/// it's treated as a leaf: its own children aren't laid out or rendered.
+#[derive(Debug)]
+pub struct CatchPanic<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Box<dyn Component<Message = Message>>,
+    fallback: String,
+    /// The message of the panic that was caught, if any. In a mutex as
+    /// panics are caught while rendering, which only has `&self`.
+    panic: Mutex<Option<String>>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> CatchPanic<Message> {
+    pub fn new(child: Box<dyn Component<Message = Message>>) -> Self {
+        Self {
+            child,
+            fallback: DEFAULT_FALLBACK.into(),
+            panic: Mutex::new(None),
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// Draw `fallback` instead of [`DEFAULT_FALLBACK`] once the child panics.
+    pub fn with_fallback<S: Into<String>>(mut self, fallback: S) -> Self {
+        self.fallback = fallback.into();
+        self
+    }
+
+    /// The message of the panic that was caught, if the child has panicked.
+    pub fn panic_message(&self) -> Option<String> {
+        self.panic.lock().unwrap().clone()
+    }
+
+    fn has_panicked(&self) -> bool {
+        self.panic.lock().unwrap().is_some()
+    }
+
+    fn record_panic(&self, payload: Box<dyn std::any::Any + Send>) {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".into());
+        *self.panic.lock().unwrap() = Some(message);
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for CatchPanic<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        if self.has_panicked() {
+            return Ok(());
+        }
+
+        match AssertUnwindSafe(self.child.update(ctx))
+            .catch_unwind()
+            .await
+        {
+            Ok(result) => result,
+            Err(payload) => {
+                self.record_panic(payload);
+                Ok(())
+            }
+        }
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        if !self.has_panicked() {
+            match AssertUnwindSafe(self.child.render(ctx))
+                .catch_unwind()
+                .await
+            {
+                Ok(result) => {
+                    let (_key, commands) = result?;
+                    return self.batch(commands);
+                }
+                Err(payload) => self.record_panic(payload),
+            }
+        }
+
+        self.batch(vec![DrawCommand::TextUnderCursor(self.fallback.clone())])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        if self.has_panicked() {
+            return Ok(Some((self.fallback.chars().count() as u64, 1)));
+        }
+
+        self.child.as_ref().dimensions()
+    }
+
+    fn style(&self) -> Option<Style> {
+        self.child.as_ref().style()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use eyre::Result;
+
+    use super::CatchPanic;
+    use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+    use crate::test::{assert_renders_one, static_text};
+    use crate::{Component, Dimensions};
+
+    #[derive(Debug)]
+    struct Panicker {
+        key: Key,
+    }
+
+    #[async_trait]
+    impl Component for Panicker {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            panic!("oh no");
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((1, 1)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panics_render_the_fallback() -> Result<()> {
+        let mut root = CatchPanic::new(Box::new(Panicker {
+            key: crate::component::generate_key(),
+        }));
+        assert_renders_one!(static_text!("[panicked]"), root);
+        assert_eq!(Some("oh no".to_string()), root.panic_message());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use unicode_width::UnicodeWidthStr;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// A labelled box that can be checked, drawn as `[x] label` or `[ ] label`.
+/// Space or Return toggles it while it's focused, and sends a message built
+/// from the new state to the checkbox's target.
+#[derive(Debug)]
+pub struct Checkbox<Message: std::fmt::Debug + Send + Sync + Clone> {
+    label: String,
+    checked: bool,
+    target: Key,
+    on_toggle: fn(bool) -> Message,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Checkbox<Message> {
+    /// Toggling sends `on_toggle(checked)` to the component with the `target`
+    /// key.
+    pub fn new<S: Into<String>>(label: S, target: Key, on_toggle: fn(bool) -> Message) -> Self {
+        Self {
+            label: label.into(),
+            checked: false,
+            target,
+            on_toggle,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Checkbox<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Char(' ') | Keypress::Return) => {
+                    self.checked = !self.checked;
+                    ctx.sender
+                        .send_message(self.target, (self.on_toggle)(self.checked))?;
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let glyph = if self.checked { 'x' } else { ' ' };
+        self.batch(vec![DrawCommand::TextUnderCursor(format!(
+            "[{glyph}] {}",
+            self.label
+        ))])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        // +4 for the `[x] ` before the label.
+        Ok(Some((self.label.width() as u64 + 4, 1)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use either::Either;
+    use makeup_console::Keypress;
+
+    use super::Checkbox;
+    use crate::component::{MessageSender, UpdateContext};
+    use crate::post_office::PostOffice;
+    use crate::test::{assert_renders_one, static_text};
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_toggling_flips_the_glyph() -> Result<()> {
+        let target = crate::component::generate_key();
+        let mut root = Checkbox::new("Wrap lines", target, |checked| checked);
+        assert_renders_one!(static_text!("[ ] Wrap lines"), root);
+
+        let mut post_office = PostOffice::<bool>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        post_office.send_makeup(
+            root.key(),
+            crate::component::MakeupMessage::Keypress(Keypress::Char(' ')),
+        );
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        assert!(root.is_checked());
+        assert_renders_one!(static_text!("[x] Wrap lines"), root);
+        let (key, message) = rx.recv().await.unwrap();
+        assert_eq!(target, key);
+        assert!(matches!(message, Either::Left(true)));
+
+        post_office.send_makeup(
+            root.key(),
+            crate::component::MakeupMessage::Keypress(Keypress::Return),
+        );
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        assert_renders_one!(static_text!("[ ] Wrap lines"), root);
+        assert!(matches!(rx.recv().await.unwrap().1, Either::Left(false)));
+
+        Ok(())
+    }
+}
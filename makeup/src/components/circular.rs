@@ -0,0 +1,215 @@
+use std::marker::PhantomData;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// The dots of the ring, clockwise from the top, as `(x, y)` in the 4x4 grid
+/// of Braille dots spanning both cells.
+const RING: [(usize, usize); 8] = [
+    (2, 0),
+    (3, 1),
+    (3, 2),
+    (2, 3),
+    (1, 3),
+    (0, 2),
+    (0, 1),
+    (1, 0),
+];
+
+/// How many dots of the ring are lit while indeterminate.
+const ARC_LENGTH: usize = 3;
+
+/// The bit of a Braille character that lights the dot at `(x, y)` in its
+/// 2x4 grid.
+fn braille_bit(x: usize, y: usize) -> u32 {
+    match (x, y) {
+        (0, 3) => 0x40,
+        (1, 3) => 0x80,
+        (0, y) => 1 << y,
+        (_, y) => 1 << (y + 3),
+    }
+}
+
+/// Draw the given dots of the ring as two Braille characters.
+fn ring<I: IntoIterator<Item = usize>>(dots: I) -> String {
+    let mut cells = [0x2800; 2];
+    for (x, y) in dots.into_iter().map(|i| RING[i % RING.len()]) {
+        cells[x / 2] |= braille_bit(x % 2, y);
+    }
+
+    cells
+        .iter()
+        .map(|cell| char::from_u32(*cell).unwrap())
+        .collect()
+}
+
+/// A compact, circular progress indicator: a ring of Braille dots, two cells
+/// wide, that fills clockwise from the top. Progress is set with
+/// [`CircularProgress::set_progress`] or by sending it a
+/// [`MakeupMessage::Progress`].
+///
+/// An indeterminate indicator instead spins an arc around the ring, advanced
+/// by the UI's global ticker. The arc stands still while motion is reduced.
+#[derive(Debug)]
+pub struct CircularProgress<Message: std::fmt::Debug + Send + Sync + Clone> {
+    progress: f64,
+    /// How often an indeterminate indicator spins, if it's indeterminate.
+    interval: Option<Duration>,
+    step: usize,
+    key: Key,
+    _phantom: PhantomData<Message>,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> CircularProgress<Message> {
+    pub fn new() -> Self {
+        Self {
+            progress: 0.0,
+            interval: None,
+            step: 0,
+            key: crate::component::generate_key(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// An indicator for progress that can't be measured, whose arc advances
+    /// a dot every `interval`.
+    pub fn indeterminate(interval: Duration) -> Self {
+        Self {
+            interval: Some(interval),
+            ..Self::new()
+        }
+    }
+
+    /// The current progress, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    /// Set the progress. Values outside of `0.0..=1.0` are clamped.
+    pub fn set_progress(&mut self, progress: f64) {
+        self.progress = if progress.is_nan() {
+            0.0
+        } else {
+            progress.clamp(0.0, 1.0)
+        };
+    }
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Default for CircularProgress<Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component
+    for CircularProgress<Message>
+{
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Progress(progress) => {
+                    self.set_progress(*progress);
+                }
+                MakeupMessage::TimerTick(_) => {
+                    self.step = (self.step + 1) % RING.len();
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let glyphs = if self.interval.is_some() {
+            let start = if ctx.reduced_motion { 0 } else { self.step };
+            ring(start..start + ARC_LENGTH)
+        } else {
+            let lit = (self.progress * RING.len() as f64).round() as usize;
+            ring(0..lit)
+        };
+
+        self.batch(vec![DrawCommand::TextUnderCursor(glyphs)])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(Some((2, 1)))
+    }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        self.interval
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::CircularProgress;
+    use crate::component::{MakeupMessage, MessageSender, UpdateContext};
+    use crate::post_office::PostOffice;
+    use crate::test::{assert_renders_one, fake_render_ctx, static_text};
+    use crate::{Component, DrawCommand};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_ring_fills_clockwise() -> Result<()> {
+        let mut root = CircularProgress::<()>::new();
+        assert_renders_one!(static_text!("⠀⠀"), root);
+
+        root.set_progress(0.5);
+        assert_renders_one!(static_text!("⠀⡱"), root);
+
+        root.set_progress(1.0);
+        assert_renders_one!(static_text!("⢎⡱"), root);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reduced_motion_stops_the_arc() -> Result<()> {
+        let interval = Duration::from_millis(1);
+        let mut root = CircularProgress::<()>::indeterminate(interval);
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        post_office.send_makeup(root.key(), MakeupMessage::TimerTick(interval));
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        let mut ctx = fake_render_ctx();
+        let (_key, commands) = root.render(&ctx).await?;
+        assert_eq!(vec![DrawCommand::TextUnderCursor("⠀⡰".into())], commands);
+
+        ctx.reduced_motion = true;
+        let (_key, commands) = root.render(&ctx).await?;
+        assert_eq!(vec![DrawCommand::TextUnderCursor("⠀⠱".into())], commands);
+
+        Ok(())
+    }
+}
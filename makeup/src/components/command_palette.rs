@@ -0,0 +1,425 @@
+use std::cell::Cell;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use taffy::prelude::{LengthPercentageAuto, Rect};
+use taffy::style::{Position, Style};
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::util::fuzzy::fuzzy_match;
+use crate::util::selection::Selection;
+use crate::{Component, Dimensions, DrawCommand};
+
+/// The narrowest the palette is ever drawn.
+const MIN_WIDTH: u64 = 20;
+
+/// The hotkey that opens a [`CommandPalette`] by default, ie. Ctrl+P.
+pub const DEFAULT_HOTKEY: Keypress = Keypress::Char('\x10');
+
+/// A command that can be run from a [`CommandPalette`].
+#[derive(Debug, Clone)]
+pub struct Command<Message> {
+    pub name: String,
+    /// Sent to the palette's target when the command is run.
+    pub message: Message,
+}
+
+impl<Message> Command<Message> {
+    pub fn new<S: Into<String>>(name: S, message: Message) -> Self {
+        Self {
+            name: name.into(),
+            message,
+        }
+    }
+}
+
+/// A fuzzy-searchable list of commands drawn over the rest of the UI.
+///
+/// The palette wraps the UI's content. The hotkey toggles it open; while it
+/// is open it takes every keypress meant for the focused component, so
+/// typing filters the commands, Up/Down select, Enter runs the selected
+/// command by sending its message to the palette's target, and Escape
+/// closes it.
+///
+/// Because it takes keypresses away from the focused component, the palette
+/// should be an ancestor of every focusable component, ie. usually the root.
+#[derive(Debug)]
+pub struct CommandPalette<Message: std::fmt::Debug + Send + Sync + Clone> {
+    /// The wrapped content, then the palette view drawn over it.
+    children: Vec<Box<dyn Component<Message = Message>>>,
+    state: Arc<Mutex<PaletteState<Message>>>,
+    target: Key,
+    hotkey: Keypress,
+    key: Key,
+}
+
+#[derive(Debug)]
+struct PaletteState<Message> {
+    commands: Vec<Command<Message>>,
+    open: bool,
+    query: String,
+    /// Indexes into `commands`, best match first.
+    results: Vec<usize>,
+    selected: Selection,
+}
+
+impl<Message> PaletteState<Message> {
+    fn filter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .commands
+            .iter()
+            .enumerate()
+            .filter_map(|(i, command)| fuzzy_match(&self.query, &command.name).map(|(s, _)| (s, i)))
+            .collect();
+        // Stable, so ties keep the order the commands were given in.
+        scored.sort_by_key(|(score, _)| -score);
+
+        self.results = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = Selection::new(self.results.len());
+    }
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> CommandPalette<Message> {
+    /// Wrap `content` in a palette for the given commands, whose messages are
+    /// sent to the component with the `target` key.
+    pub fn new(
+        content: Box<dyn Component<Message = Message>>,
+        target: Key,
+        commands: Vec<Command<Message>>,
+    ) -> Self {
+        let mut state = PaletteState {
+            commands,
+            open: false,
+            query: String::new(),
+            results: vec![],
+            selected: Selection::default(),
+        };
+        state.filter();
+        let state = Arc::new(Mutex::new(state));
+
+        Self {
+            children: vec![
+                content,
+                Box::new(PaletteView {
+                    state: state.clone(),
+                    key: crate::component::generate_key(),
+                }),
+            ],
+            state,
+            target,
+            hotkey: DEFAULT_HOTKEY,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// Toggle the palette with `hotkey` instead of [`DEFAULT_HOTKEY`].
+    pub fn with_hotkey(mut self, hotkey: Keypress) -> Self {
+        self.hotkey = hotkey;
+        self
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.lock().unwrap().open
+    }
+
+    /// The names of the commands matching the current query, best match
+    /// first.
+    pub fn results(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .results
+            .iter()
+            .map(|i| state.commands[*i].name.clone())
+            .collect()
+    }
+
+    /// The index into [`CommandPalette::results`] of the selected command.
+    pub fn selected(&self) -> usize {
+        self.state.lock().unwrap().selected.index()
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component
+    for CommandPalette<Message>
+{
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter_mut().collect())
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        // Follow the palette opening and closing through the mailbox, so that
+        // only the keypresses made while it's open are taken.
+        let open = Cell::new(self.is_open());
+        let keypresses = ctx.post_office.take_keypresses(ctx.focus, |keypress| {
+            if open.get() {
+                if matches!(keypress, Keypress::Escape | Keypress::Return)
+                    || *keypress == self.hotkey
+                {
+                    open.set(false);
+                }
+                true
+            } else if *keypress == self.hotkey {
+                open.set(true);
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut state = self.state.lock().unwrap();
+        for keypress in keypresses {
+            if !state.open {
+                if keypress == self.hotkey {
+                    state.open = true;
+                    state.query.clear();
+                    state.filter();
+                }
+                continue;
+            }
+
+            match keypress {
+                Keypress::Escape => state.open = false,
+                keypress if keypress == self.hotkey => state.open = false,
+                Keypress::Char(c) => {
+                    state.query.push(c);
+                    state.filter();
+                }
+                Keypress::Paste(text) => {
+                    state.query.push_str(&text);
+                    state.filter();
+                }
+                Keypress::Backspace => {
+                    state.query.pop();
+                    state.filter();
+                }
+                Keypress::Up => state.selected.prev(false),
+                Keypress::Down => state.selected.next(false),
+                Keypress::Return => {
+                    if let Some(index) = state.results.get(state.selected.index()) {
+                        let message = state.commands[*index].message.clone();
+                        ctx.sender.send_message(self.target, message)?;
+                    }
+                    state.open = false;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(None)
+    }
+}
+
+/// Draws an open [`CommandPalette`] over the top-left of its content.
+#[derive(Debug)]
+struct PaletteView<Message> {
+    state: Arc<Mutex<PaletteState<Message>>>,
+    key: Key,
+}
+
+impl<Message> PaletteView<Message> {
+    fn lines(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        if !state.open {
+            return vec![];
+        }
+
+        let mut lines = vec![format!("> {}", state.query)];
+        for (i, index) in state.results.iter().enumerate() {
+            let marker = if i == state.selected.index() {
+                '*'
+            } else {
+                ' '
+            };
+            lines.push(format!("{marker} {}", state.commands[*index].name));
+        }
+
+        lines
+    }
+
+    fn width(lines: &[String]) -> u64 {
+        lines
+            .iter()
+            .map(|line| line.chars().count() as u64)
+            .max()
+            .unwrap_or(0)
+            .max(MIN_WIDTH)
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for PaletteView<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let lines = self.lines();
+        let width = Self::width(&lines);
+
+        let mut commands = vec![];
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -(width as i64),
+                    y: 1,
+                });
+            }
+            // Pad so the content underneath doesn't show through.
+            commands.push(DrawCommand::TextUnderCursor(format!(
+                "{line:width$}",
+                width = width as usize
+            )));
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let lines = self.lines();
+        if lines.is_empty() {
+            return Ok(Some((0, 0)));
+        }
+
+        Ok(Some((Self::width(&lines), lines.len() as u64)))
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            position: Position::Absolute,
+            inset: Rect {
+                left: LengthPercentageAuto::Points(0.0),
+                top: LengthPercentageAuto::Points(0.0),
+                right: LengthPercentageAuto::Auto,
+                bottom: LengthPercentageAuto::Auto,
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use either::Either;
+    use makeup_console::Keypress;
+
+    use super::{Command, CommandPalette};
+    use crate::component::{MessageSender, UpdateContext};
+    use crate::components::EchoText;
+    use crate::post_office::PostOffice;
+    use crate::test::{assert_renders_one, static_text};
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Open,
+        Save,
+        Quit,
+        Split,
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_and_runs_commands() -> Result<()> {
+        let content = EchoText::<Action>::new("content");
+        let target = content.key();
+        let mut root = CommandPalette::new(
+            Box::new(content),
+            target,
+            vec![
+                Command::new("Open file", Action::Open),
+                Command::new("Save file", Action::Save),
+                Command::new("Quit", Action::Quit),
+                Command::new("Split pane", Action::Split),
+            ],
+        );
+
+        let mut post_office = PostOffice::<Action>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let keys = [
+            // Typing while closed goes to the focused component.
+            Keypress::Char('x'),
+            super::DEFAULT_HOTKEY,
+            Keypress::Char('p'),
+        ];
+        for keypress in keys {
+            post_office.send_makeup(
+                root.key(),
+                crate::component::MakeupMessage::Keypress(keypress),
+            );
+        }
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        assert!(root.is_open());
+        assert_eq!(vec!["Split pane", "Open file"], root.results());
+        assert_eq!(1, post_office.mailbox(&root).map_or(0, |m| m.len()));
+        // As the focused component would have.
+        post_office.clear_mailbox(&root);
+
+        assert_renders_one!(static_text!("> p\n* Split pane\n  Open file"), root);
+
+        for keypress in [Keypress::Down, Keypress::Return] {
+            post_office.send_makeup(
+                root.key(),
+                crate::component::MakeupMessage::Keypress(keypress),
+            );
+        }
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        assert!(!root.is_open());
+        let (key, message) = rx.recv().await.unwrap();
+        assert_eq!(target, key);
+        assert!(matches!(message, Either::Left(Action::Open)));
+
+        assert_renders_one!(static_text!("content"), root);
+
+        Ok(())
+    }
+}
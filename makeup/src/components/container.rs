@@ -1,7 +1,8 @@
 use async_trait::async_trait;
 use derivative::Derivative;
 use eyre::Result;
-use taffy::style::Style;
+use taffy::prelude::{LengthPercentage, Rect, Size};
+use taffy::style::{Dimension, FlexDirection, Style};
 
 use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
 use crate::{Component, Dimensions};
@@ -13,6 +14,9 @@ pub struct Container<Message: std::fmt::Debug + Send + Sync + Clone> {
     key: Key,
     updating: bool,
     style: Option<Style>,
+    /// Whether any min/max size constraints have been set. Constrained
+    /// containers clip their children to their resolved size.
+    constrained: bool,
 }
 
 impl<Message: std::fmt::Debug + Send + Sync + Clone> Container<Message> {
@@ -20,6 +24,16 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Container<Message> {
         Self::new_with_style(children, None)
     }
 
+    /// Lay `children` out left to right.
+    pub fn row(children: Vec<Box<dyn Component<Message = Message>>>) -> Self {
+        Self::new(children).restyle(|style| style.flex_direction = FlexDirection::Row)
+    }
+
+    /// Lay `children` out top to bottom.
+    pub fn column(children: Vec<Box<dyn Component<Message = Message>>>) -> Self {
+        Self::new(children).restyle(|style| style.flex_direction = FlexDirection::Column)
+    }
+
     pub fn new_with_style(
         children: Vec<Box<dyn Component<Message = Message>>>,
         style: Option<Style>,
@@ -29,8 +43,65 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Container<Message> {
             key: crate::component::generate_key(),
             updating: false,
             style,
+            constrained: false,
         }
     }
+
+    /// Never lay this container out narrower than `width` columns.
+    pub fn with_min_width(self, width: u64) -> Self {
+        self.constrain(|style| style.min_size.width = Dimension::Points(width as f32))
+    }
+
+    /// Never lay this container out wider than `width` columns.
+    pub fn with_max_width(self, width: u64) -> Self {
+        self.constrain(|style| style.max_size.width = Dimension::Points(width as f32))
+    }
+
+    /// Never lay this container out shorter than `height` rows.
+    pub fn with_min_height(self, height: u64) -> Self {
+        self.constrain(|style| style.min_size.height = Dimension::Points(height as f32))
+    }
+
+    /// Never lay this container out taller than `height` rows.
+    pub fn with_max_height(self, height: u64) -> Self {
+        self.constrain(|style| style.max_size.height = Dimension::Points(height as f32))
+    }
+
+    /// Leave `gap` blank cells between each child and the next, in whichever
+    /// direction they're laid out.
+    pub fn with_gap(self, gap: u64) -> Self {
+        let gap = LengthPercentage::Points(gap as f32);
+        self.restyle(|style| {
+            style.gap = Size {
+                width: gap,
+                height: gap,
+            }
+        })
+    }
+
+    /// Leave `padding` blank cells on every side, around the children.
+    pub fn with_padding(self, padding: u64) -> Self {
+        let padding = LengthPercentage::Points(padding as f32);
+        self.restyle(|style| {
+            style.padding = Rect {
+                left: padding,
+                right: padding,
+                top: padding,
+                bottom: padding,
+            }
+        })
+    }
+
+    fn restyle<F: FnOnce(&mut Style)>(mut self, f: F) -> Self {
+        f(self.style.get_or_insert_with(Style::default));
+        self
+    }
+
+    fn constrain<F: FnOnce(&mut Style)>(mut self, f: F) -> Self {
+        self = self.restyle(f);
+        self.constrained = true;
+        self
+    }
 }
 
 #[async_trait]
@@ -64,6 +135,10 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Container<Mes
     fn style(&self) -> Option<taffy::style::Style> {
         self.style.clone()
     }
+
+    fn clips_children(&self) -> bool {
+        self.constrained
+    }
 }
 
 #[cfg(test)]
@@ -150,4 +225,81 @@ mod tests {
 
         __do_test().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_gap_between_children() -> Result<()> {
+        let mut root = Container::<()>::row(vec![
+            Box::new(EchoText::<()>::new("test 1")),
+            Box::new(EchoText::<()>::new("test 2")),
+        ])
+        .with_gap(1);
+        assert_renders_one!(static_text!("test 1 test 2"), root);
+
+        let mut root = Container::<()>::column(vec![
+            Box::new(EchoText::<()>::new("test 1")),
+            Box::new(EchoText::<()>::new("test 2")),
+        ])
+        .with_gap(1);
+        assert_renders_one!(static_text!("test 1\n\ntest 2"), root);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_padding_around_children() -> Result<()> {
+        let mut root =
+            Container::<()>::row(vec![Box::new(EchoText::<()>::new("test 1"))]).with_padding(1);
+        assert_renders_one!(static_text!("\n test 1"), root);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_children_are_drawn_at_their_layout_positions() -> Result<()> {
+        use crate::render::MemoryRenderer;
+        use crate::test::make_test_ui;
+
+        let mut root = Container::<()>::column(vec![
+            Box::new(EchoText::<()>::new("first")),
+            Box::new(EchoText::<()>::new("second")),
+        ]);
+        let ui = make_test_ui!(&mut root, 16, 4);
+        ui.render_once().await?;
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .unwrap();
+
+        assert_eq!(vec!["first "], renderer.read_region(0, 0, 6, 1)?);
+        assert_eq!(vec!["second"], renderer.read_region(0, 1, 6, 1)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_width_holds_and_clips() -> Result<()> {
+        use taffy::prelude::{Dimension, Size};
+
+        let sidebar = Container::<()>::new(vec![Box::new(EchoText::<()>::new(
+            "abcdefghijklmnopqrstuvwxyz0123",
+        ))])
+        .with_min_width(20);
+        let mut root = Container::<()>::new_with_style(
+            vec![Box::new(sidebar), Box::new(EchoText::<()>::new("!"))],
+            Some(Style {
+                size: Size {
+                    width: Dimension::Points(10.0),
+                    height: Dimension::Auto,
+                },
+                ..Default::default()
+            }),
+        );
+
+        // The sidebar overflows the 10 column root, but only down to its
+        // minimum width, and its 30 column child is clipped to that.
+        assert_renders_one!(static_text!("abcdefghijklmnopqrst!"), root);
+
+        Ok(())
+    }
 }
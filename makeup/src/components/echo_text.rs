@@ -3,6 +3,7 @@ use std::marker::PhantomData;
 
 use async_trait::async_trait;
 use eyre::Result;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
 use crate::{check_mail, Component, Dimensions, DrawCommand};
@@ -11,6 +12,8 @@ use crate::{check_mail, Component, Dimensions, DrawCommand};
 #[derive(Debug)]
 pub struct EchoText<Message: std::fmt::Debug + Send + Sync + Clone> {
     text: String,
+    /// The width to wrap the text at, if any.
+    width: Option<u64>,
     key: Key,
     _phantom: PhantomData<Message>,
 }
@@ -19,10 +22,59 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> EchoText<Message> {
     pub fn new<S: Into<String>>(text: S) -> Self {
         Self {
             text: text.into(),
+            width: None,
             key: crate::component::generate_key(),
             _phantom: PhantomData,
         }
     }
+
+    /// Text that wraps at word boundaries to fit within `width` columns,
+    /// measured by display width. Words wider than `width` are broken, and
+    /// newlines in the text are kept.
+    pub fn wrapped<S: Into<String>>(text: S, width: u64) -> Self {
+        Self {
+            width: Some(width.max(1)),
+            ..Self::new(text)
+        }
+    }
+
+    /// The lines the text is drawn as.
+    fn lines(&self) -> Vec<String> {
+        let Some(width) = self.width else {
+            return vec![self.text.clone()];
+        };
+        let width = width as usize;
+
+        let mut lines = vec![];
+        for paragraph in self.text.split('\n') {
+            let mut line = String::new();
+            for word in paragraph.split_whitespace() {
+                let line_width = line.width();
+                if line_width > 0 && line_width + 1 + word.width() <= width {
+                    line.push(' ');
+                    line.push_str(word);
+                    continue;
+                }
+                if line_width > 0 {
+                    lines.push(std::mem::take(&mut line));
+                }
+
+                let mut line_width = 0;
+                for c in word.chars() {
+                    let char_width = c.width().unwrap_or(0);
+                    if line_width > 0 && line_width + char_width > width {
+                        lines.push(std::mem::take(&mut line));
+                        line_width = 0;
+                    }
+                    line.push(c);
+                    line_width += char_width;
+                }
+            }
+            lines.push(line);
+        }
+
+        lines
+    }
 }
 
 #[async_trait]
@@ -52,16 +104,40 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for EchoText<Mess
     }
 
     async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
-        self.batch(vec![DrawCommand::TextUnderCursor(self.text.clone())])
+        let mut commands = vec![];
+        let mut previous_width: Option<i64> = None;
+        for line in self.lines() {
+            if let Some(previous_width) = previous_width {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -previous_width,
+                    y: 1,
+                });
+            }
+            previous_width = Some(line.width() as i64);
+            commands.push(DrawCommand::TextUnderCursor(line));
+        }
+
+        self.batch(commands)
     }
 
     fn key(&self) -> Key {
         self.key
     }
 
+    /// The widest line's display width, and the number of lines.
     fn dimensions(&self) -> Result<Option<Dimensions>> {
-        // TODO: Newlines?
-        Ok(Some((self.text.len() as u64, 1)))
+        let lines: Vec<String> = self
+            .lines()
+            .iter()
+            .flat_map(|line| line.split('\n'))
+            .map(String::from)
+            .collect();
+        let width = lines
+            .iter()
+            .map(|line| UnicodeWidthStr::width(line.as_str()) as u64)
+            .max()
+            .unwrap_or(0);
+        Ok(Some((width, lines.len() as u64)))
     }
 }
 
@@ -75,6 +151,7 @@ impl Display for EchoText<()> {
 mod tests {
     use super::EchoText;
     use crate::test::{assert_renders_one, static_text};
+    use crate::Component;
 
     use eyre::Result;
 
@@ -93,4 +170,52 @@ mod tests {
         assert_eq!(format!("{root}"), "henol world");
         Ok(())
     }
+
+    #[test]
+    fn test_render_sync() -> Result<()> {
+        let mut root = EchoText::<()>::new("henol world");
+        assert_eq!("henol world", crate::test::render_sync(&mut root, 32, 4)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrapped() -> Result<()> {
+        let mut root = EchoText::<()>::wrapped("the quick brown fox\n\nsupercalifragilistic", 10);
+        assert_eq!(Some((10, 5)), root.dimensions()?);
+        assert_eq!(
+            "the quick\nbrown fox\n\nsupercalif\nragilistic",
+            crate::test::render_sync(&mut root, 32, 8)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_wrapped_wide_text() -> Result<()> {
+        // CJK characters are two columns wide, so only "日本 語" fits.
+        let mut root = EchoText::<()>::wrapped("日本 語 ab", 7);
+        assert_eq!(Some((7, 2)), root.dimensions()?);
+        assert_eq!("日本 語\nab", crate::test::render_sync(&mut root, 32, 8)?);
+
+        let mut root = EchoText::<()>::wrapped("日本語 テキスト", 5);
+        assert_eq!(Some((4, 4)), root.dimensions()?);
+        assert_eq!(
+            "日本\n語\nテキ\nスト",
+            crate::test::render_sync(&mut root, 32, 8)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dimensions() -> Result<()> {
+        let dimensions = |text| EchoText::<()>::new(text).dimensions();
+        assert_eq!(Some((5, 1)), dimensions("héllo")?);
+        // CJK characters are two columns wide.
+        assert_eq!(Some((6, 1)), dimensions("日本語")?);
+        assert_eq!(Some((3, 3)), dimensions("a\nbb\nccc")?);
+
+        Ok(())
+    }
 }
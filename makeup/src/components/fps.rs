@@ -60,8 +60,10 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Fps<Message>
     }
 
     fn dimensions(&self) -> Result<Option<Dimensions>> {
-        // TODO: This should be the dimensions of the text, but we don't have that yet.
-        Ok(Some((0, 0)))
+        // TODO: This should be the dimensions of the text, but we don't have
+        // that until render time. Until then, don't claim any, so the text
+        // isn't clipped.
+        Ok(None)
     }
 }
 
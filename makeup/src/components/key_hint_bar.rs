@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::{Component, Dimensions, DrawCommand};
+
+/// The space between hints.
+const SEPARATOR: &str = "  ";
+
+/// A single line of contextual help: the [`Component::key_hints`] of the
+/// focused component, followed by any global hints, ex.
+/// `^S Save  ^Q Quit`. Meant to be placed along the bottom of the UI, ex. as
+/// the last child of a column.
+#[derive(Debug)]
+pub struct KeyHintBar<Message: std::fmt::Debug + Send + Sync + Clone> {
+    /// Hints shown no matter what's focused.
+    globals: Vec<(String, String)>,
+    key: Key,
+    _phantom: PhantomData<Message>,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> KeyHintBar<Message> {
+    /// Show the given global `(key, description)` hints after the focused
+    /// component's.
+    pub fn new(globals: Vec<(String, String)>) -> Self {
+        Self {
+            globals,
+            key: crate::component::generate_key(),
+            _phantom: PhantomData,
+        }
+    }
+
+    fn line(&self, focused: &[(String, String)]) -> String {
+        focused
+            .iter()
+            .chain(&self.globals)
+            .map(|(key, description)| format!("{key} {description}"))
+            .collect::<Vec<_>>()
+            .join(SEPARATOR)
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for KeyHintBar<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![DrawCommand::TextUnderCursor(
+            self.line(&ctx.key_hints),
+        )])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The focused component's hints aren't known until render time, so
+    /// this is a full-width line.
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use eyre::Result;
+
+    use super::KeyHintBar;
+    use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+    use crate::components::Container;
+    use crate::input::TerminalInput;
+    use crate::render::MemoryRenderer;
+    use crate::ui::UiControlMessage;
+    use crate::{Component, Dimensions, DrawCommand, MUI};
+
+    #[derive(Debug)]
+    struct Editor {
+        key: Key,
+    }
+
+    #[async_trait]
+    impl Component for Editor {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((0, 0)))
+        }
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+
+        fn key_hints(&self) -> Vec<(String, String)> {
+            vec![("^S".into(), "Save".into()), ("^F".into(), "Find".into())]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_focused_hints_are_rendered() -> Result<()> {
+        let editor_key = crate::component::generate_key();
+        let mut root = Container::<()>::new(vec![
+            Box::new(Editor { key: editor_key }),
+            Box::new(KeyHintBar::new(vec![("^Q".into(), "Quit".into())])),
+        ]);
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+        let rendered_line = |commands: &[DrawCommandBatch], line: &str| {
+            commands
+                .iter()
+                .any(|(_, commands)| commands.contains(&DrawCommand::TextUnderCursor(line.into())))
+        };
+
+        // Only the globals until the editor has focus.
+        let commands = ui
+            .render_commands(&mut crate::test::fake_render_ctx())
+            .await?;
+        assert!(rendered_line(&commands, "^Q Quit"));
+
+        ui.send_control(UiControlMessage::MoveFocus(editor_key))
+            .await;
+        ui.update(&[]).await?;
+        let commands = ui
+            .render_commands(&mut crate::test::fake_render_ctx())
+            .await?;
+        assert!(rendered_line(&commands, "^S Save  ^F Find  ^Q Quit"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,269 @@
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use serde::{Deserialize, Serialize};
+use unicode_width::UnicodeWidthStr;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::util::selection::Selection;
+use crate::{check_mail, Component, Dimensions, DrawCommand, DrawStyle};
+
+/// A list of items, one per line, with one of them selected. Up and Down
+/// move the selection, wrapping around at either end, and Return sends a
+/// message built from the selected index to the list's target. The selected
+/// item is drawn in reverse video.
+#[derive(Debug)]
+pub struct List<Message: std::fmt::Debug + Send + Sync + Clone> {
+    items: Vec<String>,
+    selected: Selection,
+    target: Key,
+    on_select: fn(usize) -> Message,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> List<Message> {
+    /// Pressing Return sends `on_select(selected)` to the component with the
+    /// `target` key.
+    pub fn new(items: Vec<String>, target: Key, on_select: fn(usize) -> Message) -> Self {
+        Self {
+            selected: Selection::new(items.len()),
+            items,
+            target,
+            on_select,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// The index of the selected item.
+    pub fn selected(&self) -> usize {
+        self.selected.index()
+    }
+
+    fn width(&self) -> usize {
+        self.items
+            .iter()
+            .map(|item| item.width())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// What a [`List`] keeps across hot-reloads.
+#[derive(Serialize, Deserialize)]
+struct ListState {
+    selected: usize,
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for List<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Up) => {
+                    self.selected.prev(true);
+                }
+                MakeupMessage::Keypress(Keypress::Down) => {
+                    self.selected.next(true);
+                }
+                MakeupMessage::Keypress(Keypress::Return) => {
+                    if !self.selected.is_empty() {
+                        ctx.sender
+                            .send_message(self.target, (self.on_select)(self.selected.index()))?;
+                    }
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let width = self.width();
+
+        let mut commands = vec![];
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -(width as i64),
+                    y: 1,
+                });
+            }
+            // Pad, so that the highlight covers the whole row.
+            let line = format!("{item}{}", " ".repeat(width - item.width()));
+            if i == self.selected.index() {
+                commands.extend(DrawCommand::styled_text(line, DrawStyle::Reverse));
+            } else {
+                commands.push(DrawCommand::TextUnderCursor(line));
+            }
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(Some((self.width() as u64, self.items.len() as u64)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+
+    fn state_snapshot(&self) -> Option<serde_json::Value> {
+        serde_json::to_value(ListState {
+            selected: self.selected.index(),
+        })
+        .ok()
+    }
+
+    fn restore_state(&mut self, state: serde_json::Value) -> Result<()> {
+        let state: ListState = serde_json::from_value(state)?;
+        // The items may have changed since the snapshot.
+        self.selected.select(state.selected);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::Keypress;
+
+    use super::List;
+    use crate::component::{MessageSender, UpdateContext};
+    use crate::components::Container;
+    use crate::post_office::PostOffice;
+    use crate::state::{restore_tree, snapshot_tree};
+    use crate::test::{assert_renders_many, fake_render_ctx};
+    use crate::{Component, DrawCommand, DrawStyle};
+
+    use eyre::Result;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Pick(usize),
+    }
+
+    #[tokio::test]
+    async fn test_selection_moves_and_wraps() -> Result<()> {
+        let target = crate::component::generate_key();
+        let mut root = List::new(
+            vec!["one".into(), "two".into(), "three".into()],
+            target,
+            Action::Pick,
+        );
+        assert_eq!(Some((5, 3)), root.dimensions()?);
+
+        let mut post_office = PostOffice::<Action>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        for keypress in [Keypress::Up, Keypress::Up, Keypress::Down, Keypress::Return] {
+            post_office.send_makeup(
+                root.key(),
+                crate::component::MakeupMessage::Keypress(keypress),
+            );
+        }
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        // Up from the first item wraps to the last.
+        assert_eq!(2, root.selected());
+        let (key, message) = rx.recv().await.unwrap();
+        assert_eq!(target, key);
+        assert!(matches!(message, either::Either::Left(Action::Pick(2))));
+
+        assert_renders_many!(
+            vec![
+                DrawCommand::TextUnderCursor("one  ".into()),
+                DrawCommand::MoveCursorRelative { x: -5, y: 1 },
+                DrawCommand::TextUnderCursor("two  ".into()),
+                DrawCommand::MoveCursorRelative { x: -5, y: 1 },
+                DrawCommand::Style(DrawStyle::Reverse),
+                DrawCommand::TextUnderCursor("three".into()),
+                DrawCommand::Style(DrawStyle::Default),
+            ],
+            root
+        );
+
+        // Down from the last item wraps to the first.
+        post_office.send_makeup(
+            root.key(),
+            crate::component::MakeupMessage::Keypress(Keypress::Down),
+        );
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+        assert_eq!(0, root.selected());
+        let (_key, commands) = root.render(&fake_render_ctx()).await?;
+        assert_eq!(DrawCommand::Style(DrawStyle::Reverse), commands[0]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_selection_survives_a_reload() -> Result<()> {
+        let items = || vec!["one".into(), "two".into(), "three".into()];
+        let mut root = Container::new(vec![Box::new(List::new(items(), 0, Action::Pick))]);
+        let mut post_office = PostOffice::<Action>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let list = &mut root.children_mut().unwrap()[0];
+        post_office.send_makeup(
+            list.key(),
+            crate::component::MakeupMessage::Keypress(Keypress::Down),
+        );
+        list.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), list.key()),
+            focus: list.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        let snapshot = snapshot_tree(&root);
+        assert_eq!(1, snapshot.len());
+
+        // As if the code were reloaded, with a fresh instance.
+        let mut reloaded = Container::new(vec![Box::new(List::new(items(), 0, Action::Pick))]);
+        restore_tree(&mut reloaded, &snapshot)?;
+        assert_renders_many!(
+            vec![
+                DrawCommand::TextUnderCursor("one  ".into()),
+                DrawCommand::MoveCursorRelative { x: -5, y: 1 },
+                DrawCommand::Style(DrawStyle::Reverse),
+                DrawCommand::TextUnderCursor("two  ".into()),
+                DrawCommand::Style(DrawStyle::Default),
+                DrawCommand::MoveCursorRelative { x: -5, y: 1 },
+                DrawCommand::TextUnderCursor("three".into()),
+            ],
+            reloaded
+        );
+        let (_key, commands) = reloaded.children().unwrap()[0]
+            .render(&fake_render_ctx())
+            .await?;
+        assert_eq!(DrawCommand::Style(DrawStyle::Reverse), commands[2]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,461 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use taffy::prelude::{LengthPercentageAuto, Rect};
+use taffy::style::{Position, Style};
+use unicode_width::UnicodeWidthStr;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::components::command_palette::Command;
+use crate::util::selection::Selection;
+use crate::{check_mail, Component, Dimensions, DrawCommand, DrawStyle};
+
+/// An entry in a [`Menu`]'s dropdown.
+#[derive(Debug, Clone)]
+pub enum MenuItem<Message> {
+    /// Sends its message to the [`MenuBar`]'s target when selected.
+    Action(Command<Message>),
+    /// A line between groups of actions. It can't be selected.
+    Separator,
+}
+
+impl<Message> MenuItem<Message> {
+    pub fn action<S: Into<String>>(name: S, message: Message) -> Self {
+        Self::Action(Command::new(name, message))
+    }
+}
+
+/// A top-level menu in a [`MenuBar`].
+#[derive(Debug, Clone)]
+pub struct Menu<Message> {
+    pub title: String,
+    pub items: Vec<MenuItem<Message>>,
+}
+
+impl<Message> Menu<Message> {
+    pub fn new<S: Into<String>>(title: S, items: Vec<MenuItem<Message>>) -> Self {
+        Self {
+            title: title.into(),
+            items,
+        }
+    }
+}
+
+/// A row of menu titles, ex. File, Edit and View, with the active one drawn
+/// in reverse video.
+///
+/// While it's focused, Left and Right move between menus, and Return or Down
+/// opens the active one as a dropdown below its title. In an open dropdown
+/// Up and Down select an action, skipping separators, Return sends the
+/// action's message to the bar's target, and Escape closes it.
+///
+/// The dropdown is drawn over whatever is below the bar, so the bar is only
+/// ever one row tall.
+#[derive(Debug)]
+pub struct MenuBar<Message: std::fmt::Debug + Send + Sync + Clone> {
+    /// The dropdown.
+    children: Vec<Box<dyn Component<Message = Message>>>,
+    state: Arc<Mutex<MenuState<Message>>>,
+    target: Key,
+    key: Key,
+}
+
+#[derive(Debug)]
+struct MenuState<Message> {
+    menus: Vec<Menu<Message>>,
+    active: Selection,
+    open: bool,
+    /// Indexes into the active menu's actions, ignoring separators.
+    selected: Selection,
+}
+
+impl<Message> MenuState<Message> {
+    fn actions(&self) -> Vec<&Command<Message>> {
+        self.menus
+            .get(self.active.index())
+            .map(|menu| {
+                menu.items
+                    .iter()
+                    .filter_map(|item| match item {
+                        MenuItem::Action(command) => Some(command),
+                        MenuItem::Separator => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn open(&mut self) {
+        self.open = !self.menus.is_empty();
+        self.selected = Selection::new(self.actions().len());
+    }
+
+    /// Where the active title starts.
+    fn active_offset(&self) -> u64 {
+        self.menus
+            .iter()
+            .take(self.active.index())
+            .map(|menu| title_width(&menu.title))
+            .sum()
+    }
+}
+
+/// How wide a title is drawn, with a space either side.
+fn title_width(title: &str) -> u64 {
+    title.width() as u64 + 2
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> MenuBar<Message> {
+    /// Selecting an action sends its message to the component with the
+    /// `target` key.
+    pub fn new(menus: Vec<Menu<Message>>, target: Key) -> Self {
+        let state = Arc::new(Mutex::new(MenuState {
+            active: Selection::new(menus.len()),
+            menus,
+            open: false,
+            selected: Selection::default(),
+        }));
+
+        Self {
+            children: vec![Box::new(Dropdown {
+                state: state.clone(),
+                key: crate::component::generate_key(),
+            })],
+            state,
+            target,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// The index of the active menu.
+    pub fn active(&self) -> usize {
+        self.state.lock().unwrap().active.index()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.lock().unwrap().open
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for MenuBar<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter_mut().collect())
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        let state = self.state.clone();
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Left) => {
+                    let mut state = state.lock().unwrap();
+                    state.active.prev(true);
+                    if state.open {
+                        state.open();
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Right) => {
+                    let mut state = state.lock().unwrap();
+                    state.active.next(true);
+                    if state.open {
+                        state.open();
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Up) => {
+                    let mut state = state.lock().unwrap();
+                    if state.open {
+                        state.selected.prev(true);
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Down) => {
+                    let mut state = state.lock().unwrap();
+                    if state.open {
+                        state.selected.next(true);
+                    } else {
+                        state.open();
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Return) => {
+                    let mut state = state.lock().unwrap();
+                    if state.open {
+                        let message = state
+                            .actions()
+                            .get(state.selected.index())
+                            .map(|command| command.message.clone());
+                        if let Some(message) = message {
+                            ctx.sender.send_message(self.target, message)?;
+                        }
+                        state.open = false;
+                    } else {
+                        state.open();
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Escape) => {
+                    state.lock().unwrap().open = false;
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let state = self.state.lock().unwrap();
+
+        let mut commands = vec![];
+        for (i, menu) in state.menus.iter().enumerate() {
+            let title = format!(" {} ", menu.title);
+            if i == state.active.index() {
+                commands.extend(DrawCommand::styled_text(title, DrawStyle::Reverse));
+            } else {
+                commands.push(DrawCommand::TextUnderCursor(title));
+            }
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let state = self.state.lock().unwrap();
+        let width = state
+            .menus
+            .iter()
+            .map(|menu| title_width(&menu.title))
+            .sum();
+        Ok(Some((width, 1)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+/// Draws the open menu of a [`MenuBar`] below its title.
+#[derive(Debug)]
+struct Dropdown<Message> {
+    state: Arc<Mutex<MenuState<Message>>>,
+    key: Key,
+}
+
+impl<Message> Dropdown<Message> {
+    /// The rows of the open menu, and which of them is selected.
+    fn lines(&self) -> (Vec<String>, Option<usize>) {
+        let state = self.state.lock().unwrap();
+        let Some(menu) = state.menus.get(state.active.index()).filter(|_| state.open) else {
+            return (vec![], None);
+        };
+
+        let width = menu
+            .items
+            .iter()
+            .map(|item| match item {
+                MenuItem::Action(command) => command.name.width(),
+                MenuItem::Separator => 0,
+            })
+            .max()
+            .unwrap_or(0)
+            + 2;
+
+        let mut selected = None;
+        let mut actions = 0;
+        let lines = menu
+            .items
+            .iter()
+            .enumerate()
+            .map(|(row, item)| match item {
+                MenuItem::Action(command) => {
+                    if actions == state.selected.index() {
+                        selected = Some(row);
+                    }
+                    actions += 1;
+                    format!(
+                        " {}{}",
+                        command.name,
+                        " ".repeat(width - 1 - command.name.width())
+                    )
+                }
+                MenuItem::Separator => "─".repeat(width),
+            })
+            .collect();
+
+        (lines, selected)
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Dropdown<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let (lines, selected) = self.lines();
+
+        let mut commands = vec![];
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -(line.width() as i64),
+                    y: 1,
+                });
+            }
+            if Some(i) == selected {
+                commands.extend(DrawCommand::styled_text(line, DrawStyle::Reverse));
+            } else {
+                commands.push(DrawCommand::TextUnderCursor(line));
+            }
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let (lines, _) = self.lines();
+        let width = lines.first().map_or(0, |line| line.width() as u64);
+        Ok(Some((width, lines.len() as u64)))
+    }
+
+    fn style(&self) -> Option<Style> {
+        let left = self.state.lock().unwrap().active_offset();
+        Some(Style {
+            position: Position::Absolute,
+            inset: Rect {
+                left: LengthPercentageAuto::Points(left as f32),
+                top: LengthPercentageAuto::Points(1.0),
+                right: LengthPercentageAuto::Auto,
+                bottom: LengthPercentageAuto::Auto,
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use either::Either;
+    use makeup_console::Keypress;
+
+    use super::{Menu, MenuBar, MenuItem};
+    use crate::component::{MessageSender, UpdateContext};
+    use crate::post_office::PostOffice;
+    use crate::test::{fake_render_ctx, render_sync};
+    use crate::{Component, DrawCommand, DrawStyle};
+
+    use eyre::Result;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Action {
+        Open,
+        Quit,
+        Copy,
+        Paste,
+    }
+
+    #[tokio::test]
+    async fn test_opening_a_menu_and_selecting_an_item() -> Result<()> {
+        let target = crate::component::generate_key();
+        let mut root = MenuBar::new(
+            vec![
+                Menu::new(
+                    "File",
+                    vec![
+                        MenuItem::action("Open", Action::Open),
+                        MenuItem::Separator,
+                        MenuItem::action("Quit", Action::Quit),
+                    ],
+                ),
+                Menu::new(
+                    "Edit",
+                    vec![
+                        MenuItem::action("Copy", Action::Copy),
+                        MenuItem::Separator,
+                        MenuItem::action("Paste", Action::Paste),
+                    ],
+                ),
+            ],
+            target,
+        );
+        assert_eq!(Some((12, 1)), root.dimensions()?);
+
+        let mut post_office = PostOffice::<Action>::new();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut press = |root: &mut MenuBar<Action>, keys: Vec<Keypress>| {
+            for keypress in keys {
+                post_office.send_makeup(
+                    root.key(),
+                    crate::component::MakeupMessage::Keypress(keypress),
+                );
+            }
+            let mut ctx = UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), root.key()),
+                focus: root.key(),
+                dimensions: (100, 100),
+            };
+            futures_executor::block_on(root.update(&mut ctx))
+        };
+
+        assert_eq!(" File  Edit", render_sync(&mut root, 16, 6)?);
+
+        press(&mut root, vec![Keypress::Right, Keypress::Down])?;
+        assert_eq!(1, root.active());
+        assert!(root.is_open());
+        // The separator is skipped.
+        press(&mut root, vec![Keypress::Down])?;
+        assert_eq!(
+            " File  Edit\n       Copy\n      ───────\n       Paste",
+            render_sync(&mut root, 16, 6)?
+        );
+        let (_key, commands) = root.children().unwrap()[0]
+            .render(&fake_render_ctx())
+            .await?;
+        assert_eq!(
+            vec![
+                DrawCommand::TextUnderCursor(" Copy  ".into()),
+                DrawCommand::MoveCursorRelative { x: -7, y: 1 },
+                DrawCommand::TextUnderCursor("───────".into()),
+                DrawCommand::MoveCursorRelative { x: -7, y: 1 },
+                DrawCommand::Style(DrawStyle::Reverse),
+                DrawCommand::TextUnderCursor(" Paste ".into()),
+                DrawCommand::Style(DrawStyle::Default),
+            ],
+            commands
+        );
+
+        press(&mut root, vec![Keypress::Return])?;
+        assert!(!root.is_open());
+        let (key, message) = rx.recv().await.unwrap();
+        assert_eq!(target, key);
+        assert!(matches!(message, Either::Left(Action::Paste)));
+
+        Ok(())
+    }
+}
@@ -1,13 +1,47 @@
+pub mod blinking_text;
+pub mod border;
+pub mod catch;
+pub mod checkbox;
+pub mod circular;
+pub mod command_palette;
 pub mod container;
 pub mod echo_text;
 pub mod fps;
+pub mod key_hint_bar;
+pub mod list;
+pub mod menu_bar;
+pub mod modal;
 // pub mod positioned_text;
+pub mod progress_bar;
+pub mod radio_group;
+pub mod scroll_view;
 pub mod spinner;
+pub mod split_pane;
+pub mod stack;
+pub mod table;
 pub mod text_input;
+pub mod window;
 
+pub use blinking_text::BlinkingText;
+pub use border::{Border, BorderStyle};
+pub use catch::CatchPanic;
+pub use checkbox::Checkbox;
+pub use circular::CircularProgress;
+pub use command_palette::CommandPalette;
 pub use container::Container;
 pub use echo_text::EchoText;
 pub use fps::Fps;
+pub use key_hint_bar::KeyHintBar;
+pub use list::List;
+pub use menu_bar::{Menu, MenuBar, MenuItem};
+pub use modal::{Modal, ModalHandle};
 // pub use positioned_text::PositionedText;
+pub use progress_bar::ProgressBar;
+pub use radio_group::RadioGroup;
+pub use scroll_view::ScrollView;
 pub use spinner::Spinner;
+pub use split_pane::SplitPane;
+pub use stack::Stack;
+pub use table::{Alignment, CellOverflow, SortOrder, Table};
 pub use text_input::TextInput;
+pub use window::Window;
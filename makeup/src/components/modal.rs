@@ -0,0 +1,414 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use taffy::prelude::{LengthPercentage, LengthPercentageAuto, Rect};
+use taffy::style::{AlignItems, JustifyContent, Position, Style};
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::components::border::{draw_border, BorderStyle};
+use crate::ui::UiControlMessage;
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// How far above its siblings a [`Modal`] draws. See [`Component::z_index`].
+pub const MODAL_Z_INDEX: i32 = 100;
+
+/// Opens and closes a [`Modal`] from elsewhere, ex. from the component
+/// whose keypress should open it. Cheap to clone.
+#[derive(Debug, Clone, Default)]
+pub struct ModalHandle {
+    open: Arc<Mutex<bool>>,
+}
+
+impl ModalHandle {
+    /// Open the modal. It takes focus on its next update.
+    pub fn open(&self) {
+        *self.open.lock().unwrap() = true;
+    }
+
+    /// Close the modal without sending its close message. Focus goes back to
+    /// wherever it was before the modal opened.
+    pub fn close(&self) {
+        *self.open.lock().unwrap() = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        *self.open.lock().unwrap()
+    }
+}
+
+/// A dialog drawn centred over the rest of the UI, in a box of its own.
+///
+/// While it's open, the modal takes focus, giving it to its content if the
+/// content accepts focus, so that it gets every keypress: Tab and Shift-Tab
+/// don't leave it, and keypresses the content doesn't handle go no further.
+/// Escape closes it, sending `on_close` to its target, and focus goes back
+/// to wherever it was before the modal opened.
+///
+/// The modal covers its parent, so it should usually be a child of the root.
+/// It draws at [`MODAL_Z_INDEX`], ie. over its siblings wherever it is among
+/// them.
+#[derive(Debug)]
+pub struct Modal<Message: std::fmt::Debug + Send + Sync + Clone> {
+    /// The content, in its box.
+    frame: Vec<Box<dyn Component<Message = Message>>>,
+    content: Key,
+    content_accepts_focus: bool,
+    handle: ModalHandle,
+    /// Where focus was before the modal took it, while it has it.
+    previous_focus: Option<Key>,
+    target: Key,
+    on_close: Message,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Modal<Message> {
+    /// A closed modal showing `content`, which sends `on_close` to the
+    /// component with the `target` key when closed with Escape.
+    pub fn new(
+        content: Box<dyn Component<Message = Message>>,
+        target: Key,
+        on_close: Message,
+    ) -> Self {
+        Self {
+            content: content.key(),
+            content_accepts_focus: content.accepts_focus(),
+            frame: vec![Box::new(ModalFrame {
+                content: vec![content],
+                key: crate::component::generate_key(),
+            })],
+            handle: ModalHandle::default(),
+            previous_focus: None,
+            target,
+            on_close,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// A handle for opening and closing this modal.
+    pub fn handle(&self) -> ModalHandle {
+        self.handle.clone()
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.handle.is_open()
+    }
+
+    /// The component that has focus while the modal is open.
+    fn focus_target(&self) -> Key {
+        if self.content_accepts_focus {
+            self.content
+        } else {
+            self.key
+        }
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Modal<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        // Closed, the content can neither be seen nor focused.
+        if self.is_open() {
+            Some(self.frame.iter().collect())
+        } else {
+            None
+        }
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        if self.is_open() {
+            Some(self.frame.iter_mut().collect())
+        } else {
+            None
+        }
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        if !self.is_open() {
+            // Closed with a handle.
+            if let Some(previous) = self.previous_focus.take() {
+                ctx.post_office
+                    .send_control(UiControlMessage::MoveFocus(previous));
+            }
+            return Ok(());
+        }
+
+        let focus_target = self.focus_target();
+        if ctx.focus != focus_target {
+            // Just opened, or focus was moved out from under the modal.
+            self.previous_focus.get_or_insert(ctx.focus);
+            ctx.post_office
+                .send_control(UiControlMessage::MoveFocus(focus_target));
+            return Ok(());
+        }
+
+        // Take these before the content can see them.
+        let mut escaped = false;
+        if ctx.focus == self.content {
+            let taken = ctx.post_office.take_keypresses(self.content, |keypress| {
+                matches!(
+                    keypress,
+                    Keypress::Escape | Keypress::Tab | Keypress::ShiftTab
+                )
+            });
+            escaped = taken.contains(&Keypress::Escape);
+        }
+        // Whatever reaches the modal itself goes no further.
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Escape) => {
+                    escaped = true;
+                }
+                MakeupMessage::Keypress(_) => {}
+            }
+        );
+
+        if escaped {
+            self.handle.close();
+            if let Some(previous) = self.previous_focus.take() {
+                ctx.post_office
+                    .send_control(UiControlMessage::MoveFocus(previous));
+            }
+            ctx.sender
+                .send_message(self.target, self.on_close.clone())?;
+        }
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        if self.is_open() {
+            // Covers the parent, see `style`.
+            Ok(None)
+        } else {
+            Ok(Some((0, 0)))
+        }
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            position: Position::Absolute,
+            inset: Rect {
+                left: LengthPercentageAuto::Points(0.0),
+                right: LengthPercentageAuto::Points(0.0),
+                top: LengthPercentageAuto::Points(0.0),
+                bottom: LengthPercentageAuto::Points(0.0),
+            },
+            justify_content: Some(JustifyContent::Center),
+            align_items: Some(AlignItems::Center),
+            ..Default::default()
+        })
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.is_open() && !self.content_accepts_focus
+    }
+
+    fn z_index(&self) -> i32 {
+        MODAL_Z_INDEX
+    }
+}
+
+/// The box around a [`Modal`]'s content. Unlike a [`Border`], it's filled,
+/// so that nothing underneath shows through.
+///
+/// [`Border`]: crate::components::Border
+#[derive(Debug)]
+struct ModalFrame<Message> {
+    content: Vec<Box<dyn Component<Message = Message>>>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> ModalFrame<Message> {
+    fn frame_dimensions(&self) -> Result<Dimensions> {
+        let (width, height) = self.content[0].dimensions()?.unwrap_or((0, 0));
+        Ok((width + 2, height + 2))
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for ModalFrame<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.content.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.content.iter_mut().collect())
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let (width, height) = self.frame_dimensions()?;
+        let mut commands = draw_border(BorderStyle::default(), None, width, height);
+
+        // Back to just inside the top-left corner, and blank the inside.
+        let inner_width = width.saturating_sub(2);
+        commands.push(DrawCommand::MoveCursorRelative {
+            x: 1 - width as i64,
+            y: 2 - height as i64,
+        });
+        for _ in 0..height.saturating_sub(2) {
+            commands.push(DrawCommand::TextUnderCursor(
+                " ".repeat(inner_width as usize),
+            ));
+            commands.push(DrawCommand::MoveCursorRelative {
+                x: -(inner_width as i64),
+                y: 1,
+            });
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        self.frame_dimensions().map(Some)
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            padding: Rect {
+                left: LengthPercentage::Points(1.0),
+                right: LengthPercentage::Points(1.0),
+                top: LengthPercentage::Points(1.0),
+                bottom: LengthPercentage::Points(1.0),
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::Keypress;
+
+    use super::Modal;
+    use crate::component::{MakeupMessage, MessageSender, UpdateContext};
+    use crate::components::{Container, EchoText, TextInput};
+    use crate::post_office::PostOffice;
+    use crate::render::MemoryRenderer;
+    use crate::test::{fake_render_ctx, make_test_ui};
+    use crate::{Component, DrawCommand};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_modal_draws_over_its_siblings() -> Result<()> {
+        let modal = Modal::new(Box::new(EchoText::<()>::new("hi")), 0, ());
+        let handle = modal.handle();
+        let sibling = EchoText::<()>::new("henol\nworld\nthis\nis\nmakeup");
+        let sibling_key = sibling.key();
+        // The modal comes first in the tree, but still draws last, centred over
+        // the container.
+        let mut root = Container::new(vec![Box::new(modal), Box::new(sibling)]);
+        let ui = make_test_ui!(&mut root, 10, 5);
+        handle.open();
+        ui.update(&[]).await?;
+        ui.render_once().await?;
+
+        let batches = ui.render_commands(&mut fake_render_ctx()).await?;
+        let last_sibling = batches
+            .iter()
+            .rposition(|(key, _)| *key == sibling_key)
+            .unwrap();
+        assert!(batches[last_sibling..]
+            .iter()
+            .any(|(_, commands)| commands.contains(&DrawCommand::TextUnderCursor("hi".into()))));
+
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .unwrap();
+        assert_eq!(
+            vec![
+                "henol     ",
+                "w┌──┐     ",
+                "t│hi│     ",
+                "i└──┘     ",
+                "makeup    "
+            ],
+            renderer.read_region(0, 0, 10, 5)?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_modal_takes_focus_until_escape() -> Result<()> {
+        let input = TextInput::<()>::new("name");
+        let input_key = input.key();
+        let modal = Modal::new(Box::new(input), 0, ());
+        let handle = modal.handle();
+        let mut root = Container::new(vec![Box::new(modal)]);
+        let root_key = root.key();
+        let ui = make_test_ui!(&mut root, 20, 5);
+        ui.update(&[]).await?;
+        assert_eq!(root_key, ui.focus().await);
+
+        handle.open();
+        ui.update(&[]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(input_key, ui.focus().await);
+
+        // Tab doesn't leave the modal.
+        ui.update(&[Keypress::Tab]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(input_key, ui.focus().await);
+
+        ui.update(&[Keypress::Escape]).await?;
+        ui.update(&[]).await?;
+        assert!(!handle.is_open());
+        assert_eq!(root_key, ui.focus().await);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_escape_sends_the_close_message() -> Result<()> {
+        let input = TextInput::<()>::new("name");
+        let input_key = input.key();
+        let target = crate::component::generate_key();
+        let mut modal = Modal::new(Box::new(input), target, ());
+        modal.handle().open();
+
+        let mut post_office = PostOffice::<()>::new();
+        post_office.send_makeup(input_key, MakeupMessage::Keypress(Keypress::Escape));
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        modal
+            .update(&mut UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), input_key),
+                focus: input_key,
+                dimensions: (100, 100),
+            })
+            .await?;
+
+        assert!(!modal.is_open());
+        let (key, _message) = rx.recv().await.unwrap();
+        assert_eq!(target, key);
+
+        Ok(())
+    }
+}
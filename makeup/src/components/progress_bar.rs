@@ -0,0 +1,140 @@
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// Partially-filled cells, from one eighth to seven eighths full.
+const EIGHTHS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// A determinate progress bar, `width` cells wide, with an optional label
+/// after it. Progress is set with [`ProgressBar::set_progress`] or by sending
+/// it a [`MakeupMessage::Progress`].
+#[derive(Debug)]
+pub struct ProgressBar<Message: std::fmt::Debug + Send + Sync + Clone> {
+    width: u64,
+    label: Option<String>,
+    progress: f64,
+    key: Key,
+    _phantom: PhantomData<Message>,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> ProgressBar<Message> {
+    pub fn new(width: u64) -> Self {
+        Self {
+            width,
+            label: None,
+            progress: 0.0,
+            key: crate::component::generate_key(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn with_label<S: Into<String>>(mut self, label: S) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// The current progress, from `0.0` to `1.0`.
+    pub fn progress(&self) -> f64 {
+        self.progress
+    }
+
+    /// Set the progress. Values outside of `0.0..=1.0` are clamped.
+    pub fn set_progress(&mut self, progress: f64) {
+        self.progress = if progress.is_nan() {
+            0.0
+        } else {
+            progress.clamp(0.0, 1.0)
+        };
+    }
+
+    fn bar(&self) -> String {
+        let eighths = (self.progress * self.width as f64 * 8.0).round() as u64;
+        let full = (eighths / 8) as usize;
+        let partial = (eighths % 8) as usize;
+
+        let mut bar = "█".repeat(full);
+        if partial > 0 {
+            bar.push(EIGHTHS[partial - 1]);
+        }
+        let empty = self.width as usize - bar.chars().count();
+        bar + &" ".repeat(empty)
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for ProgressBar<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Progress(progress) => {
+                    self.set_progress(*progress);
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let mut commands = vec![DrawCommand::TextUnderCursor(self.bar())];
+        if let Some(label) = &self.label {
+            commands.push(DrawCommand::TextUnderCursor(format!(" {label}")));
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let label = self
+            .label
+            .as_ref()
+            .map_or(0, |label| label.chars().count() as u64 + 1);
+        Ok(Some((self.width + label, 1)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProgressBar;
+    use crate::test::render_sync;
+
+    use eyre::Result;
+
+    #[test]
+    fn test_progress_fills_the_bar() -> Result<()> {
+        let mut root = ProgressBar::<()>::new(10).with_label("50%");
+        root.set_progress(0.5);
+        assert_eq!("█████      50%", render_sync(&mut root, 32, 1)?);
+
+        // Sub-cell progress is drawn with partial blocks.
+        root.set_progress(0.55);
+        assert_eq!("█████▌     50%", render_sync(&mut root, 32, 1)?);
+
+        root.set_progress(1.5);
+        assert_eq!(1.0, root.progress());
+        root.set_progress(-1.0);
+        assert_eq!(0.0, root.progress());
+
+        Ok(())
+    }
+}
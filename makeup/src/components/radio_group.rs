@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use unicode_width::UnicodeWidthStr;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::util::selection::Selection;
+use crate::{check_mail, Component, Dimensions, DrawCommand};
+
+/// A set of options of which exactly one is selected, one per line, drawn as
+/// `(•) option` or `( ) option`. While it's focused, the arrow keys move the
+/// selection, wrapping around at either end, and each change sends a message
+/// built from the selected index to the group's target.
+#[derive(Debug)]
+pub struct RadioGroup<Message: std::fmt::Debug + Send + Sync + Clone> {
+    options: Vec<String>,
+    selected: Selection,
+    target: Key,
+    on_change: fn(usize) -> Message,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> RadioGroup<Message> {
+    /// Changing the selection sends `on_change(selected)` to the component
+    /// with the `target` key.
+    pub fn new(options: Vec<String>, target: Key, on_change: fn(usize) -> Message) -> Self {
+        Self {
+            selected: Selection::new(options.len()),
+            options,
+            target,
+            on_change,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// Select the option at `selected` to begin with, instead of the first.
+    pub fn with_selected(mut self, selected: usize) -> Self {
+        self.selected.select(selected);
+        self
+    }
+
+    /// The index of the selected option.
+    pub fn selected(&self) -> usize {
+        self.selected.index()
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for RadioGroup<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        let previous = self.selected.index();
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Up | Keypress::Left) => {
+                    self.selected.prev(true);
+                }
+                MakeupMessage::Keypress(Keypress::Down | Keypress::Right) => {
+                    self.selected.next(true);
+                }
+            }
+        );
+        if self.selected.index() != previous {
+            ctx.sender
+                .send_message(self.target, (self.on_change)(self.selected.index()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let mut commands = vec![];
+        let mut previous_width = 0;
+        for (i, option) in self.options.iter().enumerate() {
+            if i > 0 {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -previous_width,
+                    y: 1,
+                });
+            }
+            let glyph = if i == self.selected.index() {
+                '•'
+            } else {
+                ' '
+            };
+            let line = format!("({glyph}) {option}");
+            previous_width = line.width() as i64;
+            commands.push(DrawCommand::TextUnderCursor(line));
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let width = self
+            .options
+            .iter()
+            .map(|option| option.width())
+            .max()
+            .unwrap_or(0);
+        // +4 for the `(•) ` before each option.
+        Ok(Some((width as u64 + 4, self.options.len() as u64)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::Keypress;
+
+    use super::RadioGroup;
+    use crate::components::{Checkbox, Stack};
+    use crate::test::make_test_ui;
+    use crate::ui::UiControlMessage;
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_only_the_focused_input_reacts() -> Result<()> {
+        let options = vec!["Tabs".into(), "Spaces".into()];
+        let group = RadioGroup::new(options, 0, |_| ());
+        let group_key = group.key();
+        let mut root = Stack::<()>::new(vec![
+            (Box::new(Checkbox::new("Wrap lines", 0, |_| ())), 0, 0),
+            (Box::new(group), 0, 1),
+        ]);
+
+        {
+            let ui = make_test_ui!(&mut root);
+            ui.send_control(UiControlMessage::MoveFocus(group_key))
+                .await;
+            ui.update(&[]).await?;
+            ui.update(&[Keypress::Down, Keypress::Char(' ')]).await?;
+        }
+
+        assert_eq!(
+            vec!["[ ] Wrap lines", "( ) Tabs", "(•) Spaces"],
+            crate::test::render_sync(&mut root, 32, 4)?
+                .lines()
+                .collect::<Vec<_>>()
+        );
+
+        Ok(())
+    }
+}
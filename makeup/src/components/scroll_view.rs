@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use unicode_width::UnicodeWidthStr;
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::render::clip::{clip_commands, ClipRect};
+use crate::{
+    check_mail, Component, Coordinate, Coordinates, Dimension, Dimensions, DrawCommand,
+    RelativeCoordinate,
+};
+
+/// The scrollbar's track.
+const TRACK: char = '░';
+/// The part of the scrollbar showing which of the content is visible.
+const THUMB: char = '█';
+
+/// A `width`x`height` viewport onto a single, possibly much larger, child.
+/// While it's focused, the arrow keys scroll by a cell and Page Up/Page Down
+/// by a page, never past the edges of the child's content, as given by its
+/// [`Component::dimensions`]. An optional scrollbar is drawn in an extra
+/// column to the right.
+///
+/// The child is rendered by the view rather than by the UI, so it's treated
+/// as a leaf: its own children aren't laid out or rendered, and it can't be
+/// focused.
+#[derive(Debug)]
+pub struct ScrollView<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Box<dyn Component<Message = Message>>,
+    viewport: Dimensions,
+    offset: Coordinates,
+    scrollbar: bool,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> ScrollView<Message> {
+    pub fn new(
+        child: Box<dyn Component<Message = Message>>,
+        width: Dimension,
+        height: Dimension,
+    ) -> Self {
+        Self {
+            child,
+            viewport: (width, height),
+            offset: (0, 0),
+            scrollbar: false,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// Draw a scrollbar to the right of the viewport.
+    pub fn with_scrollbar(mut self, scrollbar: bool) -> Self {
+        self.scrollbar = scrollbar;
+        self
+    }
+
+    /// The `(x, y)` of the child's content shown in the top-left of the
+    /// viewport.
+    pub fn offset(&self) -> Coordinates {
+        self.offset
+    }
+
+    /// Scroll so that `(x, y)` of the child's content is in the top-left of
+    /// the viewport, or as close as possible without scrolling past its
+    /// edges.
+    pub fn scroll_to(&mut self, x: Coordinate, y: Coordinate) -> Result<()> {
+        let (content_width, content_height) = self.content_size()?;
+        self.offset = (
+            x.min(content_width.saturating_sub(self.viewport.0)),
+            y.min(content_height.saturating_sub(self.viewport.1)),
+        );
+        Ok(())
+    }
+
+    fn scroll_by(&mut self, x: RelativeCoordinate, y: RelativeCoordinate) -> Result<()> {
+        self.scroll_to(
+            self.offset.0.saturating_add_signed(x),
+            self.offset.1.saturating_add_signed(y),
+        )
+    }
+
+    fn content_size(&self) -> Result<Dimensions> {
+        Ok(self.child.as_ref().dimensions()?.unwrap_or((0, 0)))
+    }
+
+    /// The scrollbar, top to bottom.
+    fn scrollbar(&self) -> Result<Vec<char>> {
+        let height = self.viewport.1;
+        let content_height = self.content_size()?.1;
+        if height == 0 {
+            return Ok(vec![]);
+        }
+        if content_height <= height {
+            return Ok(vec![THUMB; height as usize]);
+        }
+
+        let thumb = (height * height / content_height).max(1);
+        let max_offset = content_height - height;
+        let start = self.offset.1 * (height - thumb) / max_offset;
+        Ok((0..height)
+            .map(|row| {
+                if (start..start + thumb).contains(&row) {
+                    THUMB
+                } else {
+                    TRACK
+                }
+            })
+            .collect())
+    }
+}
+
+/// Rewrite `commands`, clipped in the child's coordinates, to only move the
+/// cursor relatively, starting with it at `cursor`. Returns where the cursor
+/// is left.
+fn to_relative(
+    commands: Vec<DrawCommand>,
+    mut cursor: Coordinates,
+) -> (Vec<DrawCommand>, Coordinates) {
+    let commands = commands
+        .into_iter()
+        .map(|command| match command {
+            DrawCommand::MoveCursorAbsolute { x, y } => {
+                let relative = DrawCommand::MoveCursorRelative {
+                    x: x as i64 - cursor.0 as i64,
+                    y: y as i64 - cursor.1 as i64,
+                };
+                cursor = (x, y);
+                relative
+            }
+            DrawCommand::TextUnderCursor(text) => {
+                cursor.0 += text.width() as Coordinate;
+                DrawCommand::TextUnderCursor(text)
+            }
+            command => command,
+        })
+        .collect();
+
+    (commands, cursor)
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for ScrollView<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        let page = self.viewport.1 as i64;
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Up) => {
+                    self.scroll_by(0, -1)?;
+                }
+                MakeupMessage::Keypress(Keypress::Down) => {
+                    self.scroll_by(0, 1)?;
+                }
+                MakeupMessage::Keypress(Keypress::Left) => {
+                    self.scroll_by(-1, 0)?;
+                }
+                MakeupMessage::Keypress(Keypress::Right) => {
+                    self.scroll_by(1, 0)?;
+                }
+                MakeupMessage::Keypress(Keypress::PageUp) => {
+                    self.scroll_by(0, -page)?;
+                }
+                MakeupMessage::Keypress(Keypress::PageDown) => {
+                    self.scroll_by(0, page)?;
+                }
+            }
+        );
+
+        self.child.update(ctx).await
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let (width, height) = self.viewport;
+        let (_key, commands) = self.child.render(ctx).await?;
+
+        // Clip in the child's coordinates, where the viewport is at the
+        // offset, and then draw relative to the view.
+        let viewport = ClipRect {
+            x: self.offset.0,
+            y: self.offset.1,
+            width,
+            height,
+        };
+        let (mut commands, cursor) =
+            to_relative(clip_commands(commands, (0, 0), &viewport), self.offset);
+
+        if self.scrollbar {
+            commands.push(DrawCommand::MoveCursorRelative {
+                x: (self.offset.0 + width) as i64 - cursor.0 as i64,
+                y: self.offset.1 as i64 - cursor.1 as i64,
+            });
+            for c in self.scrollbar()? {
+                commands.push(DrawCommand::CharUnderCursor(c));
+                commands.push(DrawCommand::MoveCursorRelative { x: -1, y: 1 });
+            }
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let (width, height) = self.viewport;
+        Ok(Some((width + self.scrollbar as u64, height)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::Keypress;
+
+    use super::ScrollView;
+    use crate::component::{MessageSender, UpdateContext};
+    use crate::components::EchoText;
+    use crate::post_office::PostOffice;
+    use crate::test::render_sync;
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[test]
+    fn test_scrolling_moves_the_viewport() -> Result<()> {
+        let text = (0..20)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut root =
+            ScrollView::new(Box::new(EchoText::<()>::new(text)), 7, 5).with_scrollbar(true);
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut scroll = |root: &mut ScrollView<()>, keys: Vec<Keypress>| {
+            for keypress in keys {
+                post_office.send_makeup(
+                    root.key(),
+                    crate::component::MakeupMessage::Keypress(keypress),
+                );
+            }
+            let mut ctx = UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), root.key()),
+                focus: root.key(),
+                dimensions: (100, 100),
+            };
+            futures_executor::block_on(root.update(&mut ctx))
+        };
+
+        assert_eq!(
+            "line 0 █\nline 1 ░\nline 2 ░\nline 3 ░\nline 4 ░",
+            render_sync(&mut root, 16, 8)?
+        );
+
+        scroll(
+            &mut root,
+            vec![Keypress::PageDown, Keypress::Down, Keypress::Down],
+        )?;
+        assert_eq!((0, 7), root.offset());
+        assert_eq!(
+            "line 7 ░\nline 8 █\nline 9 ░\nline 10░\nline 11░",
+            render_sync(&mut root, 16, 8)?
+        );
+
+        // Clamped to the last page, and to the widest line.
+        scroll(
+            &mut root,
+            vec![
+                Keypress::PageDown,
+                Keypress::PageDown,
+                Keypress::Right,
+                Keypress::Right,
+            ],
+        )?;
+        assert_eq!((0, 15), root.offset());
+        scroll(&mut root, vec![Keypress::Right, Keypress::Right])?;
+        assert_eq!((0, 15), root.offset());
+        assert_eq!(
+            "line 15░\nline 16░\nline 17░\nline 18░\nline 19█",
+            render_sync(&mut root, 16, 8)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_the_scrollbar_follows_wide_text() -> Result<()> {
+        let mut root = ScrollView::new(Box::new(EchoText::<()>::new("日本\nab\ncd")), 4, 2)
+            .with_scrollbar(true);
+        assert_eq!("日本█\nab  ░", render_sync(&mut root, 8, 4)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_viewports_have_no_scrollbar() -> Result<()> {
+        let root =
+            ScrollView::new(Box::new(EchoText::<()>::new("a\nb")), 4, 0).with_scrollbar(true);
+        assert!(root.scrollbar()?.is_empty());
+
+        Ok(())
+    }
+}
@@ -7,15 +7,19 @@ use eyre::Result;
 use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
 use crate::{check_mail, Component, Dimensions, DrawCommand};
 
-/// A simple component that renders a spinner with the given text.
+/// A simple component that renders a spinner with the given text. The spinner
+/// is advanced by the UI's global ticker every `interval`, until it's
+/// finished, either by [`Spinner::finish`] or by a
+/// [`MakeupMessage::Finish`].
 #[derive(Debug)]
 pub struct Spinner<Message: std::fmt::Debug + Send + Sync + Clone> {
     text: String,
     spin_steps: Vec<char>,
     step: usize,
     key: Key,
-    started: bool,
     interval: Duration,
+    /// The symbol drawn in place of the animation, once finished.
+    finished: Option<char>,
     _phantom: PhantomData<Message>,
 }
 
@@ -26,11 +30,43 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Spinner<Message> {
             spin_steps,
             step: 0,
             key: crate::component::generate_key(),
-            started: false,
             interval,
+            finished: None,
             _phantom: PhantomData,
         }
     }
+
+    /// A spinner of Braille dots chasing each other around, ex. `⠋`.
+    pub fn dots<S: Into<String>>(text: S, interval: Duration) -> Self {
+        Self::new(
+            text,
+            vec!['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'],
+            interval,
+        )
+    }
+
+    /// A spinner of a gap going around a full Braille cell, ex. `⣾`.
+    pub fn braille<S: Into<String>>(text: S, interval: Duration) -> Self {
+        Self::new(text, vec!['⣾', '⣽', '⣻', '⢿', '⡿', '⣟', '⣯', '⣷'], interval)
+    }
+
+    /// A spinner of a line turning, ex. `|`.
+    pub fn line<S: Into<String>>(text: S, interval: Duration) -> Self {
+        Self::new(text, vec!['-', '\\', '|', '/'], interval)
+    }
+
+    /// Stop spinning, and draw `symbol`, ex. `✓`, with `text` from now on.
+    /// A finished spinner no longer ticks. Finishing it again does nothing.
+    pub fn finish<S: Into<String>>(&mut self, symbol: char, text: S) {
+        if self.finished.is_none() {
+            self.finished = Some(symbol);
+            self.text = text.into();
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.is_some()
+    }
 }
 
 #[async_trait]
@@ -46,24 +82,17 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Spi
     }
 
     async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
-        if !self.started {
-            ctx.sender
-                .send_makeup_message(self.key(), MakeupMessage::TimerTick(self.interval))?;
-            self.started = true;
-        }
-
         check_mail!(
             self,
             ctx,
             match _ {
                 MakeupMessage::TimerTick(_) => {
-                    self.step = (self.step + 1) % self.spin_steps.len();
-                    #[cfg(not(test))]
-                    ctx.sender.send_makeup_message_after(
-                        self.key(),
-                        MakeupMessage::TimerTick(self.interval),
-                        self.interval,
-                    )?;
+                    if self.finished.is_none() {
+                        self.step = (self.step + 1) % self.spin_steps.len();
+                    }
+                }
+                MakeupMessage::Finish(symbol, text) => {
+                    self.finish(*symbol, text.clone());
                 }
             }
         );
@@ -73,7 +102,7 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Spi
 
     async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
         self.batch(vec![
-            DrawCommand::CharUnderCursor(self.spin_steps[self.step]),
+            DrawCommand::CharUnderCursor(self.finished.unwrap_or(self.spin_steps[self.step])),
             DrawCommand::CharUnderCursor(' '),
             DrawCommand::TextUnderCursor(self.text.clone()),
         ])
@@ -87,6 +116,13 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Spi
         // +2 comes from the space and spinner character
         Ok(Some((self.text.len() as u64 + 2, 1)))
     }
+
+    fn tick_interval(&self) -> Option<Duration> {
+        match self.finished {
+            Some(_) => None,
+            None => Some(self.interval),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -176,4 +212,47 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_finished_spinner_stops() -> Result<()> {
+        let interval = Duration::from_millis(1);
+        let mut root = Spinner::<()>::line("working", interval);
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+        post_office.send_makeup(root.key(), MakeupMessage::TimerTick(interval));
+        post_office.send_makeup(root.key(), MakeupMessage::Finish('✓', "done".into()));
+        post_office.send_makeup(root.key(), MakeupMessage::TimerTick(interval));
+        let mut ctx = UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        };
+        root.update(&mut ctx).await?;
+
+        assert!(root.is_finished());
+        assert_eq!(None, root.tick_interval());
+        assert_renders_many!(
+            vec![
+                DrawCommand::CharUnderCursor('✓'),
+                DrawCommand::CharUnderCursor(' '),
+                DrawCommand::TextUnderCursor("done".into()),
+            ],
+            root
+        );
+
+        // Finishing again changes nothing.
+        root.finish('✗', "failed");
+        assert_renders_many!(
+            vec![
+                DrawCommand::CharUnderCursor('✓'),
+                DrawCommand::CharUnderCursor(' '),
+                DrawCommand::TextUnderCursor("done".into()),
+            ],
+            root
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,412 @@
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::{Keypress, Modifiers};
+use taffy::prelude::{Dimension, Size};
+use taffy::style::{FlexDirection, Style};
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::ui::UiControlMessage;
+use crate::{Component, Dimensions};
+
+/// How the two panes of a [`SplitPane`] are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// The first pane is on the left, the second on the right.
+    SideBySide,
+    /// The first pane is on top, the second below it.
+    Stacked,
+}
+
+/// One of the two panes of a [`SplitPane`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    First,
+    Second,
+}
+
+impl Pane {
+    fn other(self) -> Self {
+        match self {
+            Self::First => Self::Second,
+            Self::Second => Self::First,
+        }
+    }
+}
+
+/// Hosts two independent component subtrees in adjacent sub-rectangles, like
+/// a tmux split.
+///
+/// Each pane is its own focus scope: the split remembers which component was
+/// last focused in each pane, and Tab/Shift-Tab switch focus to the other
+/// pane's remembered component. Alt+arrow keys pointing along the split switch
+/// to the pane in that direction. These keys are taken out of the focused
+/// component's mailbox, so components inside a split never see them.
+///
+/// The split should fill the space it is given, so it is best used as the
+/// root component or as a child of a fixed-size container.
+#[derive(Debug)]
+pub struct SplitPane<Message: std::fmt::Debug + Send + Sync + Clone> {
+    panes: Vec<Box<dyn Component<Message = Message>>>,
+    pane_focus: [Key; 2],
+    active: Pane,
+    direction: SplitDirection,
+    /// The size of the first pane, as a percentage of the split.
+    split: Arc<AtomicU16>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> SplitPane<Message> {
+    /// Split the available space evenly between the two given subtrees.
+    pub fn new(
+        first: Box<dyn Component<Message = Message>>,
+        second: Box<dyn Component<Message = Message>>,
+        direction: SplitDirection,
+    ) -> Self {
+        let split = Arc::new(AtomicU16::new(50));
+        let pane_focus = [first.key(), second.key()];
+
+        Self {
+            panes: vec![
+                Box::new(PaneSlot::new(first, Pane::First, direction, split.clone())),
+                Box::new(PaneSlot::new(
+                    second,
+                    Pane::Second,
+                    direction,
+                    split.clone(),
+                )),
+            ],
+            pane_focus,
+            active: Pane::First,
+            direction,
+            split,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    /// Focus the given component when its pane becomes active, instead of the
+    /// pane's root component.
+    pub fn with_pane_focus(mut self, pane: Pane, key: Key) -> Self {
+        self.pane_focus[pane as usize] = key;
+        self
+    }
+
+    /// The size of the first pane, as a percentage of the split.
+    pub fn split(&self) -> u16 {
+        self.split.load(Ordering::Relaxed)
+    }
+
+    /// Move the divider so that the first pane takes up `percent` of the
+    /// split. Clamped to `0..=100`.
+    pub fn set_split(&mut self, percent: u16) {
+        self.split.store(percent.min(100), Ordering::Relaxed);
+    }
+
+    /// Move the divider by `delta` percentage points.
+    pub fn move_divider(&mut self, delta: i16) {
+        let split = (self.split() as i16 + delta).clamp(0, 100);
+        self.set_split(split as u16);
+    }
+
+    /// The pane that currently holds focus.
+    pub fn active_pane(&self) -> Pane {
+        self.active
+    }
+
+    pub fn direction(&self) -> SplitDirection {
+        self.direction
+    }
+
+    /// The pane whose subtree contains the given key, if any.
+    fn pane_containing(&self, key: Key) -> Option<Pane> {
+        [Pane::First, Pane::Second]
+            .into_iter()
+            .find(|pane| contains_key(self.panes[*pane as usize].as_ref(), key))
+    }
+}
+
+/// The pane that `keypress` switches focus to when `active` is focused, if it
+/// switches panes at all.
+fn switch_target(active: Pane, direction: SplitDirection, keypress: &Keypress) -> Option<Pane> {
+    match keypress {
+        Keypress::Tab | Keypress::ShiftTab => Some(active.other()),
+        Keypress::Modified(modifiers, key) if *modifiers == Modifiers::ALT => {
+            match (direction, key.as_ref()) {
+                (SplitDirection::SideBySide, Keypress::Left)
+                | (SplitDirection::Stacked, Keypress::Up) => Some(Pane::First),
+                (SplitDirection::SideBySide, Keypress::Right)
+                | (SplitDirection::Stacked, Keypress::Down) => Some(Pane::Second),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Whether the given component or any of its descendants has the given key.
+fn contains_key<M: std::fmt::Debug + Send + Sync + Clone + 'static>(
+    component: &dyn Component<Message = M>,
+    key: Key,
+) -> bool {
+    component.key() == key
+        || component
+            .children()
+            .map(|children| children.into_iter().any(|child| contains_key(child, key)))
+            .unwrap_or(false)
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for SplitPane<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.panes.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.panes.iter_mut().collect())
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        let focused_pane = self.pane_containing(ctx.focus);
+        if let Some(pane) = focused_pane {
+            // Focus may have been moved into a pane from elsewhere.
+            self.active = pane;
+            self.pane_focus[pane as usize] = ctx.focus;
+        } else if ctx.focus != self.key {
+            return Ok(());
+        }
+
+        let direction = self.direction;
+        let switches = ctx.post_office.take_keypresses(ctx.focus, |keypress| {
+            switch_target(Pane::First, direction, keypress).is_some()
+        });
+        for keypress in switches {
+            if let Some(pane) = switch_target(self.active, direction, &keypress) {
+                self.active = pane;
+            }
+        }
+
+        let target = self.pane_focus[self.active as usize];
+        if target != ctx.focus {
+            ctx.post_office
+                .send_control(UiControlMessage::MoveFocus(target));
+        }
+
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(None)
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            flex_direction: match self.direction {
+                SplitDirection::SideBySide => FlexDirection::Row,
+                SplitDirection::Stacked => FlexDirection::Column,
+            },
+            size: Size {
+                width: Dimension::Percent(1.0),
+                height: Dimension::Percent(1.0),
+            },
+            ..Default::default()
+        })
+    }
+}
+
+/// Sizes one pane of a [`SplitPane`] along the split's main axis.
+#[derive(Debug)]
+struct PaneSlot<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Box<dyn Component<Message = Message>>,
+    pane: Pane,
+    direction: SplitDirection,
+    split: Arc<AtomicU16>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> PaneSlot<Message> {
+    fn new(
+        child: Box<dyn Component<Message = Message>>,
+        pane: Pane,
+        direction: SplitDirection,
+        split: Arc<AtomicU16>,
+    ) -> Self {
+        Self {
+            child,
+            pane,
+            direction,
+            split,
+            key: crate::component::generate_key(),
+        }
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for PaneSlot<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(vec![&self.child])
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(vec![&mut self.child])
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        Ok(None)
+    }
+
+    fn style(&self) -> Option<Style> {
+        let split = self.split.load(Ordering::Relaxed) as f32 / 100.0;
+        let main_axis = match self.pane {
+            Pane::First => Dimension::Percent(split),
+            Pane::Second => Dimension::Percent(1.0 - split),
+        };
+        let size = match self.direction {
+            SplitDirection::SideBySide => Size {
+                width: main_axis,
+                height: Dimension::Percent(1.0),
+            },
+            SplitDirection::Stacked => Size {
+                width: Dimension::Percent(1.0),
+                height: main_axis,
+            },
+        };
+
+        Some(Style {
+            size,
+            flex_shrink: 0.0,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::{Keypress, Modifiers};
+
+    use super::{Pane, SplitDirection, SplitPane};
+    use crate::components::{EchoText, TextInput};
+    use crate::test::{assert_renders_one, make_test_ui, static_text};
+    use crate::Component;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_panes_render_side_by_side() -> Result<()> {
+        let mut root = SplitPane::<()>::new(
+            Box::new(EchoText::new("left")),
+            Box::new(EchoText::new("right")),
+            SplitDirection::SideBySide,
+        );
+
+        let mut ctx = crate::test::fake_render_ctx();
+        let ui = make_test_ui!(&mut root, 20, 4);
+        let actual = ui
+            .render_commands(&mut ctx)
+            .await?
+            .iter()
+            .flat_map(|(_key, cmds)| cmds.clone())
+            .collect();
+        let diff =
+            crate::test::diff::DrawCommandDiff::new(vec![static_text!("left      right")], actual);
+        assert!(!diff.into_visual_diff().await?.is_different());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_panes_render_stacked() -> Result<()> {
+        let mut root = SplitPane::<()>::new(
+            Box::new(EchoText::new("top")),
+            Box::new(EchoText::new("bottom")),
+            SplitDirection::Stacked,
+        );
+        root.set_split(25);
+
+        // The default test UI is 128x128, so the second pane starts at row 32.
+        let expected = format!("top{}bottom", "\n".repeat(32));
+        assert_renders_one!(static_text!(expected), root);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tab_switches_active_pane() -> Result<()> {
+        let left = TextInput::<()>::new("l");
+        let right = TextInput::<()>::new("r");
+        let (left_key, right_key) = (left.key(), right.key());
+        let mut root =
+            SplitPane::<()>::new(Box::new(left), Box::new(right), SplitDirection::SideBySide);
+        assert_eq!(Pane::First, root.active_pane());
+
+        let ui = make_test_ui!(&mut root, 20, 1);
+
+        // The split hands focus to the first pane...
+        ui.update(&[]).await?;
+        ui.update(&[Keypress::Char('a')]).await?;
+        assert_eq!(left_key, ui.focus().await);
+
+        // ...and Tab moves it to the second without reaching the input.
+        ui.update(&[Keypress::Tab]).await?;
+        ui.update(&[Keypress::Char('b')]).await?;
+        assert_eq!(right_key, ui.focus().await);
+
+        // Shift-Tab comes back to the first pane's remembered focus.
+        ui.update(&[Keypress::ShiftTab]).await?;
+        ui.update(&[Keypress::Char('c')]).await?;
+        assert_eq!(left_key, ui.focus().await);
+
+        // Alt+Left points at the pane that's already active, and arrows that
+        // don't point along the split are left alone.
+        let alt = |key| Keypress::Modified(Modifiers::ALT, Box::new(key));
+        ui.update(&[alt(Keypress::Left), alt(Keypress::Down)])
+            .await?;
+        ui.update(&[]).await?;
+        assert_eq!(left_key, ui.focus().await);
+
+        ui.update(&[alt(Keypress::Right)]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(right_key, ui.focus().await);
+        ui.update(&[alt(Keypress::Left)]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(left_key, ui.focus().await);
+
+        let mut ctx = crate::test::fake_render_ctx();
+        let actual = ui
+            .render_commands(&mut ctx)
+            .await?
+            .iter()
+            .flat_map(|(_key, cmds)| cmds.clone())
+            .collect();
+        let diff =
+            crate::test::diff::DrawCommandDiff::new(vec![static_text!("l: ac     r: b")], actual);
+        assert!(!diff.into_visual_diff().await?.is_different());
+
+        Ok(())
+    }
+}
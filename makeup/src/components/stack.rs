@@ -0,0 +1,169 @@
+use async_trait::async_trait;
+use eyre::Result;
+use taffy::prelude::{LengthPercentageAuto, Rect};
+use taffy::style::{Position, Style};
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::{Component, Coordinate, Dimensions};
+
+/// Places its children at explicit coordinates, relative to the stack,
+/// instead of laying them out with flexbox. Later children are drawn over
+/// earlier ones.
+#[derive(Debug)]
+pub struct Stack<Message: std::fmt::Debug + Send + Sync + Clone> {
+    /// Each child, wrapped in the [`Placement`] that positions it.
+    children: Vec<Box<dyn Component<Message = Message>>>,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Stack<Message> {
+    /// Stack the given children, each placed at its `(child, x, y)`.
+    pub fn new(
+        children: Vec<(
+            Box<dyn Component<Message = Message>>,
+            Coordinate,
+            Coordinate,
+        )>,
+    ) -> Self {
+        Self {
+            children: children
+                .into_iter()
+                .map(|(child, x, y)| {
+                    Box::new(Placement {
+                        child: vec![child],
+                        x,
+                        y,
+                        key: crate::component::generate_key(),
+                    }) as Box<dyn Component<Message = Message>>
+                })
+                .collect(),
+            key: crate::component::generate_key(),
+        }
+    }
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Stack<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.children.iter_mut().collect())
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The bounding box of every placed child.
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let mut bounds = (0, 0);
+        for child in &self.children {
+            let (x, y) = child
+                .as_ref()
+                .style()
+                .map(|style| placed_at(&style))
+                .unwrap_or((0, 0));
+            let (width, height) = child.as_ref().dimensions()?.unwrap_or((0, 0));
+            bounds = (bounds.0.max(x + width), bounds.1.max(y + height));
+        }
+
+        Ok(Some(bounds))
+    }
+}
+
+/// The coordinates a [`Placement`]'s style insets it to.
+fn placed_at(style: &Style) -> (Coordinate, Coordinate) {
+    let points = |inset| match inset {
+        LengthPercentageAuto::Points(points) => points as Coordinate,
+        _ => 0,
+    };
+
+    (points(style.inset.left), points(style.inset.top))
+}
+
+/// Holds one child of a [`Stack`] at its coordinates.
+#[derive(Debug)]
+struct Placement<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Vec<Box<dyn Component<Message = Message>>>,
+    x: Coordinate,
+    y: Coordinate,
+    key: Key,
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Placement<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter_mut().collect())
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        self.batch(vec![])
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        self.child[0].as_ref().dimensions()
+    }
+
+    fn style(&self) -> Option<Style> {
+        Some(Style {
+            position: Position::Absolute,
+            inset: Rect {
+                left: LengthPercentageAuto::Points(self.x as f32),
+                top: LengthPercentageAuto::Points(self.y as f32),
+                right: LengthPercentageAuto::Auto,
+                bottom: LengthPercentageAuto::Auto,
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::EchoText;
+    use super::*;
+    use crate::test::{assert_renders_one, static_text};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_children_are_placed_at_their_coordinates() -> Result<()> {
+        let mut root = Stack::<()>::new(vec![
+            (Box::new(EchoText::new("first")), 0, 0),
+            (Box::new(EchoText::new("second")), 5, 2),
+            // Drawn over `first`.
+            (Box::new(EchoText::new("X")), 1, 0),
+        ]);
+
+        assert_eq!(Some((11, 3)), root.dimensions()?);
+        assert_renders_one!(static_text!("fXrst\n\n     second"), root);
+
+        Ok(())
+    }
+}
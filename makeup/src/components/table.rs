@@ -0,0 +1,502 @@
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+use async_trait::async_trait;
+use eyre::Result;
+use makeup_console::Keypress;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
+use crate::util::selection::Selection;
+use crate::{check_mail, Component, Dimensions, DrawCommand, DrawStyle};
+
+/// Between the cells of a row.
+const COLUMN_SEPARATOR: &str = " │ ";
+/// Between the cells of the line under the headers.
+const HEADER_SEPARATOR: &str = "─┼─";
+
+/// How a [`Table`] column's cells are aligned within it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Right,
+    /// Centred, with any odd cell of padding on the right.
+    Center,
+}
+
+/// What a [`Table`] does with cells containing newlines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CellOverflow {
+    /// Draw each line of the cell on its own line, making the row taller.
+    #[default]
+    Wrap,
+    /// Only draw the first line, ending it with an ellipsis.
+    Truncate,
+}
+
+/// Which way a [`Table`] column is sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    /// The indicator drawn after the sorted column's header.
+    fn indicator(&self) -> char {
+        match self {
+            SortOrder::Ascending => '▲',
+            SortOrder::Descending => '▼',
+        }
+    }
+}
+
+/// Compares two cells of a column, for sorting.
+pub type Comparator = fn(&str, &str) -> Ordering;
+
+/// Rows of text laid out in columns, each as wide as its widest cell, under a
+/// line of headers.
+///
+/// ```text
+/// name │ size
+/// ─────┼─────
+/// a    │   10
+/// ```
+///
+/// Rows with fewer cells than there are headers are padded with blank cells.
+///
+/// A sortable table can be focused: Left and Right select a column, and
+/// Return sorts the rows by it, ascending and then descending, with an arrow
+/// after its header. Columns are compared with [`Table::with_comparator`]'s
+/// comparators, or by default numerically if every cell in the column is a
+/// number, and as strings otherwise.
+#[derive(Debug)]
+pub struct Table<Message: std::fmt::Debug + Send + Sync + Clone> {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+    alignments: Vec<Alignment>,
+    overflow: CellOverflow,
+    sortable: bool,
+    comparators: Vec<Option<Comparator>>,
+    /// The column that Return sorts by.
+    column: Selection,
+    sorted_by: Option<(usize, SortOrder)>,
+    key: Key,
+    _phantom: PhantomData<Message>,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Table<Message> {
+    pub fn new(headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        Self {
+            alignments: vec![Alignment::default(); headers.len()],
+            comparators: vec![None; headers.len()],
+            column: Selection::new(headers.len()),
+            headers,
+            rows,
+            overflow: CellOverflow::default(),
+            sortable: false,
+            sorted_by: None,
+            key: crate::component::generate_key(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Align the cells of the column at `column`, headers included.
+    pub fn with_alignment(mut self, column: usize, alignment: Alignment) -> Self {
+        if let Some(slot) = self.alignments.get_mut(column) {
+            *slot = alignment;
+        }
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: CellOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Let the rows be sorted by their columns.
+    pub fn with_sortable(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Compare the cells of the column at `column` with `comparator` when
+    /// sorting by it.
+    pub fn with_comparator(mut self, column: usize, comparator: Comparator) -> Self {
+        if let Some(slot) = self.comparators.get_mut(column) {
+            *slot = Some(comparator);
+        }
+        self
+    }
+
+    /// The rows, in the order they're drawn.
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// The column the rows are sorted by, if any.
+    pub fn sorted_by(&self) -> Option<(usize, SortOrder)> {
+        self.sorted_by
+    }
+
+    /// Sort the rows by the column at `column`. Rows that compare equal keep
+    /// their order.
+    pub fn sort_by(&mut self, column: usize, order: SortOrder) {
+        if column >= self.headers.len() {
+            return;
+        }
+
+        let comparator = self.comparators[column].unwrap_or_else(|| {
+            let numeric = self
+                .rows
+                .iter()
+                .filter_map(|row| row.get(column))
+                .all(|cell| cell.trim().parse::<f64>().is_ok());
+            if numeric {
+                compare_numbers
+            } else {
+                str::cmp
+            }
+        });
+        self.rows.sort_by(|a, b| {
+            let a = a.get(column).map_or("", |cell| cell);
+            let b = b.get(column).map_or("", |cell| cell);
+            match order {
+                SortOrder::Ascending => comparator(a, b),
+                SortOrder::Descending => comparator(b, a),
+            }
+        });
+        self.sorted_by = Some((column, order));
+    }
+
+    /// A header as it's drawn, with room for the sort indicator in sortable
+    /// tables.
+    fn header(&self, column: usize) -> String {
+        let header = &self.headers[column];
+        if !self.sortable {
+            return header.clone();
+        }
+
+        let indicator = match self.sorted_by {
+            Some((sorted, order)) if sorted == column => order.indicator(),
+            _ => ' ',
+        };
+        format!("{header} {indicator}")
+    }
+
+    /// The lines a cell is drawn as.
+    fn cell_lines(&self, cell: &str) -> Vec<String> {
+        match self.overflow {
+            CellOverflow::Wrap => cell.split('\n').map(String::from).collect(),
+            CellOverflow::Truncate => match cell.split_once('\n') {
+                Some((first, _)) => vec![format!("{first}…")],
+                None => vec![cell.to_string()],
+            },
+        }
+    }
+
+    /// Each row, headers first, as its cells' lines, padded with blank cells
+    /// to the number of columns.
+    fn cells(&self) -> Vec<Vec<Vec<String>>> {
+        let headers: Vec<String> = (0..self.headers.len())
+            .map(|column| self.header(column))
+            .collect();
+        std::iter::once(&headers)
+            .chain(&self.rows)
+            .map(|row| {
+                (0..self.headers.len())
+                    .map(|column| self.cell_lines(row.get(column).map_or("", |cell| cell)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn column_widths(cells: &[Vec<Vec<String>>]) -> Vec<usize> {
+        let columns = cells.first().map_or(0, |row| row.len());
+        (0..columns)
+            .map(|column| {
+                cells
+                    .iter()
+                    .flat_map(|row| &row[column])
+                    .map(|line| line.width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// The table's lines of text.
+    fn lines(&self) -> Vec<String> {
+        let cells = self.cells();
+        let widths = Self::column_widths(&cells);
+        Self::lay_out(&cells, &widths, &self.alignments)
+    }
+
+    fn lay_out(
+        cells: &[Vec<Vec<String>>],
+        widths: &[usize],
+        alignments: &[Alignment],
+    ) -> Vec<String> {
+        let mut lines = vec![];
+        for (i, row) in cells.iter().enumerate() {
+            let height = row.iter().map(|lines| lines.len()).max().unwrap_or(1);
+            for line in 0..height {
+                let columns: Vec<String> = row
+                    .iter()
+                    .zip(widths)
+                    .zip(alignments)
+                    .map(|((cell, width), alignment)| {
+                        align(cell.get(line).map_or("", |line| line), *width, *alignment)
+                    })
+                    .collect();
+                lines.push(columns.join(COLUMN_SEPARATOR));
+            }
+
+            if i == 0 {
+                let rules: Vec<String> = widths.iter().map(|width| "─".repeat(*width)).collect();
+                lines.push(rules.join(HEADER_SEPARATOR));
+            }
+        }
+
+        lines
+    }
+
+    /// The span of columns of the first line that the header at `column`
+    /// covers.
+    fn header_span(&self, column: usize) -> (usize, usize) {
+        let widths = Self::column_widths(&self.cells());
+        let start = widths[..column].iter().sum::<usize>() + column * COLUMN_SEPARATOR.width();
+        (start, start + widths[column])
+    }
+}
+
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a = a.trim().parse::<f64>().unwrap_or(f64::NAN);
+    let b = b.trim().parse::<f64>().unwrap_or(f64::NAN);
+    a.total_cmp(&b)
+}
+
+/// Split `text` where its first `width` columns end.
+fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut taken = 0;
+    for (i, c) in text.char_indices() {
+        if taken >= width {
+            return text.split_at(i);
+        }
+        taken += c.width().unwrap_or(0);
+    }
+    (text, "")
+}
+
+/// Pad `text` to `width` columns.
+fn align(text: &str, width: usize, alignment: Alignment) -> String {
+    let padding = width.saturating_sub(text.width());
+    let (left, right) = match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(right))
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for Table<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        None
+    }
+
+    async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        if !self.sortable {
+            return Ok(());
+        }
+
+        check_mail!(
+            self,
+            ctx,
+            match _ {
+                MakeupMessage::Keypress(Keypress::Left) => {
+                    self.column.prev(false);
+                }
+                MakeupMessage::Keypress(Keypress::Right) => {
+                    self.column.next(false);
+                }
+                MakeupMessage::Keypress(Keypress::Return) => {
+                    let column = self.column.index();
+                    let order = match self.sorted_by {
+                        Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                            SortOrder::Descending
+                        }
+                        _ => SortOrder::Ascending,
+                    };
+                    self.sort_by(column, order);
+                }
+            }
+        );
+
+        Ok(())
+    }
+
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        // The selected column's header is highlighted while focused.
+        let highlight = (self.sortable && ctx.focus == self.key && !self.column.is_empty())
+            .then(|| self.header_span(self.column.index()));
+
+        let mut commands = vec![];
+        let mut previous_width = 0;
+        for (i, line) in self.lines().into_iter().enumerate() {
+            if i > 0 {
+                commands.push(DrawCommand::MoveCursorRelative {
+                    x: -previous_width,
+                    y: 1,
+                });
+            }
+            previous_width = line.width() as i64;
+            match highlight {
+                Some((start, end)) if i == 0 => {
+                    let (before, rest) = split_at_width(&line, start);
+                    let (header, after) = split_at_width(rest, end - start);
+                    commands.push(DrawCommand::TextUnderCursor(before.to_string()));
+                    commands.extend(DrawCommand::styled_text(header, DrawStyle::Reverse));
+                    commands.push(DrawCommand::TextUnderCursor(after.to_string()));
+                }
+                _ => commands.push(DrawCommand::TextUnderCursor(line)),
+            }
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let lines = self.lines();
+        let width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+        Ok(Some((width as u64, lines.len() as u64)))
+    }
+
+    fn accepts_focus(&self) -> bool {
+        self.sortable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_console::Keypress;
+
+    use super::{Alignment, CellOverflow, SortOrder, Table};
+    use crate::component::{MakeupMessage, MessageSender, UpdateContext};
+    use crate::post_office::PostOffice;
+    use crate::test::render_sync;
+    use crate::Component;
+
+    use eyre::Result;
+
+    fn strings(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|cell| cell.to_string()).collect()
+    }
+
+    #[test]
+    fn test_columns_are_sized_and_aligned() -> Result<()> {
+        let mut root = Table::<()>::new(
+            strings(&["name", "size", "kind"]),
+            vec![strings(&["a", "10", "file"]), strings(&["longer", "2048"])],
+        )
+        .with_alignment(1, Alignment::Right)
+        .with_alignment(2, Alignment::Center);
+
+        assert_eq!(Some((20, 4)), root.dimensions()?);
+        assert_eq!(
+            [
+                "name   │ size │ kind",
+                "───────┼──────┼─────",
+                "a      │   10 │ file",
+                "longer │ 2048 │",
+            ]
+            .join("\n"),
+            render_sync(&mut root, 32, 8)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_newlines_wrap_or_truncate() -> Result<()> {
+        let rows = || vec![strings(&["one\ntwo", "x"])];
+
+        let mut root = Table::<()>::new(strings(&["a", "b"]), rows());
+        assert_eq!(
+            "a   │ b\n────┼──\none │ x\ntwo │",
+            render_sync(&mut root, 32, 8)?
+        );
+
+        let mut root =
+            Table::<()>::new(strings(&["a", "b"]), rows()).with_overflow(CellOverflow::Truncate);
+        assert_eq!(
+            "a    │ b\n─────┼──\none… │ x",
+            render_sync(&mut root, 32, 8)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorting_by_a_numeric_column() -> Result<()> {
+        let mut root = Table::<()>::new(
+            strings(&["name", "size"]),
+            vec![
+                strings(&["b", "10"]),
+                strings(&["a", "9"]),
+                strings(&["c", "100"]),
+            ],
+        )
+        .with_sortable(true)
+        .with_alignment(1, Alignment::Right);
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut press = |root: &mut Table<()>, keys: Vec<Keypress>| {
+            for keypress in keys {
+                post_office.send_makeup(root.key(), MakeupMessage::Keypress(keypress));
+            }
+            let mut ctx = UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), root.key()),
+                focus: root.key(),
+                dimensions: (100, 100),
+            };
+            futures_executor::block_on(root.update(&mut ctx))
+        };
+
+        // Numerically, rather than "10" < "100" < "9".
+        press(&mut root, vec![Keypress::Right, Keypress::Return])?;
+        assert_eq!(Some((1, SortOrder::Ascending)), root.sorted_by());
+        assert_eq!(
+            [
+                "name   │ size ▲",
+                "───────┼───────",
+                "a      │      9",
+                "b      │     10",
+                "c      │    100",
+            ]
+            .join("\n"),
+            render_sync(&mut root, 32, 8)?
+        );
+
+        press(&mut root, vec![Keypress::Return])?;
+        assert_eq!(Some((1, SortOrder::Descending)), root.sorted_by());
+        let names: Vec<&str> = root.rows().iter().map(|row| row[0].as_str()).collect();
+        assert_eq!(vec!["c", "b", "a"], names);
+        assert!(render_sync(&mut root, 32, 8)?.starts_with("name   │ size ▼"));
+
+        Ok(())
+    }
+}
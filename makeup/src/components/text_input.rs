@@ -1,19 +1,32 @@
 use std::marker::PhantomData;
 
 use async_trait::async_trait;
+use derivative::Derivative;
 use eyre::Result;
 use makeup_ansi::LineEraseMode;
 use makeup_console::Keypress;
+use unicode_width::UnicodeWidthStr;
 
 use crate::component::{DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext};
 use crate::{check_mail, Component, Dimensions, DrawCommand};
 
-/// A simple component that renders text under the cursor.
-#[derive(Debug)]
+/// A simple component that renders text under the cursor. Text is inserted
+/// at the cursor, which Left/Right/Home/End move through the buffer.
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct TextInput<Message: std::fmt::Debug + Send + Sync + Clone> {
     prompt: String,
     key: Key,
     buffer: String,
+    /// Where text is inserted, as a char index into `buffer`.
+    cursor: usize,
+    /// Drawn in place of each character of the buffer, if set.
+    mask: Option<char>,
+    /// The most chars the buffer holds. `0` is unlimited.
+    max_len: usize,
+    /// Chars it rejects aren't entered.
+    #[derivative(Debug = "ignore")]
+    validator: Option<Box<dyn Fn(char) -> bool + Send + Sync>>,
     input_offset: Option<i32>,
     _phantom: PhantomData<Message>,
 }
@@ -23,11 +36,91 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> TextInput<Message> {
         Self {
             prompt: prompt.into(),
             buffer: String::new(),
+            cursor: 0,
+            mask: None,
+            max_len: 0,
+            validator: None,
             key: crate::component::generate_key(),
             input_offset: None,
             _phantom: PhantomData,
         }
     }
+
+    /// An input that draws `mask` in place of each character typed, ex. for
+    /// passwords.
+    pub fn masked<S: Into<String>>(prompt: S, mask: char) -> Self {
+        Self {
+            mask: Some(mask),
+            ..Self::new(prompt)
+        }
+    }
+
+    /// Accept at most `max_len` chars, ignoring any typed past that. `0`, the
+    /// default, is unlimited.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Ignore typed chars that `validator` returns `false` for, ex.
+    /// `char::is_ascii_digit` for numeric input. Deleting is unaffected.
+    pub fn with_validator(mut self, validator: Box<dyn Fn(char) -> bool + Send + Sync>) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// The text entered so far. Never masked.
+    pub fn value(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Where text is inserted, as a char index into the buffer.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// `text` from the buffer as it's drawn, ie. masked if need be.
+    fn displayed(&self, text: &str) -> String {
+        match self.mask {
+            Some(mask) => mask.to_string().repeat(text.chars().count()),
+            None => text.to_string(),
+        }
+    }
+
+    /// The byte index of the char at `cursor`, or the end of the buffer.
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.buffer
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.buffer.len(), |(i, _)| i)
+    }
+
+    /// Insert `text` at the cursor, less any chars that are rejected or over
+    /// the maximum length.
+    fn insert(&mut self, text: &str) {
+        for c in text.chars() {
+            if self.max_len > 0 && self.buffer.chars().count() >= self.max_len {
+                break;
+            }
+            if self.validator.as_ref().is_some_and(|valid| !valid(c)) {
+                continue;
+            }
+
+            let index = self.byte_index(self.cursor);
+            self.buffer.insert(index, c);
+            self.cursor += 1;
+        }
+    }
+
+    /// Remove the char at `cursor`, if there is one.
+    fn remove(&mut self, cursor: usize) -> bool {
+        if cursor >= self.buffer.chars().count() {
+            return false;
+        }
+        let index = self.byte_index(cursor);
+        self.buffer.remove(index);
+        true
+    }
 }
 
 #[async_trait]
@@ -49,11 +142,34 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for TextInput<Mes
             ctx,
             match _ {
                 MakeupMessage::Keypress(Keypress::Char(c)) => {
-                    self.buffer.push(*c);
+                    self.insert(&c.to_string());
+                }
+                MakeupMessage::Keypress(Keypress::Paste(text)) => {
+                    // The input is a single line, so pasted lines are joined.
+                    self.insert(&text.replace("\r\n", " ").replace(['\r', '\n'], " "));
                 }
                 MakeupMessage::Keypress(Keypress::Backspace) => {
-                    self.buffer.pop();
-                    offset -= 1;
+                    if self.cursor > 0 && self.remove(self.cursor - 1) {
+                        self.cursor -= 1;
+                        offset -= 1;
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Delete) => {
+                    if self.remove(self.cursor) {
+                        offset -= 1;
+                    }
+                }
+                MakeupMessage::Keypress(Keypress::Left) => {
+                    self.cursor = self.cursor.saturating_sub(1);
+                }
+                MakeupMessage::Keypress(Keypress::Right) => {
+                    self.cursor = (self.cursor + 1).min(self.buffer.chars().count());
+                }
+                MakeupMessage::Keypress(Keypress::Home) => {
+                    self.cursor = 0;
+                }
+                MakeupMessage::Keypress(Keypress::End) => {
+                    self.cursor = self.buffer.chars().count();
                 }
             }
         );
@@ -66,26 +182,33 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for TextInput<Mes
         Ok(())
     }
 
-    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
-        match self.input_offset {
-            Some(offset) if offset < 0 => {
-                // If we have a negative offset, erase to the end of the line.
-                self.batch(vec![
-                    DrawCommand::TextUnderCursor(self.prompt.clone()),
-                    DrawCommand::CharUnderCursor(':'),
-                    DrawCommand::CharUnderCursor(' '),
-                    DrawCommand::TextUnderCursor(self.buffer.clone()),
-                    // TODO: This should probably just replace the characters with whitespace...
-                    DrawCommand::EraseCurrentLine(LineEraseMode::FromCursorToEnd),
-                ])
-            }
-            _ => self.batch(vec![
-                DrawCommand::TextUnderCursor(self.prompt.clone()),
-                DrawCommand::CharUnderCursor(':'),
-                DrawCommand::CharUnderCursor(' '),
-                DrawCommand::TextUnderCursor(self.buffer.clone()),
-            ]),
+    async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let mut commands = vec![
+            DrawCommand::TextUnderCursor(self.prompt.clone()),
+            DrawCommand::CharUnderCursor(':'),
+            DrawCommand::CharUnderCursor(' '),
+            DrawCommand::TextUnderCursor(self.displayed(&self.buffer)),
+        ];
+        if matches!(self.input_offset, Some(offset) if offset < 0) {
+            // If we have a negative offset, erase to the end of the line.
+            // TODO: This should probably just replace the characters with whitespace...
+            commands.push(DrawCommand::EraseCurrentLine(
+                LineEraseMode::FromCursorToEnd,
+            ));
+        }
+
+        // Leave the terminal cursor at the insertion point while focused.
+        if ctx.focus == self.key {
+            let after_cursor = self
+                .displayed(&self.buffer[self.byte_index(self.cursor)..])
+                .width();
+            commands.push(DrawCommand::SetFinalCursor {
+                x: -(after_cursor as i64),
+                y: 0,
+            });
         }
+
+        self.batch(commands)
     }
 
     fn key(&self) -> Key {
@@ -95,7 +218,7 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for TextInput<Mes
     fn dimensions(&self) -> Result<Option<Dimensions>> {
         // +2 comes from the `: ` between the prompt and the buffer.
         Ok(Some((
-            self.prompt.len() as u64 + 2 + self.buffer.len() as u64,
+            self.prompt.len() as u64 + 2 + self.displayed(&self.buffer).width() as u64,
             1,
         )))
     }
@@ -108,9 +231,10 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> Component for TextInput<Mes
 #[cfg(test)]
 mod tests {
     use super::TextInput;
-    use crate::component::{MessageSender, UpdateContext};
+    use crate::component::{MessageSender, RenderContext, UpdateContext};
     use crate::post_office::PostOffice;
-    use crate::test::assert_renders_many;
+    use crate::render::{MemoryRenderer, Renderer};
+    use crate::test::{assert_renders_many, fake_render_ctx, make_test_ui};
     use crate::{Component, DrawCommand};
 
     use eyre::Result;
@@ -157,4 +281,215 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_editing_mid_string() -> Result<()> {
+        let mut root = TextInput::<()>::new("edit");
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut type_keys = |root: &mut TextInput<()>, keys: Vec<Keypress>| {
+            for keypress in keys {
+                post_office.send_makeup(
+                    root.key(),
+                    crate::component::MakeupMessage::Keypress(keypress),
+                );
+            }
+            let mut ctx = UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), root.key()),
+                focus: root.key(),
+                dimensions: (100, 100),
+            };
+            futures_executor::block_on(root.update(&mut ctx))
+        };
+
+        type_keys(
+            &mut root,
+            vec![
+                Keypress::Char('a'),
+                Keypress::Char('b'),
+                Keypress::Char('c'),
+                Keypress::Left,
+                Keypress::Left,
+                Keypress::Char('X'),
+            ],
+        )?;
+        assert_eq!("aXbc", root.value());
+        assert_eq!(2, root.cursor());
+
+        // The terminal cursor is left after the X.
+        let ctx = RenderContext {
+            focus: root.key(),
+            ..fake_render_ctx()
+        };
+        let (_key, commands) = root.render(&ctx).await?;
+        assert_eq!(
+            Some(&DrawCommand::SetFinalCursor { x: -2, y: 0 }),
+            commands.last()
+        );
+
+        type_keys(&mut root, vec![Keypress::Delete, Keypress::Backspace])?;
+        assert_eq!("ac", root.value());
+        assert_eq!(1, root.cursor());
+
+        // Moving past either end does nothing.
+        type_keys(
+            &mut root,
+            vec![Keypress::Home, Keypress::Left, Keypress::Backspace],
+        )?;
+        assert_eq!(("ac", 0), (root.value(), root.cursor()));
+        type_keys(
+            &mut root,
+            vec![Keypress::End, Keypress::Right, Keypress::Delete],
+        )?;
+        assert_eq!(("ac", 2), (root.value(), root.cursor()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_masked_input_hides_the_value() -> Result<()> {
+        let mut root = TextInput::<()>::masked("password", '*');
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        for c in "secret".chars() {
+            post_office.send_makeup(
+                root.key(),
+                crate::component::MakeupMessage::Keypress(Keypress::Char(c)),
+            );
+        }
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        assert_renders_many!(
+            vec![
+                DrawCommand::TextUnderCursor("password".into()),
+                DrawCommand::CharUnderCursor(':'),
+                DrawCommand::CharUnderCursor(' '),
+                DrawCommand::TextUnderCursor("******".into())
+            ],
+            root
+        );
+        assert_eq!("secret", root.value());
+        assert_eq!(Some((16, 1)), root.dimensions()?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_max_len_and_validator_reject_input() -> Result<()> {
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut type_keys = |root: &mut TextInput<()>, keys: Vec<Keypress>| {
+            for keypress in keys {
+                post_office.send_makeup(
+                    root.key(),
+                    crate::component::MakeupMessage::Keypress(keypress),
+                );
+            }
+            let mut ctx = UpdateContext {
+                post_office: &mut post_office,
+                sender: MessageSender::new(tx.clone(), root.key()),
+                focus: root.key(),
+                dimensions: (100, 100),
+            };
+            futures_executor::block_on(root.update(&mut ctx))
+        };
+
+        let mut numeric =
+            TextInput::<()>::new("pin").with_validator(Box::new(|c| c.is_ascii_digit()));
+        type_keys(
+            &mut numeric,
+            vec![
+                Keypress::Char('1'),
+                Keypress::Char('a'),
+                Keypress::Paste("2b3".into()),
+            ],
+        )?;
+        assert_eq!("123", numeric.value());
+        type_keys(&mut numeric, vec![Keypress::Backspace])?;
+        assert_eq!("12", numeric.value());
+
+        let mut capped = TextInput::<()>::new("code").with_max_len(3);
+        type_keys(
+            &mut capped,
+            vec![
+                Keypress::Char('a'),
+                Keypress::Paste("bcd".into()),
+                Keypress::Char('e'),
+            ],
+        )?;
+        assert_eq!("abc", capped.value());
+        type_keys(&mut capped, vec![Keypress::Backspace, Keypress::Char('z')])?;
+        assert_eq!("abz", capped.value());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pasted_lines_are_joined() -> Result<()> {
+        let mut root = TextInput::<()>::new("name");
+        let mut post_office = PostOffice::<()>::new();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        post_office.send_makeup(
+            root.key(),
+            crate::component::MakeupMessage::Keypress(Keypress::Paste(
+                "one\ntwo\r\nthree\r".into(),
+            )),
+        );
+        let mut ctx = UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx, root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        };
+        root.update(&mut ctx).await?;
+
+        assert_eq!("one two three ", root.value());
+        assert_eq!(Some((20, 1)), root.dimensions()?);
+        assert_eq!(
+            "name: one two three",
+            crate::test::render_sync(&mut root, 32, 4)?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_final_cursor_is_left_after_the_typed_text() -> Result<()> {
+        let mut root = TextInput::<()>::new("name");
+        let mut post_office = PostOffice::<()>::new();
+        for c in "bob".chars() {
+            post_office.send_makeup(
+                root.key(),
+                crate::component::MakeupMessage::Keypress(Keypress::Char(c)),
+            );
+        }
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        root.update(&mut UpdateContext {
+            post_office: &mut post_office,
+            sender: MessageSender::new(tx.clone(), root.key()),
+            focus: root.key(),
+            dimensions: (100, 100),
+        })
+        .await?;
+
+        let ui = make_test_ui!(&mut root, 20, 2);
+        ui.render_once().await?;
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .expect("renderer is a MemoryRenderer");
+        // After "name: bob".
+        assert_eq!(Some((9, 0)), renderer.final_cursor());
+        assert_eq!((9, 0), renderer.cursor());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use eyre::Result;
+use taffy::prelude::{LengthPercentage, Rect};
+use taffy::style::Style;
+
+use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
+use crate::components::border::{draw_border, BorderStyle};
+use crate::{Component, Dimensions, DrawCommand, DrawStyle};
+
+/// How opaque a [`Window`]'s shadow is, out of 255.
+const SHADOW_ALPHA: u8 = 128;
+
+/// A titled frame around a child, with a drop shadow offset one cell to the
+/// bottom-right, for dialogs and the like.
+///
+/// The shadow darkens the background of the cells it covers. Renderers that
+/// can't blend draw it as solid black; see [`DrawStyle::ColouredAlpha`].
+#[derive(Debug)]
+pub struct Window<Message: std::fmt::Debug + Send + Sync + Clone> {
+    child: Vec<Box<dyn Component<Message = Message>>>,
+    title: String,
+    border_style: BorderStyle,
+    shadow: bool,
+    key: Key,
+}
+
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Window<Message> {
+    pub fn new<S: Into<String>>(title: S, child: Box<dyn Component<Message = Message>>) -> Self {
+        Self {
+            child: vec![child],
+            title: title.into(),
+            border_style: BorderStyle::default(),
+            shadow: true,
+            key: crate::component::generate_key(),
+        }
+    }
+
+    pub fn with_border_style(mut self, border_style: BorderStyle) -> Self {
+        self.border_style = border_style;
+        self
+    }
+
+    /// Set whether the window casts a shadow. Enabled by default.
+    pub fn with_shadow(mut self, shadow: bool) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// The size of the frame, without the shadow.
+    fn frame_dimensions(&self) -> Result<Dimensions> {
+        let (width, height) = self.child[0].as_ref().dimensions()?.unwrap_or((0, 0));
+        Ok((width + 2, height + 2))
+    }
+}
+
+/// Draw a shadow along the right and bottom of a `width`x`height` frame,
+/// starting with the cursor just right of the frame's bottom-right corner.
+fn draw_shadow(width: u64, height: u64) -> Vec<DrawCommand> {
+    let mut commands = vec![
+        DrawCommand::Style(DrawStyle::ColouredAlpha {
+            foreground: None,
+            background: Some(0x000000),
+            alpha: SHADOW_ALPHA,
+        }),
+        // Below the top-right corner.
+        DrawCommand::MoveCursorRelative {
+            x: 0,
+            y: 2 - height as i64,
+        },
+    ];
+    for _ in 1..height {
+        commands.push(DrawCommand::CharUnderCursor(' '));
+        commands.push(DrawCommand::MoveCursorRelative { x: -1, y: 1 });
+    }
+    commands.push(DrawCommand::MoveCursorRelative {
+        x: 1 - width as i64,
+        y: 0,
+    });
+    commands.push(DrawCommand::TextUnderCursor(" ".repeat(width as usize)));
+    commands.push(DrawCommand::Style(DrawStyle::Default));
+
+    commands
+}
+
+#[async_trait]
+impl<Message: std::fmt::Debug + Send + Sync + Clone + 'static> Component for Window<Message> {
+    type Message = Message;
+
+    fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter().collect())
+    }
+
+    fn children_mut(&mut self) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+        Some(self.child.iter_mut().collect())
+    }
+
+    async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+        let (width, height) = self.frame_dimensions()?;
+        let mut commands = draw_border(self.border_style, Some(&self.title), width, height);
+        if self.shadow {
+            commands.extend(draw_shadow(width, height));
+        }
+
+        self.batch(commands)
+    }
+
+    fn key(&self) -> Key {
+        self.key
+    }
+
+    /// The child's dimensions, plus the frame and shadow.
+    fn dimensions(&self) -> Result<Option<Dimensions>> {
+        let (width, height) = self.frame_dimensions()?;
+        let shadow = self.shadow as u64;
+        Ok(Some((width + shadow, height + shadow)))
+    }
+
+    fn style(&self) -> Option<Style> {
+        let shadow = self.shadow as u8 as f32;
+        Some(Style {
+            padding: Rect {
+                left: LengthPercentage::Points(1.0),
+                right: LengthPercentage::Points(1.0 + shadow),
+                top: LengthPercentage::Points(1.0),
+                bottom: LengthPercentage::Points(1.0 + shadow),
+            },
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Window;
+    use crate::components::EchoText;
+    use crate::render::MemoryRenderer;
+    use crate::test::make_test_ui;
+    use crate::{Component, DrawStyle};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_window_has_a_frame_and_shadow() -> Result<()> {
+        let mut root = Window::new("hi", Box::new(EchoText::<()>::new("henol")));
+        assert_eq!(Some((8, 4)), root.dimensions()?);
+
+        let ui = make_test_ui!(&mut root, 16, 5);
+        ui.render_once().await?;
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .unwrap();
+
+        assert_eq!(
+            vec!["┌hi───┐ ", "│henol│ ", "└─────┘ ", "        "],
+            renderer.read_region(0, 0, 8, 4)?
+        );
+
+        let shadow = Some(DrawStyle::Coloured {
+            foreground: None,
+            background: Some(0x000000),
+        });
+        let mut cells: Vec<_> = renderer
+            .grid()
+            .iter()
+            .filter(|(_, (_, style))| *style == shadow)
+            .map(|(position, _)| *position)
+            .collect();
+        cells.sort_by_key(|(x, y)| (*y, *x));
+        // Down the right, then along the bottom, offset by a cell.
+        let mut expected = vec![(7, 1), (7, 2)];
+        expected.extend((1..=7).map(|x| (x, 3)));
+        assert_eq!(expected, cells);
+
+        Ok(())
+    }
+}
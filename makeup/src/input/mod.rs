@@ -16,4 +16,14 @@ pub enum InputFrame {
 #[async_trait]
 pub trait Input: std::fmt::Debug + Send + Sync + Clone {
     async fn next_frame(&self) -> Result<InputFrame>;
+
+    /// Whether or not [`Input::next_frame`] would produce a frame right now,
+    /// without consuming it or waiting. Lets makeup's input be driven from an
+    /// external event loop.
+    ///
+    /// Wakeups may be spurious, so callers must still handle
+    /// [`InputFrame::Empty`]. By default, always returns `true`.
+    async fn poll(&self) -> Result<bool> {
+        Ok(true)
+    }
 }
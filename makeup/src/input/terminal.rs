@@ -43,4 +43,8 @@ impl Input for TerminalInput {
             }
         }
     }
+
+    async fn poll(&self) -> Result<bool> {
+        makeup_console::has_input(&self.state).await
+    }
 }
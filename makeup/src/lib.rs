@@ -13,6 +13,7 @@ pub mod components;
 pub mod input;
 pub mod post_office;
 pub mod render;
+pub mod state;
 pub mod test;
 pub mod ui;
 pub mod util;
@@ -20,7 +21,7 @@ pub mod util;
 pub use component::Component;
 pub use input::Input;
 pub use render::Renderer;
-pub use ui::MUI;
+pub use ui::{MuiBuilder, MUI};
 
 pub use makeup_ansi::prelude::*;
 
@@ -35,7 +36,11 @@ pub type RelativeCoordinate = i64;
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
 pub enum DrawCommand {
     /// Draw text under the cursor, advancing the cursor by `text.len()`
-    /// characters.
+    /// characters. Newlines move the cursor to the start of the next line.
+    /// When the component drawing the text declares its
+    /// [dimensions](Component::dimensions), or is inside one that
+    /// [clips its children](Component::clips_children), its commands are
+    /// clipped, and newlines return to the component's left edge instead.
     TextUnderCursor(String),
 
     /// Draw a single character under the cursor, advancing the cursor by 1
@@ -47,11 +52,15 @@ pub enum DrawCommand {
     EraseCurrentLine(LineEraseMode),
 
     /// Move the cursor absolutely. This is almost certainly ***NOT*** what you
-    /// want.
+    /// want: the coordinates are in the whole character grid, not relative to
+    /// the component, so the component draws in the same place however it's
+    /// laid out.
     MoveCursorAbsolute { x: Coordinate, y: Coordinate },
 
     /// Move the cursor relative to its current position. You probably want
-    /// this and not `MoveCursorAbsolute`.
+    /// this and not `MoveCursorAbsolute`. Each component's commands start
+    /// with the cursor at its laid-out location, so relative moves are
+    /// effectively relative to the component's origin.
     MoveCursorRelative {
         x: RelativeCoordinate,
         y: RelativeCoordinate,
@@ -63,8 +72,61 @@ pub enum DrawCommand {
     /// Show the cursor.
     ShowCursor,
 
+    /// Leave the terminal's cursor `x`, `y` away from the cursor's current
+    /// position once the whole frame has been drawn, ex. so that a text
+    /// input's blinking cursor sits where text is typed. If more than one
+    /// component sets it, the last one drawn wins.
+    SetFinalCursor {
+        x: RelativeCoordinate,
+        y: RelativeCoordinate,
+    },
+
     /// Style the text that follows this command.
     Style(DrawStyle),
+
+    /// Enter (`true`) or leave (`false`) the alternate screen, clearing it on
+    /// the way in. Like the other terminal modes, it's only switched when it
+    /// changes, so components can draw it every frame.
+    AlternateScreen(bool),
+
+    /// Turn bracketed paste on or off, so that pastes arrive as a single
+    /// [`Keypress::Paste`](makeup_console::Keypress::Paste).
+    BracketedPaste(bool),
+
+    /// Turn mouse reporting on or off.
+    MouseReporting(bool),
+}
+
+impl DrawCommand {
+    /// Draw `text` in `style`, then go back to the default style, so that the
+    /// style doesn't leak into whatever's drawn next.
+    ///
+    /// ```
+    /// # use makeup::{DrawCommand, DrawStyle};
+    /// let mut commands = vec![DrawCommand::from("status: ")];
+    /// commands.extend(DrawCommand::styled_text("ok", DrawStyle::Bold));
+    /// ```
+    pub fn styled_text<S: Into<String>>(text: S, style: DrawStyle) -> [DrawCommand; 3] {
+        [
+            DrawCommand::Style(style),
+            DrawCommand::TextUnderCursor(text.into()),
+            DrawCommand::Style(DrawStyle::Default),
+        ]
+    }
+}
+
+impl From<&str> for DrawCommand {
+    /// Draw `text` under the cursor.
+    fn from(text: &str) -> Self {
+        DrawCommand::TextUnderCursor(text.to_string())
+    }
+}
+
+impl From<char> for DrawCommand {
+    /// Draw `c` under the cursor.
+    fn from(c: char) -> Self {
+        DrawCommand::CharUnderCursor(c)
+    }
 }
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
@@ -75,6 +137,22 @@ pub enum DrawStyle {
         background: Option<u32>,
     },
 
+    /// Draw the text with the given foreground and background colours,
+    /// blending the background over whatever was already drawn underneath.
+    /// `alpha` is the background's opacity, from `0` (invisible) to `255`
+    /// (opaque). Without a background, the one underneath is kept. Ex. a
+    /// modal's backdrop can dim the UI behind it with a half-transparent
+    /// black.
+    ///
+    /// Only renderers that know what's underneath, ie. the
+    /// [`MemoryRenderer`](render::MemoryRenderer), can blend; others draw the
+    /// colours as if they were opaque.
+    ColouredAlpha {
+        foreground: Option<u32>,
+        background: Option<u32>,
+        alpha: u8,
+    },
+
     /// Draw the text with the given foreground colour, and the default
     /// background colour.
     Foreground(u32),
@@ -110,6 +188,30 @@ pub enum DrawStyle {
 
     /// Draw the text with an underline.
     Underline,
+
+    /// Draw the text with the foreground and background colours swapped.
+    Reverse,
+
+    /// Draw the text with any of the given colours and attributes at once,
+    /// ex. in bold red, which the other variants can't do, as each replaces
+    /// the last.
+    Compound {
+        foreground: Option<u32>,
+        background: Option<u32>,
+        bold: bool,
+        italic: bool,
+        underline: bool,
+    },
+}
+
+impl TryFrom<&str> for DrawStyle {
+    type Error = eyre::Report;
+
+    /// Parse a hex colour string into a foreground colour. See
+    /// [`parse_hex_colour`] for the accepted formats.
+    fn try_from(colour: &str) -> eyre::Result<Self> {
+        parse_hex_colour(colour).map(DrawStyle::Foreground)
+    }
 }
 
 #[cfg(test)]
@@ -117,7 +219,7 @@ mod tests {
     use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
     use crate::input::TerminalInput;
     use crate::render::MemoryRenderer;
-    use crate::{Component, Dimensions, DrawCommand, MUI};
+    use crate::{Component, Dimensions, DrawCommand, DrawStyle, MUI};
 
     use async_trait::async_trait;
     use eyre::Result;
@@ -183,4 +285,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_draw_commands_from_text() {
+        let commands: Vec<DrawCommand> = vec!["hello".into(), ' '.into()];
+        assert_eq!(
+            vec![
+                DrawCommand::TextUnderCursor("hello".into()),
+                DrawCommand::CharUnderCursor(' '),
+            ],
+            commands
+        );
+    }
+
+    #[test]
+    fn test_styled_text_resets_the_style() {
+        assert_eq!(
+            [
+                DrawCommand::Style(DrawStyle::Foreground(0xDB325C)),
+                DrawCommand::TextUnderCursor("henol".into()),
+                DrawCommand::Style(DrawStyle::Default),
+            ],
+            DrawCommand::styled_text("henol", DrawStyle::Foreground(0xDB325C))
+        );
+    }
+
+    #[test]
+    fn test_draw_style_from_hex_colour() -> Result<()> {
+        assert_eq!(
+            DrawStyle::Foreground(0xDB325C),
+            DrawStyle::try_from("#DB325C")?
+        );
+        assert!(DrawStyle::try_from("not a colour").is_err());
+
+        Ok(())
+    }
 }
@@ -1,17 +1,70 @@
 use std::collections::HashMap;
 
 use either::Either;
+use makeup_console::Keypress;
 
-use crate::component::{Key, Mailbox, MakeupMessage, RawComponentMessage};
+use crate::component::{Key, Mailbox, MakeupMessage, RawComponentMessage, SharedSubscriptions};
 use crate::ui::UiControlMessage;
 use crate::Component;
 
+/// The kinds of message a component can be sent, for ordering delivery. See
+/// [`PostOffice::set_delivery_order`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageKind {
+    /// Focus changes and resizes.
+    Lifecycle,
+    /// Keypresses.
+    Input,
+    /// Timer ticks.
+    Timer,
+    /// The UI's own messages, and any other makeup messages, ex. text and
+    /// progress updates.
+    User,
+}
+
+impl MessageKind {
+    fn of<Message>(message: &RawComponentMessage<Message>) -> Self {
+        match message {
+            Either::Left(_) => MessageKind::User,
+            Either::Right(
+                MakeupMessage::Focus | MakeupMessage::Blur | MakeupMessage::Resize(_),
+            ) => MessageKind::Lifecycle,
+            Either::Right(MakeupMessage::Keypress(_)) => MessageKind::Input,
+            Either::Right(MakeupMessage::TimerTick(_)) => MessageKind::Timer,
+            Either::Right(_) => MessageKind::User,
+        }
+    }
+}
+
+/// The order messages are delivered in within a frame, by default: focus
+/// changes and resizes, then keypresses, then timer ticks, then everything
+/// else.
+pub const DEFAULT_DELIVERY_ORDER: [MessageKind; 4] = [
+    MessageKind::Lifecycle,
+    MessageKind::Input,
+    MessageKind::Timer,
+    MessageKind::User,
+];
+
 /// The post office is used for managing component mailboxes, including sending
 /// and receiving messages.
+///
+/// Mailboxes are first in, first out: messages of the same [`MessageKind`]
+/// are always delivered in the order they were sent. Before each update, the
+/// UI sorts mailboxes by kind; see [`PostOffice::set_delivery_order`].
 #[derive(Debug)]
 pub struct PostOffice<Message: std::fmt::Debug + Send + Sync + Clone> {
     boxes: HashMap<Key, Vec<RawComponentMessage<Message>>>,
     ui_mailbox: Vec<UiControlMessage>,
+    delivery_order: Vec<MessageKind>,
+    /// Keypresses that components didn't handle, to be offered to their
+    /// ancestors.
+    unhandled: HashMap<Key, Vec<Keypress>>,
+    /// How many messages have been sent to components since the UI last
+    /// checked.
+    sent: usize,
+    /// Timer subscriptions made through the UI's senders.
+    subscriptions: SharedSubscriptions,
 }
 
 impl<Message: std::fmt::Debug + Send + Sync + Clone> PostOffice<Message> {
@@ -21,11 +74,40 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> PostOffice<Message> {
         Self {
             boxes: HashMap::new(),
             ui_mailbox: vec![],
+            delivery_order: DEFAULT_DELIVERY_ORDER.to_vec(),
+            unhandled: HashMap::new(),
+            sent: 0,
+            subscriptions: SharedSubscriptions::default(),
+        }
+    }
+
+    /// Set the order that the kinds of message are delivered in within a
+    /// frame, instead of [`DEFAULT_DELIVERY_ORDER`]. Kinds that aren't listed
+    /// are delivered last. Messages of the same kind are always delivered in
+    /// the order they were sent.
+    pub fn set_delivery_order(&mut self, order: Vec<MessageKind>) {
+        self.delivery_order = order;
+    }
+
+    /// Sort every mailbox into the delivery order. Done by the UI before each
+    /// update pass.
+    pub(crate) fn sort_mailboxes(&mut self) {
+        let rank = |message: &RawComponentMessage<Message>| {
+            let kind = MessageKind::of(message);
+            self.delivery_order
+                .iter()
+                .position(|k| *k == kind)
+                .unwrap_or(self.delivery_order.len())
+        };
+        for mailbox in self.boxes.values_mut() {
+            // Stable, so messages of the same kind stay in the order sent.
+            mailbox.sort_by_key(rank);
         }
     }
 
     /// Send a message to the mailbox with the given key.
     pub fn send(&mut self, key: Key, message: Message) {
+        self.sent += 1;
         self.boxes
             .entry(key)
             .or_default()
@@ -34,12 +116,33 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> PostOffice<Message> {
 
     /// Send an internal (makeup) message to the mailbox with the given key.
     pub fn send_makeup(&mut self, key: Key, message: MakeupMessage) {
+        self.sent += 1;
         self.boxes
             .entry(key)
             .or_default()
             .push(Either::Right(message));
     }
 
+    /// Send a message to every mailbox. Mailboxes are only created when
+    /// they're first sent to, so components that have never been sent
+    /// anything aren't reached; [`MUI::broadcast`](crate::MUI::broadcast)
+    /// reaches every component in the tree.
+    pub fn broadcast(&mut self, message: Message) {
+        for mailbox in self.boxes.values_mut() {
+            self.sent += 1;
+            mailbox.push(Either::Left(message.clone()));
+        }
+    }
+
+    /// Send an internal (makeup) message to every mailbox. See
+    /// [`PostOffice::broadcast`].
+    pub fn broadcast_makeup(&mut self, message: MakeupMessage) {
+        for mailbox in self.boxes.values_mut() {
+            self.sent += 1;
+            mailbox.push(Either::Right(message.clone()));
+        }
+    }
+
     /// Send a UI control message to the UI message queue.
     pub fn send_control(&mut self, message: UiControlMessage) {
         self.ui_mailbox.push(message);
@@ -53,6 +156,31 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> PostOffice<Message> {
         self.boxes.get(&component.key())
     }
 
+    /// The messages in the mailbox for the given component, oldest first,
+    /// without removing them.
+    pub fn peek_mailbox<C: Component<Message = Message> + ?Sized>(
+        &self,
+        component: &C,
+    ) -> &[RawComponentMessage<Message>] {
+        self.boxes
+            .get(&component.key())
+            .map_or(&[], |mailbox| mailbox.as_slice())
+    }
+
+    /// Remove and return every message in the mailbox for the given
+    /// component, oldest first. Unlike [`check_mail!`](crate::check_mail),
+    /// lets a component handle some messages and send the rest on, or back
+    /// to itself.
+    pub fn drain_mailbox<C: Component<Message = Message> + ?Sized>(
+        &mut self,
+        component: &C,
+    ) -> Vec<RawComponentMessage<Message>> {
+        self.boxes
+            .get_mut(&component.key())
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
     #[inline]
     #[doc(hidden)]
     pub fn clear_mailbox<C: Component<Message = Message> + ?Sized>(&mut self, component: &C) {
@@ -61,6 +189,69 @@ impl<Message: std::fmt::Debug + Send + Sync + Clone> PostOffice<Message> {
         }
     }
 
+    /// Remove and return every keypress matching `filter` from the mailbox
+    /// with the given key, leaving all other messages in place. Lets a
+    /// component handle keys on behalf of a focused descendant.
+    pub fn take_keypresses<F: Fn(&Keypress) -> bool>(
+        &mut self,
+        key: Key,
+        filter: F,
+    ) -> Vec<Keypress> {
+        let mut taken = vec![];
+        if let Some(mailbox) = self.boxes.get_mut(&key) {
+            mailbox.retain(|message| match message {
+                Either::Right(MakeupMessage::Keypress(keypress)) if filter(keypress) => {
+                    taken.push(keypress.clone());
+                    false
+                }
+                _ => true,
+            });
+        }
+
+        taken
+    }
+
+    /// Record keypresses that the given component didn't handle, so that the
+    /// UI can offer them to its ancestors. Done by
+    /// [`check_mail!`](crate::check_mail).
+    #[inline]
+    #[doc(hidden)]
+    pub fn pass_keypresses<C: Component<Message = Message> + ?Sized>(
+        &mut self,
+        component: &C,
+        keypresses: Vec<Keypress>,
+    ) {
+        if !keypresses.is_empty() {
+            self.unhandled
+                .entry(component.key())
+                .or_default()
+                .extend(keypresses);
+        }
+    }
+
+    /// Remove and return the keypresses that the component with the given
+    /// key didn't handle.
+    pub(crate) fn take_unhandled_keypresses(&mut self, key: Key) -> Vec<Keypress> {
+        self.unhandled.remove(&key).unwrap_or_default()
+    }
+
+    /// Forget every unhandled keypress, once they've had their chance to
+    /// bubble up.
+    pub(crate) fn clear_unhandled_keypresses(&mut self) {
+        self.unhandled.clear();
+    }
+
+    /// How many messages have been sent to components since the last call.
+    /// Lets the UI tell whether anything might have changed.
+    pub(crate) fn take_sent_count(&mut self) -> usize {
+        std::mem::take(&mut self.sent)
+    }
+
+    /// The timer subscriptions made through senders handed out by the UI.
+    pub(crate) fn subscriptions(&self) -> SharedSubscriptions {
+        self.subscriptions.clone()
+    }
+
     /// Get the UI message queue.
     pub(crate) fn ui_mailbox(&self) -> &Vec<UiControlMessage> {
         &self.ui_mailbox
@@ -86,6 +277,9 @@ macro_rules! mail_pattern {
 
 /// Check the mail for the current component. Clears mailboxes after reading.
 ///
+/// Keypresses that none of the arms match are passed on to the component's
+/// ancestors, nearest first, until one of them matches it.
+///
 /// Example:
 ///
 /// ```ignore
@@ -102,12 +296,86 @@ macro_rules! mail_pattern {
 macro_rules! check_mail {
     ( $component:expr, $ctx:expr, $arms:expr ) => {{
         if let Some(mailbox) = $ctx.post_office.mailbox($component) {
+            let mut unhandled = vec![];
             for message in mailbox.iter() {
                 use either::Either;
-                (makeup_macros::__do_check_mail_arms!($arms))
+                let handled: bool = makeup_macros::__do_check_mail_arms!($arms);
+                if !handled {
+                    if let Either::Right($crate::component::MakeupMessage::Keypress(keypress)) =
+                        message
+                    {
+                        unhandled.push(keypress.clone());
+                    }
+                }
             }
 
             $ctx.post_office.clear_mailbox($component);
+            $ctx.post_office.pass_keypresses($component, unhandled);
         }
     }};
 }
+
+#[cfg(test)]
+mod tests {
+    use either::Either;
+    use makeup_console::Keypress;
+
+    use super::PostOffice;
+    use crate::component::MakeupMessage;
+    use crate::components::EchoText;
+    use crate::Component;
+
+    #[test]
+    fn test_mailboxes_are_drained_in_send_order() {
+        let component = EchoText::<u32>::new("henol");
+        let mut post_office = PostOffice::<u32>::new();
+        post_office.send(component.key(), 1);
+        post_office.send_makeup(
+            component.key(),
+            MakeupMessage::Keypress(Keypress::Char('a')),
+        );
+        post_office.send(component.key(), 2);
+
+        // Peeking doesn't consume anything.
+        assert_eq!(3, post_office.peek_mailbox(&component).len());
+        assert_eq!(3, post_office.peek_mailbox(&component).len());
+
+        let messages = post_office.drain_mailbox(&component);
+        assert!(matches!(
+            messages[..],
+            [
+                Either::Left(1),
+                Either::Right(MakeupMessage::Keypress(Keypress::Char('a'))),
+                Either::Left(2),
+            ]
+        ));
+        assert!(post_office.peek_mailbox(&component).is_empty());
+        assert!(post_office.drain_mailbox(&component).is_empty());
+    }
+
+    #[test]
+    fn test_broadcasts_reach_every_mailbox() {
+        let first = EchoText::<u32>::new("henol");
+        let second = EchoText::<u32>::new("world");
+        let mut post_office = PostOffice::<u32>::new();
+        post_office.send(first.key(), 1);
+        post_office.send(second.key(), 2);
+        post_office.drain_mailbox(&first);
+
+        post_office.broadcast(3);
+        post_office.broadcast_makeup(MakeupMessage::TextUpdate("hi".into()));
+
+        assert!(matches!(
+            post_office.drain_mailbox(&first)[..],
+            [Either::Left(3), Either::Right(MakeupMessage::TextUpdate(_))]
+        ));
+        assert!(matches!(
+            post_office.drain_mailbox(&second)[..],
+            [
+                Either::Left(2),
+                Either::Left(3),
+                Either::Right(MakeupMessage::TextUpdate(_))
+            ]
+        ));
+    }
+}
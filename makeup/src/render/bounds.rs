@@ -0,0 +1,76 @@
+use unicode_width::UnicodeWidthChar;
+
+use crate::{Coordinates, Dimensions, DrawCommand, RelativeCoordinate};
+
+/// Estimate how much of the grid `commands` draw on, when they start drawing
+/// with the cursor at `origin`: the size of the area from `origin` to the
+/// furthest cell drawn right of and below it.
+pub(crate) fn rendered_size(commands: &[DrawCommand], origin: Coordinates) -> Dimensions {
+    let origin = (
+        origin.0 as RelativeCoordinate,
+        origin.1 as RelativeCoordinate,
+    );
+    let mut cursor = origin;
+    let mut size = (0, 0);
+    let mut draw = |cursor: &mut (RelativeCoordinate, RelativeCoordinate), c: char| {
+        if c == '\n' {
+            *cursor = (origin.0, cursor.1 + 1);
+            return;
+        }
+        let width = c.width().unwrap_or(1) as RelativeCoordinate;
+        if width == 0 {
+            return;
+        }
+        cursor.0 += width;
+        size = (
+            size.0.max(cursor.0 - origin.0),
+            size.1.max(cursor.1 - origin.1 + 1),
+        );
+    };
+
+    for command in commands {
+        match command {
+            DrawCommand::TextUnderCursor(text) => {
+                for c in text.chars() {
+                    draw(&mut cursor, c);
+                }
+            }
+            DrawCommand::CharUnderCursor(c) => draw(&mut cursor, *c),
+            DrawCommand::MoveCursorRelative { x, y } => {
+                cursor = (cursor.0 + x, cursor.1 + y);
+            }
+            DrawCommand::MoveCursorAbsolute { x, y } => {
+                cursor = (*x as RelativeCoordinate, *y as RelativeCoordinate);
+            }
+            DrawCommand::EraseCurrentLine(_)
+            | DrawCommand::HideCursor
+            | DrawCommand::ShowCursor
+            | DrawCommand::SetFinalCursor { .. }
+            | DrawCommand::AlternateScreen(_)
+            | DrawCommand::BracketedPaste(_)
+            | DrawCommand::MouseReporting(_)
+            | DrawCommand::Style(_) => {}
+        }
+    }
+
+    (size.0.max(0) as u64, size.1.max(0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rendered_size;
+    use crate::DrawCommand;
+
+    #[test]
+    fn test_rendered_size() {
+        let commands = vec![
+            DrawCommand::TextUnderCursor("abc".into()),
+            DrawCommand::MoveCursorRelative { x: -3, y: 1 },
+            DrawCommand::TextUnderCursor("日本語".into()),
+            DrawCommand::MoveCursorAbsolute { x: 4, y: 7 },
+            DrawCommand::CharUnderCursor('!'),
+        ];
+        assert_eq!((6, 3), rendered_size(&commands, (2, 5)));
+        assert_eq!((0, 0), rendered_size(&[], (2, 5)));
+    }
+}
@@ -0,0 +1,288 @@
+use makeup_ansi::LineEraseMode;
+use unicode_width::UnicodeWidthChar;
+
+use crate::render::memory::DEFAULT_TAB_WIDTH;
+use crate::{Coordinate, Coordinates, Dimension, DrawCommand, RelativeCoordinate};
+
+/// A rectangle of the character grid that draw commands are clipped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ClipRect {
+    pub x: Coordinate,
+    pub y: Coordinate,
+    pub width: Dimension,
+    pub height: Dimension,
+}
+
+impl ClipRect {
+    /// The overlap of the two rectangles. May be empty.
+    pub fn intersect(&self, other: &ClipRect) -> ClipRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        ClipRect {
+            x,
+            y,
+            width: right.saturating_sub(x),
+            height: bottom.saturating_sub(y),
+        }
+    }
+
+    fn contains(&self, x: RelativeCoordinate, y: RelativeCoordinate) -> bool {
+        x >= self.x as RelativeCoordinate
+            && y >= self.y as RelativeCoordinate
+            && x < (self.x + self.width) as RelativeCoordinate
+            && y < (self.y + self.height) as RelativeCoordinate
+    }
+}
+
+/// Rewrite `commands`, which start drawing with the cursor at `cursor`, so
+/// that nothing is drawn outside of `rect`. Visible text is drawn with
+/// absolute cursor moves, and line erases only erase inside `rect`. Newlines
+/// in text return to the column the commands started drawing in, ie. the
+/// left edge of the component drawing them. Characters take up their display
+/// width, and tabs are drawn as spaces up to the next tab stop; wide
+/// characters that don't entirely fit are left out.
+pub(crate) fn clip_commands(
+    commands: Vec<DrawCommand>,
+    cursor: Coordinates,
+    rect: &ClipRect,
+) -> Vec<DrawCommand> {
+    let mut clipper = Clipper {
+        rect,
+        cursor: (
+            cursor.0 as RelativeCoordinate,
+            cursor.1 as RelativeCoordinate,
+        ),
+        real_cursor: cursor,
+        left: cursor.0 as RelativeCoordinate,
+        run: String::new(),
+        run_width: 0,
+        out: vec![],
+    };
+
+    for command in commands {
+        match command {
+            DrawCommand::TextUnderCursor(text) => {
+                for c in text.chars() {
+                    clipper.put(c);
+                }
+            }
+            DrawCommand::CharUnderCursor(c) => clipper.put(c),
+            DrawCommand::MoveCursorRelative { x, y } => {
+                clipper.flush();
+                clipper.cursor = (clipper.cursor.0 + x, clipper.cursor.1 + y);
+            }
+            DrawCommand::MoveCursorAbsolute { x, y } => {
+                clipper.flush();
+                clipper.cursor = (x as RelativeCoordinate, y as RelativeCoordinate);
+            }
+            DrawCommand::EraseCurrentLine(mode) => {
+                clipper.flush();
+                clipper.erase(mode);
+            }
+            DrawCommand::SetFinalCursor { x, y } => {
+                clipper.flush();
+                // Relative to where the cursor would be, not where it is.
+                let (real_x, real_y) = clipper.real_cursor;
+                clipper.out.push(DrawCommand::SetFinalCursor {
+                    x: clipper.cursor.0 + x - real_x as RelativeCoordinate,
+                    y: clipper.cursor.1 + y - real_y as RelativeCoordinate,
+                });
+            }
+            command @ (DrawCommand::HideCursor
+            | DrawCommand::ShowCursor
+            | DrawCommand::Style(_)
+            | DrawCommand::AlternateScreen(_)
+            | DrawCommand::BracketedPaste(_)
+            | DrawCommand::MouseReporting(_)) => {
+                clipper.flush();
+                clipper.out.push(command);
+            }
+        }
+    }
+    clipper.flush();
+
+    clipper.out
+}
+
+struct Clipper<'a> {
+    rect: &'a ClipRect,
+    /// Where the cursor would be if nothing were clipped.
+    cursor: (RelativeCoordinate, RelativeCoordinate),
+    /// Where the cursor actually is after the clipped commands.
+    real_cursor: Coordinates,
+    /// The column that newlines return to.
+    left: RelativeCoordinate,
+    /// Visible text not yet written to `out`, ending at `cursor`.
+    run: String,
+    /// How many columns `run` takes up.
+    run_width: RelativeCoordinate,
+    out: Vec<DrawCommand>,
+}
+
+impl<'a> Clipper<'a> {
+    fn put(&mut self, c: char) {
+        if c == '\n' {
+            self.flush();
+            self.cursor = (self.left, self.cursor.1 + 1);
+            return;
+        }
+        if c == '\t' {
+            // Tab stops are on absolute columns, like the renderers'.
+            let tab_width = DEFAULT_TAB_WIDTH as RelativeCoordinate;
+            let stop = (self.cursor.0.div_euclid(tab_width) + 1) * tab_width;
+            while self.cursor.0 < stop {
+                self.put(' ');
+            }
+            return;
+        }
+
+        let width = c.width().unwrap_or(1) as RelativeCoordinate;
+        if width == 0 {
+            // Combining marks go with the visible character before them.
+            if !self.run.is_empty() {
+                self.run.push(c);
+            }
+            return;
+        }
+
+        let (x, y) = self.cursor;
+        if self.rect.contains(x, y) && self.rect.contains(x + width - 1, y) {
+            self.run.push(c);
+            self.run_width += width;
+        } else {
+            self.flush();
+        }
+        self.cursor.0 += width;
+    }
+
+    /// Write out the pending run of visible text.
+    fn flush(&mut self) {
+        if self.run.is_empty() {
+            return;
+        }
+
+        let run = std::mem::take(&mut self.run);
+        let width = std::mem::take(&mut self.run_width);
+        let start = (
+            (self.cursor.0 - width) as Coordinate,
+            self.cursor.1 as Coordinate,
+        );
+        self.move_to(start);
+        self.out.push(DrawCommand::TextUnderCursor(run));
+        self.real_cursor = (start.0 + width as Coordinate, start.1);
+    }
+
+    fn move_to(&mut self, (x, y): Coordinates) {
+        if self.real_cursor != (x, y) {
+            self.out.push(DrawCommand::MoveCursorAbsolute { x, y });
+            self.real_cursor = (x, y);
+        }
+    }
+
+    fn erase(&mut self, mode: LineEraseMode) {
+        let (x, y) = self.cursor;
+        if y < self.rect.y as RelativeCoordinate
+            || y >= (self.rect.y + self.rect.height) as RelativeCoordinate
+        {
+            return;
+        }
+
+        let left = self.rect.x as RelativeCoordinate;
+        let right = (self.rect.x + self.rect.width) as RelativeCoordinate;
+        let (start, end) = match mode {
+            LineEraseMode::FromCursorToEnd => (x.max(left), right),
+            LineEraseMode::FromCursorToStart => (left, (x + 1).min(right)),
+            LineEraseMode::All => (left, right),
+        };
+        if start >= end {
+            return;
+        }
+
+        self.move_to((start as Coordinate, y as Coordinate));
+        self.out.push(DrawCommand::TextUnderCursor(
+            " ".repeat((end - start) as usize),
+        ));
+        self.real_cursor = (end as Coordinate, y as Coordinate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use makeup_ansi::LineEraseMode;
+
+    use super::{clip_commands, ClipRect};
+    use crate::DrawCommand;
+
+    #[test]
+    fn test_text_is_clipped_to_rect() {
+        let rect = ClipRect {
+            x: 2,
+            y: 0,
+            width: 3,
+            height: 2,
+        };
+
+        assert_eq!(
+            vec![
+                DrawCommand::MoveCursorAbsolute { x: 2, y: 0 },
+                DrawCommand::TextUnderCursor("cde".into()),
+                DrawCommand::MoveCursorAbsolute { x: 2, y: 1 },
+                DrawCommand::TextUnderCursor("cd".into()),
+                DrawCommand::MoveCursorAbsolute { x: 3, y: 1 },
+                DrawCommand::TextUnderCursor("  ".into()),
+            ],
+            clip_commands(
+                vec![
+                    DrawCommand::TextUnderCursor("abcdefg\nabcd".into()),
+                    DrawCommand::MoveCursorRelative { x: -1, y: 0 },
+                    DrawCommand::EraseCurrentLine(LineEraseMode::FromCursorToEnd),
+                    DrawCommand::TextUnderCursor("\nhidden".into()),
+                ],
+                (0, 0),
+                &rect,
+            )
+        );
+    }
+
+    #[test]
+    fn test_wide_text_is_clipped_by_width() {
+        let rect = ClipRect {
+            x: 0,
+            y: 0,
+            width: 3,
+            height: 1,
+        };
+
+        // 本 doesn't fit entirely, and 語 not at all.
+        assert_eq!(
+            vec![DrawCommand::TextUnderCursor("日".into())],
+            clip_commands(
+                vec![DrawCommand::TextUnderCursor("日本語".into())],
+                (0, 0),
+                &rect,
+            )
+        );
+
+        let rect = ClipRect { width: 8, ..rect };
+        assert_eq!(
+            vec![
+                DrawCommand::TextUnderCursor("日本".into()),
+                DrawCommand::MoveCursorAbsolute { x: 5, y: 0 },
+                DrawCommand::TextUnderCursor("x  ".into()),
+            ],
+            clip_commands(
+                vec![
+                    DrawCommand::TextUnderCursor("日本".into()),
+                    DrawCommand::MoveCursorRelative { x: 1, y: 0 },
+                    DrawCommand::CharUnderCursor('x'),
+                    DrawCommand::TextUnderCursor("\tb".into()),
+                ],
+                (0, 0),
+                &rect,
+            )
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::AsFd;
+use std::sync::{Arc, Mutex, Once};
+
+use eyre::Result;
+use makeup_ansi::{Ansi, CursorVisibility};
+use makeup_console::SavedAttributes;
+
+/// What a [`TerminalGuard`] puts back.
+#[derive(Debug, Clone)]
+struct Restore {
+    alternate_screen: bool,
+    attributes: Option<SavedAttributes>,
+    /// The terminal to restore, for the panic hook.
+    terminal: Option<Arc<File>>,
+}
+
+impl Restore {
+    fn apply(&self, writer: &mut dyn Write) -> Result<()> {
+        if self.alternate_screen {
+            write!(writer, "{}", Ansi::AlternateScreen(false))?;
+        }
+        write!(
+            writer,
+            "{}",
+            Ansi::CursorVisibility(CursorVisibility::Visible)
+        )?;
+        writer.flush()?;
+        if let Some(attributes) = &self.attributes {
+            attributes.restore()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes to a terminal that the panic hook can also write to.
+struct SharedTerminal(Arc<File>);
+
+impl Write for SharedTerminal {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        (&*self.0).write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        (&*self.0).flush()
+    }
+}
+
+/// What the live guard, if any, would restore, for the panic hook.
+static LIVE_GUARD: Mutex<Option<Restore>> = Mutex::new(None);
+static PANIC_HOOK: Once = Once::new();
+
+/// Puts the terminal back the way it was when the guard is dropped: leaves
+/// the alternate screen, if it was entered, shows the cursor, and restores
+/// the terminal's attributes, ex. taking it out of raw mode. Installed by
+/// [`MUI::render`](crate::MUI::render) when rendering to the alternate
+/// screen, so that a program that panics or returns early doesn't leave the
+/// user's shell garbled.
+///
+/// While a guard is alive, a panic restores the terminal before the panic
+/// message is printed, so that the message isn't lost with the alternate
+/// screen.
+#[derive(derivative::Derivative)]
+#[derivative(Debug)]
+pub struct TerminalGuard {
+    #[derivative(Debug = "ignore")]
+    writer: Box<dyn Write + Send>,
+    restore: Restore,
+    /// Whether panics restore the terminal.
+    armed: bool,
+    restored: bool,
+}
+
+impl TerminalGuard {
+    /// Guard the terminal on stdout, saving its attributes as they are now.
+    /// `alternate_screen` is whether the alternate screen has been entered.
+    pub fn new(alternate_screen: bool) -> Self {
+        match std::io::stdout().as_fd().try_clone_to_owned() {
+            Ok(stdout) => Self::for_terminal(File::from(stdout), alternate_screen),
+            Err(_) => Self::with_writer(Box::new(std::io::stdout()), alternate_screen),
+        }
+    }
+
+    /// Guard `terminal`, ex. the one a renderer draws on. See
+    /// [`Renderer::terminal`](crate::Renderer::terminal).
+    pub(crate) fn for_terminal(terminal: File, alternate_screen: bool) -> Self {
+        use std::os::fd::AsRawFd;
+
+        let terminal = Arc::new(terminal);
+        let mut guard =
+            Self::with_writer(Box::new(SharedTerminal(terminal.clone())), alternate_screen);
+        // Not being a terminal, ex. when piped, just means there's nothing
+        // to restore. The guard holds the terminal open for as long as the
+        // attributes are kept.
+        guard.restore.attributes = SavedAttributes::save(Some(terminal.as_raw_fd())).ok();
+        guard.restore.terminal = Some(terminal);
+        guard.arm();
+        guard
+    }
+
+    /// Write the restoring sequences to `writer` instead of stdout. The
+    /// terminal's attributes aren't touched.
+    pub fn with_writer(writer: Box<dyn Write + Send>, alternate_screen: bool) -> Self {
+        Self {
+            writer,
+            restore: Restore {
+                alternate_screen,
+                attributes: None,
+                terminal: None,
+            },
+            armed: false,
+            restored: false,
+        }
+    }
+
+    /// Restore the terminal now, rather than on drop. Only the first call
+    /// does anything.
+    pub fn restore(&mut self) -> Result<()> {
+        if self.restored {
+            return Ok(());
+        }
+        self.restored = true;
+        if self.armed {
+            LIVE_GUARD.lock().unwrap_or_else(|e| e.into_inner()).take();
+        }
+
+        self.restore.apply(&mut self.writer)
+    }
+
+    /// Have panics restore the terminal while this guard is alive.
+    fn arm(&mut self) {
+        self.armed = true;
+        *LIVE_GUARD.lock().unwrap_or_else(|e| e.into_inner()) = Some(self.restore.clone());
+        PANIC_HOOK.call_once(|| {
+            let previous = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |info| {
+                let live = LIVE_GUARD.lock().unwrap_or_else(|e| e.into_inner()).take();
+                if let Some(restore) = live {
+                    if let Some(terminal) = &restore.terminal {
+                        let _ = restore.apply(&mut SharedTerminal(terminal.clone()));
+                    }
+                }
+                previous(info);
+            }));
+        });
+    }
+}
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TerminalGuard;
+    use crate::test::SharedBuffer;
+    use crate::Ansi;
+
+    use eyre::Result;
+
+    #[test]
+    fn test_dropping_the_guard_restores_the_terminal() -> Result<()> {
+        let buffer = SharedBuffer::default();
+        let guard = TerminalGuard::with_writer(Box::new(buffer.clone()), true);
+        assert!(buffer.0.lock().unwrap().is_empty());
+        drop(guard);
+
+        let output = buffer.contents();
+        assert_eq!(
+            format!(
+                "\x1b[?1049l{}",
+                Ansi::CursorVisibility(makeup_ansi::CursorVisibility::Visible)
+            ),
+            output
+        );
+
+        // Restoring early doesn't restore again on drop.
+        let buffer = SharedBuffer::default();
+        let mut guard = TerminalGuard::with_writer(Box::new(buffer.clone()), false);
+        guard.restore()?;
+        drop(guard);
+        let output = buffer.contents();
+        assert_eq!("\x1b[?25h", output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_guards_restore_the_terminal_they_guard() -> Result<()> {
+        use std::fs::File;
+        use std::io::Read;
+        use std::os::fd::OwnedFd;
+        use std::os::unix::net::UnixStream;
+
+        let (terminal, mut other_end) = UnixStream::pair()?;
+        let terminal = File::from(OwnedFd::from(terminal));
+        drop(TerminalGuard::for_terminal(terminal, true));
+
+        let mut output = String::new();
+        other_end.read_to_string(&mut output)?;
+        assert_eq!("\x1b[?1049l\x1b[?25h", output);
+
+        Ok(())
+    }
+}
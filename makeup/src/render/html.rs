@@ -0,0 +1,230 @@
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::component::DrawCommandBatch;
+use crate::{Coordinate, Coordinates, Dimension, Dimensions, DrawStyle, RelativeCoordinate};
+
+use super::memory::WIDE_CONTINUATION;
+use super::{MemoryRenderer, Renderer};
+
+/// A [`Renderer`] that renders frames to self-contained HTML snippets, ex.
+/// for embedding coloured examples in documentation. Each frame is a `<pre>`
+/// with styled text in inline-styled `<span>`s. See [`HtmlRenderer::html`].
+#[derive(Debug)]
+pub struct HtmlRenderer {
+    memory_renderer: MemoryRenderer,
+}
+
+impl HtmlRenderer {
+    pub fn new(width: Dimension, height: Dimension) -> Self {
+        Self {
+            memory_renderer: MemoryRenderer::new(width, height),
+        }
+    }
+
+    /// The last committed frame as HTML. Trailing unstyled whitespace is
+    /// trimmed from each line.
+    pub fn html(&self) -> String {
+        let grid = self.memory_renderer.grid();
+        let height = grid.keys().map(|(_, y)| y + 1).max().unwrap_or(0);
+
+        let mut lines = vec![];
+        for y in 0..height {
+            let width = grid
+                .iter()
+                .filter(|((_, row), (c, style))| *row == y && (*c != ' ' || style.is_some()))
+                .map(|((x, _), _)| x + 1)
+                .max()
+                .unwrap_or(0);
+
+            let mut line = String::new();
+            let mut run = String::new();
+            let mut run_style = None;
+            for x in 0..width {
+                let (c, style) = *grid.get(&(x, y)).unwrap_or(&(' ', None));
+                if c == WIDE_CONTINUATION {
+                    continue;
+                }
+                if style != run_style {
+                    push_run(&mut line, &run, run_style);
+                    run.clear();
+                    run_style = style;
+                }
+                run.push(c);
+            }
+            push_run(&mut line, &run, run_style);
+
+            lines.push(line);
+        }
+
+        format!("<pre>{}</pre>", lines.join("\n"))
+    }
+}
+
+/// Append `text` to `out`, escaped, and in a `<span>` if it's styled.
+fn push_run(out: &mut String, text: &str, style: Option<DrawStyle>) {
+    if text.is_empty() {
+        return;
+    }
+
+    let escaped = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;");
+    match style.map(css) {
+        Some(css) if !css.is_empty() => {
+            out.push_str(&format!("<span style=\"{css}\">{escaped}</span>"));
+        }
+        _ => out.push_str(&escaped),
+    }
+}
+
+/// The inline CSS for a [`DrawStyle`].
+fn css(style: DrawStyle) -> String {
+    let colour = |property: &str, colour: Option<u32>| {
+        colour.map(|colour| format!("{property}:#{colour:06x};"))
+    };
+
+    let declarations = match style {
+        // Cells are blended as they're drawn into the grid.
+        DrawStyle::Coloured {
+            foreground,
+            background,
+        }
+        | DrawStyle::ColouredAlpha {
+            foreground,
+            background,
+            ..
+        } => vec![
+            colour("color", foreground),
+            colour("background", background),
+        ],
+        DrawStyle::Foreground(foreground) => vec![colour("color", Some(foreground))],
+        DrawStyle::Background(background) => vec![colour("background", Some(background))],
+        DrawStyle::Coloured8Bit {
+            foreground,
+            background,
+        } => vec![
+            colour("color", foreground.map(|c| c.rgb())),
+            colour("background", background.map(|c| c.rgb())),
+        ],
+        DrawStyle::Foreground8Bit(foreground) => vec![colour("color", Some(foreground.rgb()))],
+        DrawStyle::Background8Bit(background) => {
+            vec![colour("background", Some(background.rgb()))]
+        }
+        DrawStyle::Default => vec![],
+        DrawStyle::Bold => vec![Some("font-weight:bold;".into())],
+        DrawStyle::Italic => vec![Some("font-style:italic;".into())],
+        DrawStyle::Underline => vec![Some("text-decoration:underline;".into())],
+        DrawStyle::Reverse => vec![Some("filter:invert(1);".into())],
+        DrawStyle::Compound {
+            foreground,
+            background,
+            bold,
+            italic,
+            underline,
+        } => vec![
+            colour("color", foreground),
+            colour("background", background),
+            bold.then(|| "font-weight:bold;".into()),
+            italic.then(|| "font-style:italic;".into()),
+            underline.then(|| "text-decoration:underline;".into()),
+        ],
+    };
+
+    declarations.into_iter().flatten().collect()
+}
+
+#[async_trait]
+impl Renderer for HtmlRenderer {
+    async fn render(&mut self, commands: &[DrawCommandBatch]) -> Result<()> {
+        self.memory_renderer.render(commands).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.memory_renderer.flush().await
+    }
+
+    async fn begin_frame(&mut self) -> Result<()> {
+        self.memory_renderer.begin_frame().await
+    }
+
+    async fn end_frame(&mut self) -> Result<()> {
+        self.memory_renderer.end_frame().await
+    }
+
+    async fn move_cursor(&mut self, x: Coordinate, y: Coordinate) -> Result<()> {
+        self.memory_renderer.move_cursor(x, y).await
+    }
+
+    async fn move_cursor_relative(
+        &mut self,
+        x: RelativeCoordinate,
+        y: RelativeCoordinate,
+    ) -> Result<()> {
+        self.memory_renderer.move_cursor_relative(x, y).await
+    }
+
+    async fn read_at_cursor(&self, width: Dimension) -> Result<String> {
+        self.memory_renderer.read_at_cursor(width).await
+    }
+
+    async fn read_string(&self, x: Coordinate, y: Coordinate, width: Dimension) -> Result<String> {
+        self.memory_renderer.read_string(x, y, width).await
+    }
+
+    fn cursor(&self) -> Coordinates {
+        self.memory_renderer.cursor()
+    }
+
+    fn dimensions(&self) -> Dimensions {
+        self.memory_renderer.dimensions()
+    }
+
+    fn set_width(&mut self, width: Dimension) {
+        self.memory_renderer.set_width(width);
+    }
+
+    fn set_height(&mut self, height: Dimension) {
+        self.memory_renderer.set_height(height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HtmlRenderer;
+    use crate::{Colour, DrawCommand, DrawStyle, Renderer};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_styled_text_is_rendered_in_spans() -> Result<()> {
+        let mut renderer = HtmlRenderer::new(32, 4);
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::TextUnderCursor("a <b> ".into()),
+                    DrawCommand::Style(DrawStyle::Coloured {
+                        foreground: Some(0xff0000),
+                        background: Some(0x000080),
+                    }),
+                    DrawCommand::TextUnderCursor("red".into()),
+                    DrawCommand::Style(DrawStyle::Default),
+                    DrawCommand::TextUnderCursor("\n".into()),
+                    DrawCommand::Style(DrawStyle::Foreground8Bit(Colour::Green)),
+                    DrawCommand::TextUnderCursor("green".into()),
+                ],
+            ))
+            .await?;
+
+        assert_eq!(
+            "<pre>a &lt;b&gt; <span style=\"color:#ff0000;background:#000080;\">red</span>\n\
+             <span style=\"color:#00aa00;\">green</span></pre>",
+            renderer.html()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+use eyre::Result;
+use unicode_width::UnicodeWidthChar;
+
+use crate::component::DrawCommandBatch;
+use crate::{Coordinate, Coordinates, Dimension, Dimensions, DrawCommand, RelativeCoordinate};
+
+use super::{MemoryRenderer, Renderer};
+
+/// How much work an [`InstrumentedRenderer`] was asked to do in a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderStats {
+    /// Draw commands that wrote text, however long.
+    pub writes: u64,
+    /// Cells written by those commands. Wide characters count for each cell
+    /// they cover.
+    pub cells_written: u64,
+    /// Cursor moves, both as draw commands and as calls to
+    /// [`Renderer::move_cursor`] and [`Renderer::move_cursor_relative`].
+    pub cursor_moves: u64,
+    /// [`DrawCommand::Style`] commands.
+    pub style_changes: u64,
+}
+
+/// A [`MemoryRenderer`] that counts what it's asked to draw, so that tests
+/// can assert that an optimization actually saves work, ex. that changing
+/// one cell writes no more than a few.
+///
+/// The counts are reset at the start of every frame; see
+/// [`InstrumentedRenderer::stats`].
+#[derive(Debug)]
+pub struct InstrumentedRenderer {
+    memory_renderer: MemoryRenderer,
+    stats: RenderStats,
+}
+
+impl InstrumentedRenderer {
+    pub fn new(width: Dimension, height: Dimension) -> Self {
+        Self {
+            memory_renderer: MemoryRenderer::new(width, height),
+            stats: RenderStats::default(),
+        }
+    }
+
+    /// The counts since the last [`Renderer::begin_frame`], or since the
+    /// renderer was created or last reset, whichever was most recent.
+    pub fn stats(&self) -> RenderStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = RenderStats::default();
+    }
+
+    /// The underlying renderer, ex. to read back what was drawn.
+    pub fn memory_renderer(&self) -> &MemoryRenderer {
+        &self.memory_renderer
+    }
+
+    fn count(&mut self, command: &DrawCommand) {
+        match command {
+            DrawCommand::TextUnderCursor(text) => {
+                self.stats.writes += 1;
+                self.stats.cells_written += text.chars().map(cells).sum::<u64>();
+            }
+            DrawCommand::CharUnderCursor(c) => {
+                self.stats.writes += 1;
+                self.stats.cells_written += cells(*c);
+            }
+            DrawCommand::MoveCursorAbsolute { .. }
+            | DrawCommand::MoveCursorRelative { .. }
+            | DrawCommand::SetFinalCursor { .. } => {
+                self.stats.cursor_moves += 1;
+            }
+            DrawCommand::Style(_) => {
+                self.stats.style_changes += 1;
+            }
+            DrawCommand::EraseCurrentLine(_)
+            | DrawCommand::HideCursor
+            | DrawCommand::ShowCursor
+            | DrawCommand::AlternateScreen(_)
+            | DrawCommand::BracketedPaste(_)
+            | DrawCommand::MouseReporting(_) => {}
+        }
+    }
+}
+
+/// How many cells drawing `c` writes.
+fn cells(c: char) -> u64 {
+    match c {
+        '\n' => 0,
+        c => c.width().unwrap_or(1) as u64,
+    }
+}
+
+#[async_trait]
+impl Renderer for InstrumentedRenderer {
+    async fn render(&mut self, commands: &[DrawCommandBatch]) -> Result<()> {
+        for command in commands.iter().flat_map(|(_key, commands)| commands) {
+            self.count(command);
+        }
+        self.memory_renderer.render(commands).await
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.memory_renderer.flush().await
+    }
+
+    async fn begin_frame(&mut self) -> Result<()> {
+        self.reset_stats();
+        self.memory_renderer.begin_frame().await
+    }
+
+    async fn end_frame(&mut self) -> Result<()> {
+        self.memory_renderer.end_frame().await
+    }
+
+    async fn move_cursor(&mut self, x: Coordinate, y: Coordinate) -> Result<()> {
+        self.stats.cursor_moves += 1;
+        self.memory_renderer.move_cursor(x, y).await
+    }
+
+    async fn move_cursor_relative(
+        &mut self,
+        x: RelativeCoordinate,
+        y: RelativeCoordinate,
+    ) -> Result<()> {
+        self.stats.cursor_moves += 1;
+        self.memory_renderer.move_cursor_relative(x, y).await
+    }
+
+    async fn read_at_cursor(&self, width: Dimension) -> Result<String> {
+        self.memory_renderer.read_at_cursor(width).await
+    }
+
+    async fn read_string(&self, x: Coordinate, y: Coordinate, width: Dimension) -> Result<String> {
+        self.memory_renderer.read_string(x, y, width).await
+    }
+
+    fn cursor(&self) -> Coordinates {
+        self.memory_renderer.cursor()
+    }
+
+    fn dimensions(&self) -> Dimensions {
+        self.memory_renderer.dimensions()
+    }
+
+    fn set_width(&mut self, width: Dimension) {
+        self.memory_renderer.set_width(width);
+    }
+
+    fn set_height(&mut self, height: Dimension) {
+        self.memory_renderer.set_height(height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstrumentedRenderer, RenderStats};
+    use crate::{DrawCommand, DrawStyle, Renderer};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_coalesced_text_is_fewer_writes() -> Result<()> {
+        let mut renderer = InstrumentedRenderer::new(16, 2);
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                "henol"
+                    .chars()
+                    .map(DrawCommand::CharUnderCursor)
+                    .collect::<Vec<_>>(),
+            ))
+            .await?;
+        renderer.end_frame().await?;
+        let uncoalesced = renderer.stats();
+        assert_eq!(5, uncoalesced.writes);
+        assert_eq!(5, uncoalesced.cells_written);
+
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 0, y: 1 },
+                    DrawCommand::Style(DrawStyle::Bold),
+                    DrawCommand::TextUnderCursor("henol".into()),
+                    DrawCommand::Style(DrawStyle::Default),
+                ],
+            ))
+            .await?;
+        renderer.end_frame().await?;
+        let coalesced = renderer.stats();
+        assert_eq!(
+            RenderStats {
+                writes: 1,
+                cells_written: 5,
+                cursor_moves: 1,
+                style_changes: 2,
+            },
+            coalesced
+        );
+        assert!(coalesced.writes < uncoalesced.writes);
+
+        assert_eq!("henol", renderer.read_string(0, 1, 5).await?);
+
+        Ok(())
+    }
+}
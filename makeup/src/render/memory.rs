@@ -1,11 +1,48 @@
 use async_trait::async_trait;
 use eyre::Result;
-use makeup_ansi::LineEraseMode;
+use makeup_ansi::{Ansi, DisplayEraseMode, LineEraseMode};
+use unicode_width::UnicodeWidthChar;
 
+use super::terminal::diff_grids;
 use super::RenderError;
 use crate::component::DrawCommandBatch;
 use crate::{Coordinate, Coordinates, Dimension, Dimensions, RelativeCoordinate};
-use crate::{DrawCommand, Renderer};
+use crate::{DrawCommand, DrawStyle, Renderer};
+
+/// A character in the grid, and the style it was drawn with, if any.
+pub type StyledChar = (char, Option<DrawStyle>);
+
+/// Fills the cells after the first of a wide character, ex. CJK, which takes
+/// up more than one column. Reading from the grid skips these cells, so the
+/// original characters are read back.
+pub const WIDE_CONTINUATION: char = '\0';
+
+/// How many columns apart tab stops are by default.
+pub const DEFAULT_TAB_WIDTH: Dimension = 8;
+
+/// What a [`MemoryRenderer`] does with text that runs past the edge of its
+/// grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowBehaviour {
+    /// Fail the render with [`RenderError::OutOfBounds`].
+    #[default]
+    Error,
+    /// Drop the characters past the edge.
+    Clamp,
+    /// Continue on the next line. Running past the last line is still an
+    /// error.
+    Wrap,
+    /// Expand the grid to fit.
+    Grow,
+}
+
+/// A copy of a [`MemoryRenderer`]'s grid and cursor, taken with
+/// [`MemoryRenderer::snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridSnapshot {
+    text: std::collections::HashMap<Coordinates, StyledChar>,
+    cursor: Coordinates,
+}
 
 /// A [`Renderer`] that renders to an in-memory grid.
 #[derive(Debug)]
@@ -14,7 +51,16 @@ pub struct MemoryRenderer {
     cursor_y: Coordinate,
     pub(crate) width: Dimension,
     pub(crate) height: Dimension,
-    text: std::collections::HashMap<Coordinates, char>,
+    text: std::collections::HashMap<Coordinates, StyledChar>,
+    /// The in-progress frame, if any. Swapped into `text` on commit.
+    scratch: Option<std::collections::HashMap<Coordinates, StyledChar>>,
+    /// The style applied to characters as they're drawn. `None` is the
+    /// default style.
+    style: Option<DrawStyle>,
+    overflow: OverflowBehaviour,
+    tab_width: Dimension,
+    /// Where the cursor is left at the end of the frame, if anything set it.
+    final_cursor: Option<Coordinates>,
 }
 
 impl MemoryRenderer {
@@ -25,7 +71,142 @@ impl MemoryRenderer {
             width,
             height,
             text: std::collections::HashMap::new(),
+            scratch: None,
+            style: None,
+            overflow: OverflowBehaviour::default(),
+            tab_width: DEFAULT_TAB_WIDTH,
+            final_cursor: None,
+        }
+    }
+
+    /// Set how many columns apart tab stops are. Tabs advance the cursor to
+    /// the next tab stop, filling the cells they skip with spaces. Defaults to
+    /// [`DEFAULT_TAB_WIDTH`].
+    pub fn set_tab_width(&mut self, tab_width: Dimension) {
+        self.tab_width = tab_width.max(1);
+    }
+
+    /// Set what happens to text drawn past the edge of the grid. Defaults to
+    /// [`OverflowBehaviour::Error`].
+    pub fn set_overflow_behaviour(&mut self, overflow: OverflowBehaviour) {
+        self.overflow = overflow;
+    }
+
+    /// Where the cursor is left at the end of the current or last frame, as
+    /// set by [`DrawCommand::SetFinalCursor`], if anything set it.
+    pub fn final_cursor(&self) -> Option<Coordinates> {
+        self.final_cursor
+    }
+
+    /// Forget the final cursor, returning it, ex. when rendering without
+    /// frames.
+    pub(crate) fn take_final_cursor(&mut self) -> Option<Coordinates> {
+        self.final_cursor.take()
+    }
+
+    /// Copy the grid and cursor as they are now, including anything drawn in
+    /// the in-progress frame, ex. to [`restore`](Self::restore) the base UI
+    /// after drawing an overlay over it.
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot {
+            text: self.scratch.as_ref().unwrap_or(&self.text).clone(),
+            cursor: (self.cursor_x, self.cursor_y),
+        }
+    }
+
+    /// Put the grid and cursor back as they were when `snapshot` was taken.
+    pub fn restore(&mut self, snapshot: &GridSnapshot) {
+        *self.grid_mut() = snapshot.text.clone();
+        (self.cursor_x, self.cursor_y) = snapshot.cursor;
+    }
+
+    /// The grid that draw commands should currently be written to.
+    fn grid_mut(&mut self) -> &mut std::collections::HashMap<Coordinates, StyledChar> {
+        self.scratch.as_mut().unwrap_or(&mut self.text)
+    }
+
+    /// The committed grid of characters, keyed by `(x, y)`. Cells that have
+    /// never been written are absent.
+    pub fn grid(&self) -> &std::collections::HashMap<Coordinates, StyledChar> {
+        &self.text
+    }
+
+    /// The committed grid as escape sequences: the screen is cleared, then
+    /// every written cell is drawn at its position, in its style. Printing
+    /// the result to a terminal reproduces the grid, ex. to replay a captured
+    /// frame.
+    pub fn to_ansi(&self) -> String {
+        Ansi::EraseInDisplay(DisplayEraseMode::All).to_string()
+            + &diff_grids(&std::collections::HashMap::new(), &self.text)
+    }
+
+    /// Read a `width`x`height` region starting at `(x, y)`, one string per
+    /// line. Unwritten cells read as spaces. The region is bounds-checked
+    /// once up front rather than per line.
+    pub fn read_region(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+        width: Dimension,
+        height: Dimension,
+    ) -> Result<Vec<String>> {
+        if x + width > self.width || y + height > self.height {
+            return Err(RenderError::OutOfBounds(
+                (x + width) as RelativeCoordinate,
+                (y + height) as RelativeCoordinate,
+            )
+            .into());
         }
+
+        Ok((y..y + height)
+            .map(|row| {
+                (x..x + width)
+                    .map(|column| self.text.get(&(column, row)).map_or(' ', |(c, _)| *c))
+                    .filter(|c| *c != WIDE_CONTINUATION)
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Like [`Renderer::read_string`], but unset cells read as `fill` rather
+    /// than as spaces, ex. to tell them apart from spaces that were drawn.
+    pub fn read_string_with_fill(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+        width: Dimension,
+        fill: char,
+    ) -> Result<String> {
+        self.bounds_check(x, y)?;
+        self.bounds_check(x + width, y)?;
+
+        Ok((x..x + width)
+            .map(|column| self.text.get(&(column, y)).map_or(fill, |(c, _)| *c))
+            .filter(|c| *c != WIDE_CONTINUATION)
+            .collect())
+    }
+
+    /// Whether anything, even a space, has been drawn in the cell at
+    /// `(x, y)` of the committed grid.
+    pub fn is_set(&self, x: Coordinate, y: Coordinate) -> bool {
+        self.text.contains_key(&(x, y))
+    }
+
+    /// Like [`Renderer::read_string`], but with the style each character was
+    /// drawn with. Unwritten cells read as unstyled spaces.
+    pub fn read_styled_string(
+        &self,
+        x: Coordinate,
+        y: Coordinate,
+        width: Dimension,
+    ) -> Result<Vec<StyledChar>> {
+        self.bounds_check(x, y)?;
+        self.bounds_check(x + width, y)?;
+
+        Ok((x..x + width)
+            .map(|column| *self.text.get(&(column, y)).unwrap_or(&(' ', None)))
+            .filter(|(c, _)| *c != WIDE_CONTINUATION)
+            .collect())
     }
 
     // TODO: Should we just be truncating instead?
@@ -50,21 +231,137 @@ impl MemoryRenderer {
     }
 
     fn insert_char(&mut self, c: char) -> Result<()> {
-        if c == '\n' {
-            self.bounds_check(0, self.cursor_y + 1)?;
+        if c == '\t' {
+            let line = self.cursor_y;
+            let stop = (self.cursor_x / self.tab_width + 1) * self.tab_width;
+            // If the tab wraps, it stops at the start of the next line.
+            while self.cursor_x < stop && self.cursor_y == line {
+                self.insert_char(' ')?;
+            }
+        } else if c == '\n' {
+            match self.overflow {
+                OverflowBehaviour::Error | OverflowBehaviour::Wrap => {
+                    self.bounds_check(0, self.cursor_y + 1)?;
+                }
+                // Lines past the bottom are dropped as they're drawn.
+                OverflowBehaviour::Clamp => {}
+                OverflowBehaviour::Grow => {
+                    self.set_height(self.height.max(self.cursor_y + 2));
+                }
+            }
             self.cursor_x = 0;
             self.cursor_y += 1;
         } else {
-            self.bounds_check(self.cursor_x, self.cursor_y)?;
-            self.bounds_check(self.cursor_x + 1, self.cursor_y)?;
-            self.text.insert((self.cursor_x, self.cursor_y), c);
-            self.cursor_x += 1;
+            // Zero-width characters, ex. combining marks, can't be stored on
+            // their own in a cell, so they're dropped.
+            let width = c.width().unwrap_or(1) as Dimension;
+            if width == 0 {
+                return Ok(());
+            }
+
+            match self.overflow {
+                OverflowBehaviour::Error => {
+                    self.bounds_check(self.cursor_x, self.cursor_y)?;
+                    self.bounds_check(self.cursor_x + width, self.cursor_y)?;
+                }
+                OverflowBehaviour::Clamp => {
+                    if self
+                        .bounds_check(self.cursor_x + width - 1, self.cursor_y)
+                        .is_err()
+                    {
+                        self.cursor_x += width;
+                        return Ok(());
+                    }
+                }
+                OverflowBehaviour::Wrap => {
+                    if self.cursor_x + width > self.width {
+                        self.cursor_x = 0;
+                        self.cursor_y += 1;
+                    }
+                    self.bounds_check(self.cursor_x + width - 1, self.cursor_y)?;
+                }
+                OverflowBehaviour::Grow => {
+                    self.set_width(self.width.max(self.cursor_x + width));
+                    self.set_height(self.height.max(self.cursor_y + 1));
+                }
+            }
+            let (x, y) = (self.cursor_x, self.cursor_y);
+            let style = match self.style {
+                Some(DrawStyle::ColouredAlpha {
+                    foreground,
+                    background,
+                    alpha,
+                }) => {
+                    let under = self
+                        .grid_mut()
+                        .get(&(x, y))
+                        .and_then(|(_, style)| style.as_ref().and_then(background_of));
+                    let background = match (background, under) {
+                        (Some(background), Some(under)) => Some(blend(background, under, alpha)),
+                        (background, under) => background.or(under),
+                    };
+                    Some(DrawStyle::Coloured {
+                        foreground,
+                        background,
+                    })
+                }
+                style => style,
+            };
+
+            let grid = self.grid_mut();
+            // Don't leave half of a wide character behind on either side.
+            if x > 0
+                && grid
+                    .get(&(x, y))
+                    .is_some_and(|(c, _)| *c == WIDE_CONTINUATION)
+            {
+                if let Some(cell) = grid.get_mut(&(x - 1, y)) {
+                    cell.0 = ' ';
+                }
+            }
+            if let Some(cell) = grid.get_mut(&(x + width, y)) {
+                if cell.0 == WIDE_CONTINUATION {
+                    cell.0 = ' ';
+                }
+            }
+
+            grid.insert((x, y), (c, style));
+            for column in 1..width {
+                grid.insert((x + column, y), (WIDE_CONTINUATION, style));
+            }
+            self.cursor_x += width;
         }
 
         Ok(())
     }
 }
 
+/// The background colour drawn by `style`, if any.
+fn background_of(style: &DrawStyle) -> Option<u32> {
+    match style {
+        DrawStyle::Coloured { background, .. }
+        | DrawStyle::ColouredAlpha { background, .. }
+        | DrawStyle::Compound { background, .. } => *background,
+        DrawStyle::Background(background) => Some(*background),
+        DrawStyle::Coloured8Bit { background, .. } => background.map(|c| c.rgb()),
+        DrawStyle::Background8Bit(background) => Some(background.rgb()),
+        _ => None,
+    }
+}
+
+/// Blend the RGB `colour` over `under`, with `alpha` being the opacity of
+/// `colour`.
+fn blend(colour: u32, under: u32, alpha: u8) -> u32 {
+    let alpha = alpha as u32;
+    let channel = |shift: u32| {
+        let colour = (colour >> shift) & 0xff;
+        let under = (under >> shift) & 0xff;
+        (colour * alpha + under * (255 - alpha)) / 255
+    };
+
+    (channel(16) << 16) | (channel(8) << 8) | channel(0)
+}
+
 #[async_trait]
 impl Renderer for MemoryRenderer {
     async fn render(&mut self, commands: &[DrawCommandBatch]) -> Result<()> {
@@ -84,18 +381,21 @@ impl Renderer for MemoryRenderer {
 
                     DrawCommand::EraseCurrentLine(mode) => match mode {
                         LineEraseMode::FromCursorToStart => {
+                            let y = self.cursor_y;
                             for x in 0..self.cursor_x {
-                                self.text.remove(&(x, self.cursor_y));
+                                self.grid_mut().remove(&(x, y));
                             }
                         }
                         LineEraseMode::FromCursorToEnd => {
+                            let y = self.cursor_y;
                             for x in self.cursor_x..self.width {
-                                self.text.remove(&(x, self.cursor_y));
+                                self.grid_mut().remove(&(x, y));
                             }
                         }
                         LineEraseMode::All => {
+                            let y = self.cursor_y;
                             for x in 0..self.width {
-                                self.text.remove(&(x, self.cursor_y));
+                                self.grid_mut().remove(&(x, y));
                             }
                         }
                     },
@@ -113,8 +413,30 @@ impl Renderer for MemoryRenderer {
 
                     DrawCommand::ShowCursor => {}
 
-                    // TODO: We actually do need to implement this tho...
-                    DrawCommand::Style(_) => {}
+                    DrawCommand::AlternateScreen(_)
+                    | DrawCommand::BracketedPaste(_)
+                    | DrawCommand::MouseReporting(_) => {}
+
+                    DrawCommand::SetFinalCursor { x, y } => {
+                        // Only the cursor goes here, so rather than failing
+                        // the frame, keep it on the grid.
+                        let clamp = |position: RelativeCoordinate, size: Dimension| {
+                            position.clamp(0, size.saturating_sub(1) as RelativeCoordinate)
+                                as Coordinate
+                        };
+                        self.final_cursor = Some((
+                            clamp(self.cursor_x as RelativeCoordinate + x, self.width),
+                            clamp(self.cursor_y as RelativeCoordinate + y, self.height),
+                        ));
+                    }
+
+                    DrawCommand::Style(DrawStyle::Default) => {
+                        self.style = None;
+                    }
+
+                    DrawCommand::Style(style) => {
+                        self.style = Some(*style);
+                    }
 
                     DrawCommand::MoveCursorAbsolute { x, y } => {
                         self.bounds_check(*x, *y)?;
@@ -131,6 +453,23 @@ impl Renderer for MemoryRenderer {
         Ok(())
     }
 
+    async fn begin_frame(&mut self) -> Result<()> {
+        self.scratch = Some(self.text.clone());
+        self.final_cursor = None;
+        Ok(())
+    }
+
+    async fn end_frame(&mut self) -> Result<()> {
+        if let Some(scratch) = self.scratch.take() {
+            self.text = scratch;
+        }
+        if let Some((x, y)) = self.final_cursor {
+            self.cursor_x = x;
+            self.cursor_y = y;
+        }
+        self.flush().await
+    }
+
     async fn move_cursor(&mut self, x: Coordinate, y: Coordinate) -> Result<()> {
         self.bounds_check(x, y)?;
         self.cursor_x = x;
@@ -157,13 +496,7 @@ impl Renderer for MemoryRenderer {
     }
 
     async fn read_string(&self, x: Coordinate, y: Coordinate, width: Dimension) -> Result<String> {
-        self.bounds_check(x, y)?;
-        self.bounds_check(x + width, y)?;
-        let mut result = String::new();
-        for i in 0..width {
-            result.push(*self.text.get(&(x + i, y)).unwrap_or(&' '));
-        }
-        Ok(result)
+        self.read_string_with_fill(x, y, width, ' ')
     }
 
     fn cursor(&self) -> Coordinates {
@@ -182,3 +515,330 @@ impl Renderer for MemoryRenderer {
         self.height = height;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use makeup_ansi::{Ansi, SgrParameter};
+    use unicode_width::UnicodeWidthChar;
+
+    use super::{MemoryRenderer, StyledChar, WIDE_CONTINUATION};
+    use crate::{Coordinates, DrawCommand, DrawStyle, Renderer};
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_frame_is_not_visible_until_committed() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(16, 1);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("old".into())]))
+            .await?;
+
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 0, y: 0 },
+                    DrawCommand::TextUnderCursor("new".into()),
+                ],
+            ))
+            .await?;
+        assert_eq!("old", renderer.read_string(0, 0, 3).await?);
+
+        renderer.end_frame().await?;
+        assert_eq!("new", renderer.read_string(0, 0, 3).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_snapshots_restore_the_grid() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(16, 1);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("henol world".into())]))
+            .await?;
+        let snapshot = renderer.snapshot();
+
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 2, y: 0 },
+                    DrawCommand::TextUnderCursor("[popup]".into()),
+                ],
+            ))
+            .await?;
+        assert_eq!("he[popup]ld", renderer.read_string(0, 0, 11).await?);
+
+        renderer.restore(&snapshot);
+        assert_eq!("henol world", renderer.read_string(0, 0, 11).await?);
+        assert_eq!((11, 0), renderer.cursor());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_written_spaces_are_set() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 1);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("a b".into())]))
+            .await?;
+
+        assert!(renderer.is_set(1, 0));
+        assert!(!renderer.is_set(3, 0));
+        assert_eq!("a b..", renderer.read_string_with_fill(0, 0, 5, '.')?);
+        assert_eq!("a b  ", renderer.read_string(0, 0, 5).await?);
+
+        Ok(())
+    }
+
+    /// Replay `ansi` into a grid. Only understands what
+    /// [`MemoryRenderer::to_ansi`] produces for [`DrawStyle::Coloured`] cells.
+    fn replay(ansi: &str) -> Result<HashMap<Coordinates, StyledChar>> {
+        let mut grid = HashMap::new();
+        let mut cursor = (0, 0);
+        let mut style = None;
+        let mut input = ansi;
+        while let Some(c) = input.chars().next() {
+            if c != '\x1b' {
+                grid.insert(cursor, (c, style));
+                for i in 1..c.width().unwrap_or(1) as u64 {
+                    grid.insert((cursor.0 + i, cursor.1), (WIDE_CONTINUATION, style));
+                }
+                cursor.0 += c.width().unwrap_or(1) as u64;
+                input = &input[c.len_utf8()..];
+                continue;
+            }
+
+            let (sequence, rest) = Ansi::parse(input)?;
+            match sequence {
+                Ansi::EraseInDisplay(_) => grid.clear(),
+                Ansi::CursorPosition(x, y) => cursor = (x, y),
+                Ansi::Sgr(parameters) => {
+                    for parameter in parameters {
+                        let (foreground, background) = match style {
+                            Some(DrawStyle::Coloured {
+                                foreground,
+                                background,
+                            }) => (foreground, background),
+                            _ => (None, None),
+                        };
+                        style = match parameter {
+                            SgrParameter::Reset => None,
+                            SgrParameter::HexForegroundColour(colour) => {
+                                Some(DrawStyle::Coloured {
+                                    foreground: Some(colour),
+                                    background,
+                                })
+                            }
+                            SgrParameter::HexBackgroundColour(colour) => {
+                                Some(DrawStyle::Coloured {
+                                    foreground,
+                                    background: Some(colour),
+                                })
+                            }
+                            parameter => panic!("unexpected {parameter:?}"),
+                        };
+                    }
+                }
+                sequence => panic!("unexpected {sequence:?}"),
+            }
+            input = rest;
+        }
+
+        Ok(grid)
+    }
+
+    #[tokio::test]
+    async fn test_to_ansi_round_trips() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(16, 3);
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::TextUnderCursor("plain".into()),
+                    DrawCommand::MoveCursorAbsolute { x: 2, y: 1 },
+                    DrawCommand::Style(DrawStyle::Coloured {
+                        foreground: Some(0xff0000),
+                        background: None,
+                    }),
+                    DrawCommand::TextUnderCursor("red".into()),
+                    DrawCommand::Style(DrawStyle::Coloured {
+                        foreground: Some(0x00ff00),
+                        background: Some(0x000080),
+                    }),
+                    DrawCommand::TextUnderCursor(" 日本".into()),
+                    DrawCommand::Style(DrawStyle::Default),
+                    DrawCommand::MoveCursorAbsolute { x: 0, y: 2 },
+                    DrawCommand::TextUnderCursor("end".into()),
+                ],
+            ))
+            .await?;
+
+        assert_eq!(*renderer.grid(), replay(&renderer.to_ansi())?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_region() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 4);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("ab\n cd".into())]))
+            .await?;
+
+        assert_eq!(
+            vec!["ab   ".to_string(), " cd  ".into(), "     ".into()],
+            renderer.read_region(0, 0, 5, 3)?
+        );
+        assert_eq!(vec!["cd".to_string()], renderer.read_region(1, 1, 2, 1)?);
+        assert!(renderer.read_region(4, 0, 5, 1).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_styles_are_recorded_per_cell() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 1);
+        let red = DrawStyle::Foreground(0xff0000);
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::TextUnderCursor("a".into()),
+                    DrawCommand::Style(red),
+                    DrawCommand::TextUnderCursor("bc".into()),
+                    DrawCommand::Style(DrawStyle::Default),
+                    DrawCommand::TextUnderCursor("d".into()),
+                ],
+            ))
+            .await?;
+
+        assert_eq!(
+            vec![
+                ('a', None),
+                ('b', Some(red)),
+                ('c', Some(red)),
+                ('d', None),
+                (' ', None),
+            ],
+            renderer.read_styled_string(0, 0, 5)?
+        );
+        assert_eq!("abcd ", renderer.read_string(0, 0, 5).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_alpha_blends_with_the_background() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 1);
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::Style(DrawStyle::Background(0xffffff)),
+                    DrawCommand::TextUnderCursor("ab".into()),
+                    DrawCommand::MoveCursorAbsolute { x: 0, y: 0 },
+                    // Dim the first cell to 50%.
+                    DrawCommand::Style(DrawStyle::ColouredAlpha {
+                        foreground: None,
+                        background: Some(0x000000),
+                        alpha: 128,
+                    }),
+                    DrawCommand::TextUnderCursor(" ".into()),
+                ],
+            ))
+            .await?;
+
+        assert_eq!(
+            vec![
+                (
+                    ' ',
+                    Some(DrawStyle::Coloured {
+                        foreground: None,
+                        background: Some(0x7f7f7f),
+                    })
+                ),
+                ('b', Some(DrawStyle::Background(0xffffff))),
+            ],
+            renderer.read_styled_string(0, 0, 2)?
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_overflow_behaviours() -> Result<()> {
+        use super::OverflowBehaviour;
+
+        async fn render(overflow: OverflowBehaviour) -> (MemoryRenderer, Result<()>) {
+            let mut renderer = MemoryRenderer::new(4, 2);
+            renderer.set_overflow_behaviour(overflow);
+            let result = renderer
+                .render_one((0, vec![DrawCommand::TextUnderCursor("abcdef".into())]))
+                .await;
+            (renderer, result)
+        }
+
+        let (_, result) = render(OverflowBehaviour::Error).await;
+        assert!(result.is_err());
+
+        let (renderer, result) = render(OverflowBehaviour::Clamp).await;
+        result?;
+        assert_eq!(vec!["abcd", "    "], renderer.read_region(0, 0, 4, 2)?);
+
+        let (renderer, result) = render(OverflowBehaviour::Wrap).await;
+        result?;
+        assert_eq!(vec!["abcd", "ef  "], renderer.read_region(0, 0, 4, 2)?);
+
+        let (renderer, result) = render(OverflowBehaviour::Grow).await;
+        result?;
+        assert_eq!((6, 2), renderer.dimensions());
+        assert_eq!(vec!["abcdef", "      "], renderer.read_region(0, 0, 6, 2)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tabs_advance_to_the_next_tab_stop() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 1);
+        renderer.set_tab_width(4);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("a\tb".into())]))
+            .await?;
+
+        assert_eq!("a   b  ", renderer.read_string(0, 0, 7).await?);
+        assert_eq!((5, 0), renderer.cursor());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_wide_characters_take_two_columns() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(8, 1);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("日本a".into())]))
+            .await?;
+
+        assert_eq!((5, 0), renderer.cursor());
+        assert_eq!("日本a ", renderer.read_string(0, 0, 6).await?);
+        // Starting halfway through a wide character skips it.
+        assert_eq!("a", renderer.read_string(3, 0, 2).await?);
+
+        // Overwriting half of a wide character blanks the other half.
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 1, y: 0 },
+                    DrawCommand::TextUnderCursor("x".into()),
+                ],
+            ))
+            .await?;
+        assert_eq!(" x本a", renderer.read_string(0, 0, 5).await?);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::fs::File;
+
 use async_trait::async_trait;
 use eyre::Result;
 use thiserror::Error;
@@ -6,10 +8,21 @@ use crate::component::DrawCommandBatch;
 use crate::util::AsAny;
 use crate::{Coordinate, Coordinates, Dimension, Dimensions, RelativeCoordinate};
 
+#[cfg(debug_assertions)]
+pub(crate) mod bounds;
+pub(crate) mod clip;
+pub mod guard;
+#[cfg(feature = "html")]
+pub mod html;
+pub mod instrumented;
 pub mod memory;
 pub mod terminal;
 
-pub use memory::MemoryRenderer;
+pub use guard::TerminalGuard;
+#[cfg(feature = "html")]
+pub use html::HtmlRenderer;
+pub use instrumented::{InstrumentedRenderer, RenderStats};
+pub use memory::{GridSnapshot, MemoryRenderer, OverflowBehaviour};
 pub use terminal::TerminalRenderer;
 
 /// A `Renderer` takes in a slice of [`DrawCommandBatch`]es and renders them
@@ -23,8 +36,39 @@ pub use terminal::TerminalRenderer;
 pub trait Renderer: std::fmt::Debug + AsAny + Send + Sync {
     async fn render(&mut self, commands: &[DrawCommandBatch]) -> Result<()>;
 
+    /// Render a single batch of commands.
+    async fn render_one(&mut self, batch: DrawCommandBatch) -> Result<()> {
+        self.render(&[batch]).await
+    }
+
     async fn flush(&mut self) -> Result<()>;
 
+    /// Begin a new frame. Anything rendered until the matching
+    /// [`Renderer::end_frame`] call is buffered, and must not be presented
+    /// until the frame is committed.
+    async fn begin_frame(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Commit the current frame, presenting everything rendered since
+    /// [`Renderer::begin_frame`] at once, and flush.
+    async fn end_frame(&mut self) -> Result<()> {
+        self.flush().await
+    }
+
+    /// Enter or leave the alternate screen. Entering clears it. Renderers
+    /// that don't draw to a terminal have nothing to do.
+    async fn set_alternate_screen(&mut self, _alternate: bool) -> Result<()> {
+        Ok(())
+    }
+
+    /// A handle to the terminal this renderer draws on, for a
+    /// [`TerminalGuard`] to restore. `None` for renderers that don't draw on
+    /// a terminal.
+    fn terminal(&self) -> Result<Option<File>> {
+        Ok(None)
+    }
+
     async fn move_cursor(&mut self, x: Coordinate, y: Coordinate) -> Result<()>;
 
     async fn move_cursor_relative(
@@ -55,10 +99,10 @@ pub enum RenderError {
 
 #[cfg(test)]
 mod tests {
-    use super::MemoryRenderer;
+    use super::{MemoryRenderer, Renderer};
     use crate::components::EchoText;
     use crate::input::TerminalInput;
-    use crate::MUI;
+    use crate::{DrawCommand, MUI};
 
     use eyre::Result;
 
@@ -76,4 +120,16 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_render_one() -> Result<()> {
+        let mut renderer = MemoryRenderer::new(16, 1);
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("henol".into())]))
+            .await?;
+
+        assert_eq!("henol", renderer.read_string(0, 0, 5).await?);
+
+        Ok(())
+    }
 }
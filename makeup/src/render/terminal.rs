@@ -1,30 +1,280 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
 use std::io::Write;
+use std::os::fd::{AsFd, AsRawFd, RawFd};
+use std::sync::{Mutex, PoisonError};
 
 use async_trait::async_trait;
+use derivative::Derivative;
 use eyre::Result;
 use makeup_ansi::{CursorVisibility, DisplayEraseMode, SgrParameter};
+use unicode_width::UnicodeWidthChar;
 
 use crate::component::DrawCommandBatch;
 use crate::{Ansi, DrawCommand, DrawStyle};
-use crate::{Coordinate, Coordinates, Dimension, RelativeCoordinate};
+use crate::{Coordinate, Coordinates, Dimension, Dimensions, RelativeCoordinate};
 
+use super::memory::{OverflowBehaviour, StyledChar, WIDE_CONTINUATION};
 use super::{MemoryRenderer, Renderer};
 
 /// A [`Renderer`] that renders to a terminal.
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct TerminalRenderer {
+    /// Where output is written, stdout unless otherwise specified.
+    #[derivative(Debug = "ignore")]
+    writer: Mutex<Box<dyn Write + Send>>,
     memory_renderer: MemoryRenderer,
-    saved_position: bool,
+    /// Where the grid's cursor was when the terminal's cursor position was
+    /// first saved, if it has been.
+    saved_position: Option<Coordinates>,
+    /// Output buffered for the in-progress frame, if any.
+    frame: Option<String>,
+    synchronized_output: bool,
+    /// Whether only the cells that changed since the last render are drawn.
+    diffing: bool,
+    /// Whether `memory_renderer`'s grid is known to match the terminal.
+    synced: bool,
+    /// The hash of the last rendered commands, if they were diffed.
+    last_hash: Option<u64>,
+    /// The terminal modes drawn so far.
+    modes: Modes,
+    /// The terminal that's rendered to, for its size and for restoring it.
+    terminal: Terminal,
+}
+
+/// The terminal a [`TerminalRenderer`] draws on.
+#[derive(Debug)]
+enum Terminal {
+    Stdout,
+    Stderr,
+    Tty(File),
+}
+
+impl Terminal {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Self::Stdout => libc::STDOUT_FILENO,
+            Self::Stderr => libc::STDERR_FILENO,
+            Self::Tty(tty) => tty.as_raw_fd(),
+        }
+    }
+
+    /// A new handle to the terminal, independent of the renderer's.
+    fn try_clone(&self) -> std::io::Result<File> {
+        let fd = match self {
+            Self::Stdout => std::io::stdout().as_fd().try_clone_to_owned()?,
+            Self::Stderr => std::io::stderr().as_fd().try_clone_to_owned()?,
+            Self::Tty(tty) => return tty.try_clone(),
+        };
+
+        Ok(File::from(fd))
+    }
+}
+
+/// Which terminal modes are switched on.
+#[derive(Debug, Default)]
+struct Modes {
+    alternate_screen: bool,
+    bracketed_paste: bool,
+    mouse_reporting: bool,
 }
 
 impl TerminalRenderer {
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        let (w, h) = ioctls::get_terminal_size();
+        Self::with_writer(Box::new(std::io::stdout()))
+    }
+
+    /// Render to stderr instead of stdout, ex. so that stdout is left for a
+    /// CLI's results, which can then be piped elsewhere.
+    pub fn to_stderr() -> Self {
+        Self::with_writer_and_terminal(Box::new(std::io::stderr()), Terminal::Stderr)
+    }
+
+    /// Render to the controlling terminal, `/dev/tty`, even if stdout and
+    /// stderr are both redirected. Fails if there isn't one.
+    pub fn to_tty() -> Result<Self> {
+        let tty = std::fs::OpenOptions::new().write(true).open("/dev/tty")?;
+        let writer = tty.try_clone()?;
+        Ok(Self::with_writer_and_terminal(
+            Box::new(writer),
+            Terminal::Tty(tty),
+        ))
+    }
+
+    /// Render to `writer` instead of stdout, ex. to log rendered output to a
+    /// file, or to capture the exact escape sequences written. The terminal
+    /// size is still read from stdout.
+    pub fn with_writer(writer: Box<dyn Write + Send>) -> Self {
+        Self::with_writer_and_terminal(writer, Terminal::Stdout)
+    }
+
+    fn with_writer_and_terminal(writer: Box<dyn Write + Send>, terminal: Terminal) -> Self {
+        let (w, h) = ioctls::get_terminal_size(terminal.as_raw_fd());
 
         Self {
+            writer: Mutex::new(writer),
             memory_renderer: MemoryRenderer::new(w, h),
-            saved_position: false,
+            saved_position: None,
+            frame: None,
+            synchronized_output: true,
+            diffing: true,
+            synced: false,
+            last_hash: None,
+            modes: Modes::default(),
+            terminal,
+        }
+    }
+
+    /// Set whether only the cells that changed since the last render are
+    /// redrawn. Enabled by default. When disabled, every draw command is
+    /// written to the terminal as-is.
+    pub fn set_diffing(&mut self, diffing: bool) {
+        self.diffing = diffing;
+        self.synced = false;
+        self.last_hash = None;
+    }
+
+    /// Set whether committed frames are wrapped in synchronized-output
+    /// sequences. Enabled by default; terminals that don't support
+    /// synchronized output ignore these sequences.
+    pub fn set_synchronized_output(&mut self, synchronized_output: bool) {
+        self.synchronized_output = synchronized_output;
+    }
+
+    /// Render `commands` into the grid, and return the output that draws the
+    /// cells that changed, or `None` if they can't be rendered into the grid,
+    /// ex. because they draw out of bounds.
+    async fn render_diff(&mut self, commands: &[DrawCommandBatch]) -> Option<String> {
+        let mut hasher = DefaultHasher::new();
+        commands.hash(&mut hasher);
+        let hash = hasher.finish();
+        if self.synced && self.last_hash == Some(hash) {
+            return Some(String::new());
+        }
+
+        // If the terminal's contents are unknown, draw everything.
+        let previous = if self.synced {
+            self.memory_renderer.grid().clone()
+        } else {
+            HashMap::new()
+        };
+        let rendered = match self.memory_renderer.move_cursor(0, 0).await {
+            Ok(()) => self.memory_renderer.render(commands).await,
+            Err(err) => Err(err),
+        };
+        if rendered.is_err() {
+            self.synced = false;
+            self.last_hash = None;
+            return None;
+        }
+        self.synced = true;
+        self.last_hash = Some(hash);
+
+        let mut buffer = diff_grids(&previous, self.memory_renderer.grid());
+        for command in commands.iter().flat_map(|(_key, commands)| commands) {
+            match command {
+                DrawCommand::HideCursor => {
+                    buffer += &Ansi::CursorVisibility(CursorVisibility::Invisible).to_string();
+                }
+                DrawCommand::ShowCursor => {
+                    buffer += &Ansi::CursorVisibility(CursorVisibility::Visible).to_string();
+                }
+                _ => {}
+            }
+        }
+        let (x, y) = self
+            .memory_renderer
+            .take_final_cursor()
+            .unwrap_or(self.memory_renderer.cursor());
+        buffer += &Ansi::CursorPosition(x, y).to_string();
+
+        Some(buffer)
+    }
+
+    /// The output that switches the terminal into the modes `commands` ask
+    /// for, leaving out any that it's already in.
+    fn switch_modes(&mut self, commands: &[DrawCommandBatch]) -> String {
+        let mut output = String::new();
+        for command in commands.iter().flat_map(|(_key, commands)| commands) {
+            let (mode, on, sequence) = match *command {
+                DrawCommand::AlternateScreen(on) => (
+                    &mut self.modes.alternate_screen,
+                    on,
+                    Ansi::AlternateScreen(on),
+                ),
+                DrawCommand::BracketedPaste(on) => (
+                    &mut self.modes.bracketed_paste,
+                    on,
+                    Ansi::BracketedPaste(on),
+                ),
+                DrawCommand::MouseReporting(on) => (
+                    &mut self.modes.mouse_reporting,
+                    on,
+                    Ansi::MouseReporting(on),
+                ),
+                _ => continue,
+            };
+            if *mode == on {
+                continue;
+            }
+            *mode = on;
+
+            output += &sequence.to_string();
+            if let Ansi::AlternateScreen(_) = sequence {
+                if on {
+                    output += &Ansi::EraseInDisplay(DisplayEraseMode::All).to_string();
+                }
+                // The screen no longer shows what was last drawn.
+                self.synced = false;
+                self.last_hash = None;
+            }
+        }
+
+        output
+    }
+
+    /// Write `output` to the in-progress frame, or straight to the terminal if
+    /// there isn't one.
+    fn write(&mut self, output: &str) -> Result<()> {
+        if let Some(frame) = self.frame.as_mut() {
+            *frame += output;
+            Ok(())
+        } else {
+            self.write_through(output)
+        }
+    }
+
+    /// Write `output` straight to the terminal, bypassing any in-progress
+    /// frame.
+    fn write_through(&mut self, output: &str) -> Result<()> {
+        self.writer().write_all(output.as_bytes())?;
+        Ok(())
+    }
+
+    fn writer(&mut self) -> &mut Box<dyn Write + Send> {
+        // Nothing can panic while holding the lock, as it's never actually
+        // locked.
+        self.writer
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Take the buffered output of the in-progress frame, ready to be written
+    /// to the terminal in one go.
+    fn commit_frame(&mut self) -> Option<String> {
+        let frame = self.frame.take()?;
+        if self.synchronized_output {
+            Some(format!(
+                "{}{frame}{}",
+                Ansi::BeginSynchronizedUpdate,
+                Ansi::EndSynchronizedUpdate
+            ))
+        } else {
+            Some(frame)
         }
     }
 }
@@ -36,34 +286,50 @@ impl Renderer for TerminalRenderer {
 
         // Handle terminal resizes.
         // Blank the screen to avoid lingering state on rerender.
-        let (w, h) = ioctls::get_terminal_size();
+        let (w, h) = ioctls::get_terminal_size(self.terminal.as_raw_fd());
         if w != self.memory_renderer.width || h != self.memory_renderer.height {
             self.set_width(w);
             self.set_height(h);
             buffer += &Ansi::EraseInDisplay(DisplayEraseMode::All).to_string();
+            // The terminal is blank now.
+            self.memory_renderer = MemoryRenderer::new(w, h);
+            self.synced = true;
+            self.last_hash = None;
+        }
+        buffer += &self.switch_modes(commands);
+
+        if self.diffing {
+            if let Some(diff) = self.render_diff(commands).await {
+                buffer += &diff;
+                return self.write(&buffer);
+            }
         }
 
         // Save the cursor position before each render, and restore it after.
         // Not restoring the cursor position until we've saved it the first
         // time ensures that ex. the cursor will be positioned at the expected
         // character when rendering.
-        if self.saved_position {
+        if let Some((x, y)) = self.saved_position {
             buffer += &Ansi::RestoreCursorPosition.to_string();
+            // The grid's cursor goes back with the terminal's, so that relative
+            // moves land in the same place in both.
+            let _ = self.memory_renderer.move_cursor(x, y).await;
         } else {
-            self.saved_position = true;
+            self.saved_position = Some(self.memory_renderer.cursor());
         }
         buffer += &Ansi::SaveCursorPosition.to_string();
 
+        let mut style = None;
         for (_key, commands) in commands {
             // debug!("rendering to terminal: {}", key);
             for command in commands {
                 match command {
                     DrawCommand::TextUnderCursor(text) => {
-                        buffer += text;
+                        push_text(&mut buffer, text, style.as_ref());
                     }
 
                     DrawCommand::CharUnderCursor(c) => {
-                        buffer.push(*c);
+                        push_text(&mut buffer, &c.to_string(), style.as_ref());
                     }
 
                     DrawCommand::EraseCurrentLine(mode) => {
@@ -104,91 +370,76 @@ impl Renderer for TerminalRenderer {
                         buffer += &Ansi::CursorVisibility(CursorVisibility::Visible).to_string();
                     }
 
-                    DrawCommand::Style(style) => match style {
-                        DrawStyle::Coloured {
-                            foreground,
-                            background,
-                        } => {
-                            if let Some(background) = background {
-                                buffer += &Ansi::Sgr(vec![SgrParameter::HexBackgroundColour(
-                                    *background,
-                                )])
-                                .to_string();
-                            }
-                            if let Some(foreground) = foreground {
-                                buffer += &Ansi::Sgr(vec![SgrParameter::HexForegroundColour(
-                                    *foreground,
-                                )])
-                                .to_string();
-                            }
-                        }
-
-                        DrawStyle::Foreground(foreground) => {
-                            buffer +=
-                                &Ansi::Sgr(vec![SgrParameter::HexForegroundColour(*foreground)])
-                                    .to_string();
-                        }
-
-                        DrawStyle::Background(background) => {
-                            buffer +=
-                                &Ansi::Sgr(vec![SgrParameter::HexBackgroundColour(*background)])
-                                    .to_string();
-                        }
-
-                        DrawStyle::Coloured8Bit {
-                            foreground,
-                            background,
-                        } => {
-                            if let Some(background) = background {
-                                buffer += &Ansi::TerminalBackgroundColour(*background).to_string();
-                            }
-                            if let Some(foreground) = foreground {
-                                buffer += &Ansi::TerminalForegroundColour(*foreground).to_string();
-                            }
-                        }
-
-                        DrawStyle::Foreground8Bit(foreground) => {
-                            buffer += &Ansi::TerminalForegroundColour(*foreground).to_string();
-                        }
-
-                        DrawStyle::Background8Bit(background) => {
-                            buffer += &Ansi::TerminalBackgroundColour(*background).to_string();
-                        }
-
-                        DrawStyle::Default => {
-                            buffer += &Ansi::Sgr(vec![SgrParameter::Reset]).to_string();
-                        }
-
-                        DrawStyle::Bold => {
-                            buffer += &Ansi::Sgr(vec![SgrParameter::Bold]).to_string();
-                        }
-
-                        DrawStyle::Italic => {
-                            buffer += &Ansi::Sgr(vec![SgrParameter::Italic]).to_string();
-                        }
-
-                        DrawStyle::Underline => {
-                            buffer += &Ansi::Sgr(vec![SgrParameter::Underline]).to_string();
-                        }
-                    },
+                    DrawCommand::SetFinalCursor { .. }
+                    | DrawCommand::AlternateScreen(_)
+                    | DrawCommand::BracketedPaste(_)
+                    | DrawCommand::MouseReporting(_) => {}
+
+                    DrawCommand::Style(new_style) => {
+                        buffer += &style_sequence(new_style);
+                        style = match new_style {
+                            DrawStyle::Default => None,
+                            new_style => Some(*new_style),
+                        };
+                    }
                 }
             }
         }
 
-        print!("{buffer}");
+        // Keep the grid in step with the terminal, so that what was drawn can
+        // be read back. The terminal drops text past its edges, so the grid
+        // does as well.
+        self.memory_renderer
+            .set_overflow_behaviour(OverflowBehaviour::Clamp);
+        let _ = self.memory_renderer.render(commands).await;
+        self.memory_renderer
+            .set_overflow_behaviour(OverflowBehaviour::Error);
+        if let Some((x, y)) = self.memory_renderer.take_final_cursor() {
+            buffer += &Ansi::CursorPosition(x, y).to_string();
+        }
 
-        Ok(())
+        self.write(&buffer)
     }
 
     async fn flush(&mut self) -> Result<()> {
         // NOTE: Can't flush with tokio, doesn't work for some reason.
-        std::io::stdout().flush()?;
+        self.writer().flush()?;
+        Ok(())
+    }
+
+    async fn begin_frame(&mut self) -> Result<()> {
+        self.frame = Some(String::new());
         Ok(())
     }
 
+    async fn end_frame(&mut self) -> Result<()> {
+        if let Some(frame) = self.commit_frame() {
+            self.write_through(&frame)?;
+        }
+        self.flush().await
+    }
+
+    async fn set_alternate_screen(&mut self, alternate: bool) -> Result<()> {
+        let mut output = Ansi::AlternateScreen(alternate).to_string();
+        if alternate {
+            output += &Ansi::EraseInDisplay(DisplayEraseMode::All).to_string();
+        }
+        self.write_through(&output)?;
+        self.flush().await?;
+        self.modes.alternate_screen = alternate;
+        // The screen no longer shows what was last drawn.
+        self.synced = false;
+        self.last_hash = None;
+        Ok(())
+    }
+
+    fn terminal(&self) -> Result<Option<File>> {
+        Ok(Some(self.terminal.try_clone()?))
+    }
+
     async fn move_cursor(&mut self, x: Coordinate, y: Coordinate) -> eyre::Result<()> {
         let res = self.memory_renderer.move_cursor(x, y).await;
-        print!("{}", Ansi::CursorPosition(x, y),);
+        self.write_through(&Ansi::CursorPosition(x, y).to_string())?;
         res
     }
 
@@ -200,21 +451,21 @@ impl Renderer for TerminalRenderer {
         let res = self.memory_renderer.move_cursor_relative(x, y).await;
         match x.cmp(&0) {
             std::cmp::Ordering::Less => {
-                print!("{}", Ansi::CursorLeft(-x as Dimension));
+                self.write_through(&Ansi::CursorLeft(-x as Dimension).to_string())?;
             }
             std::cmp::Ordering::Equal => {}
             std::cmp::Ordering::Greater => {
-                print!("{}", Ansi::CursorRight(x as Dimension));
+                self.write_through(&Ansi::CursorRight(x as Dimension).to_string())?;
             }
         }
 
         match y.cmp(&0) {
             std::cmp::Ordering::Less => {
-                print!("{}", Ansi::CursorUp(-y as Dimension));
+                self.write_through(&Ansi::CursorUp(-y as Dimension).to_string())?;
             }
             std::cmp::Ordering::Equal => {}
             std::cmp::Ordering::Greater => {
-                print!("{}", Ansi::CursorDown(y as Dimension));
+                self.write_through(&Ansi::CursorDown(y as Dimension).to_string())?;
             }
         }
         res
@@ -237,7 +488,7 @@ impl Renderer for TerminalRenderer {
         self.memory_renderer.cursor()
     }
 
-    fn dimensions(&self) -> Coordinates {
+    fn dimensions(&self) -> Dimensions {
         self.memory_renderer.dimensions()
     }
 
@@ -250,20 +501,482 @@ impl Renderer for TerminalRenderer {
     }
 }
 
+/// The output that turns the `previous` grid into the `next` one, drawing
+/// only the cells that differ, top to bottom.
+pub(crate) fn diff_grids(
+    previous: &HashMap<Coordinates, StyledChar>,
+    next: &HashMap<Coordinates, StyledChar>,
+) -> String {
+    let blank = (' ', None);
+    let mut changed: Vec<(Coordinates, StyledChar)> = next
+        .iter()
+        // Cells that were never drawn may hold anything, so drawing a blank
+        // over one is still a change.
+        .filter(|(position, cell)| previous.get(position) != Some(*cell))
+        .map(|(position, cell)| (*position, *cell))
+        .collect();
+    changed.extend(
+        previous
+            .iter()
+            .filter(|(position, cell)| !next.contains_key(position) && **cell != blank)
+            .map(|(position, _)| (*position, blank)),
+    );
+    changed.sort_by_key(|((x, y), _)| (*y, *x));
+
+    let mut buffer = String::new();
+    let mut cursor = None;
+    let mut style = None;
+    for ((x, y), (c, cell_style)) in changed {
+        // Drawn along with the first half of the character.
+        if c == WIDE_CONTINUATION {
+            continue;
+        }
+        if cursor != Some((x, y)) {
+            buffer += &Ansi::CursorPosition(x, y).to_string();
+        }
+        if cell_style != style {
+            buffer += &Ansi::Sgr(vec![SgrParameter::Reset]).to_string();
+            if let Some(cell_style) = &cell_style {
+                buffer += &style_sequence(cell_style);
+            }
+            style = cell_style;
+        }
+        buffer.push(c);
+        cursor = Some((x + c.width().unwrap_or(1) as Coordinate, y));
+    }
+    if style.is_some() {
+        buffer += &Ansi::Sgr(vec![SgrParameter::Reset]).to_string();
+    }
+
+    buffer
+}
+
+/// Append `text`, drawn with `style`, to `buffer`. Styles are reset at the
+/// end of each line and reapplied on the next, so that a background colour
+/// can't bleed to the edge of the terminal when it scrolls.
+fn push_text(buffer: &mut String, text: &str, style: Option<&DrawStyle>) {
+    let Some(style) = style else {
+        *buffer += text;
+        return;
+    };
+
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            *buffer += &Ansi::Sgr(vec![SgrParameter::Reset]).to_string();
+            buffer.push('\n');
+            *buffer += &style_sequence(style);
+        }
+        *buffer += line;
+    }
+}
+
+/// The escape sequence that applies `style`.
+fn style_sequence(style: &DrawStyle) -> String {
+    let mut buffer = String::new();
+    match style {
+        // Blending needs to know what's underneath, which is only the case
+        // when diffing, where styles are already blended.
+        DrawStyle::Coloured {
+            foreground,
+            background,
+        }
+        | DrawStyle::ColouredAlpha {
+            foreground,
+            background,
+            ..
+        } => {
+            if let Some(background) = background {
+                buffer +=
+                    &Ansi::Sgr(vec![SgrParameter::HexBackgroundColour(*background)]).to_string();
+            }
+            if let Some(foreground) = foreground {
+                buffer +=
+                    &Ansi::Sgr(vec![SgrParameter::HexForegroundColour(*foreground)]).to_string();
+            }
+        }
+
+        DrawStyle::Foreground(foreground) => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::HexForegroundColour(*foreground)]).to_string();
+        }
+
+        DrawStyle::Background(background) => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::HexBackgroundColour(*background)]).to_string();
+        }
+
+        DrawStyle::Coloured8Bit {
+            foreground,
+            background,
+        } => {
+            if let Some(background) = background {
+                buffer += &Ansi::TerminalBackgroundColour(*background).to_string();
+            }
+            if let Some(foreground) = foreground {
+                buffer += &Ansi::TerminalForegroundColour(*foreground).to_string();
+            }
+        }
+
+        DrawStyle::Foreground8Bit(foreground) => {
+            buffer += &Ansi::TerminalForegroundColour(*foreground).to_string();
+        }
+
+        DrawStyle::Background8Bit(background) => {
+            buffer += &Ansi::TerminalBackgroundColour(*background).to_string();
+        }
+
+        DrawStyle::Default => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::Reset]).to_string();
+        }
+
+        DrawStyle::Bold => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::Bold]).to_string();
+        }
+
+        DrawStyle::Italic => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::Italic]).to_string();
+        }
+
+        DrawStyle::Underline => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::Underline]).to_string();
+        }
+
+        DrawStyle::Reverse => {
+            buffer += &Ansi::Sgr(vec![SgrParameter::ReverseVideo]).to_string();
+        }
+
+        DrawStyle::Compound {
+            foreground,
+            background,
+            bold,
+            italic,
+            underline,
+        } => {
+            let parameters: Vec<_> = [
+                bold.then_some(SgrParameter::Bold),
+                italic.then_some(SgrParameter::Italic),
+                underline.then_some(SgrParameter::Underline),
+                foreground.map(SgrParameter::HexForegroundColour),
+                background.map(SgrParameter::HexBackgroundColour),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if !parameters.is_empty() {
+                buffer += &Ansi::Sgr(parameters).to_string();
+            }
+        }
+    }
+
+    buffer
+}
+
 mod ioctls {
+    use std::os::fd::RawFd;
+
     use crate::{Dimension, Dimensions};
 
-    pub fn get_terminal_size() -> Dimensions {
+    /// The size of the terminal on `fd`, or 80x24 if it isn't one.
+    pub fn get_terminal_size(fd: RawFd) -> Dimensions {
         use std::mem::zeroed;
 
         // Safety: Unfortuantely no other way to do this, ioctls suck.
         #[allow(unsafe_code)]
         unsafe {
             let mut size: libc::winsize = zeroed();
-            match libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) {
+            match libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) {
                 0 => (size.ws_col as Dimension, size.ws_row as Dimension),
                 _ => (80, 24),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::os::fd::AsFd;
+
+    use super::TerminalRenderer;
+    use crate::{DrawCommand, Renderer};
+
+    use eyre::Result;
+
+    #[test]
+    fn test_is_a_renderer() {
+        let mut renderer: Box<dyn Renderer> = Box::new(TerminalRenderer::new());
+        renderer.set_width(12);
+        renderer.set_height(34);
+        assert_eq!((12, 34), renderer.dimensions());
+    }
+
+    /// What `file` is a handle to.
+    fn file_id(file: &File) -> Result<(u64, u64)> {
+        use std::os::unix::fs::MetadataExt;
+
+        let metadata = file.metadata()?;
+        Ok((metadata.dev(), metadata.ino()))
+    }
+
+    #[test]
+    fn test_renderers_for_other_outputs() -> Result<()> {
+        // Sized by stderr, or the fallback if it isn't a terminal.
+        let renderer = TerminalRenderer::to_stderr();
+        assert_eq!(super::ioctls::get_terminal_size(2), renderer.dimensions());
+        // And restored by guards there, not on stdout.
+        let terminal = renderer.terminal()?.expect("renders to a terminal");
+        let stderr = File::from(std::io::stderr().as_fd().try_clone_to_owned()?);
+        assert_eq!(file_id(&stderr)?, file_id(&terminal)?);
+
+        // There may not be a controlling terminal to open.
+        match TerminalRenderer::to_tty() {
+            Ok(renderer) => assert_eq!(
+                super::ioctls::get_terminal_size(renderer.terminal.as_raw_fd()),
+                renderer.dimensions()
+            ),
+            Err(_) => assert!(std::fs::File::open("/dev/tty").is_err()),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frame_is_buffered_until_committed() -> Result<()> {
+        let mut renderer = TerminalRenderer::new();
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("henol world".into())]))
+            .await?;
+
+        let frame = renderer.frame.as_ref().expect("frame should be buffered");
+        assert!(frame.contains("henol world"));
+
+        let committed = renderer.commit_frame().expect("frame should commit");
+        assert!(committed.starts_with("\x1b[?2026h"));
+        assert!(committed.ends_with("\x1b[?2026l"));
+        assert!(committed.contains("henol world"));
+        assert!(renderer.frame.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frame_without_synchronized_output() -> Result<()> {
+        let mut renderer = TerminalRenderer::new();
+        renderer.set_synchronized_output(false);
+        renderer.set_diffing(false);
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("henol world".into())]))
+            .await?;
+
+        let committed = renderer.commit_frame().expect("frame should commit");
+        assert!(!committed.contains("\x1b[?2026h"));
+        assert!(committed.ends_with("henol world"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_styles_are_reset_at_line_ends() -> Result<()> {
+        use crate::DrawStyle;
+
+        let mut renderer = TerminalRenderer::new();
+        renderer.set_synchronized_output(false);
+        renderer.set_diffing(false);
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::Style(DrawStyle::Background(0xff0000)),
+                    DrawCommand::TextUnderCursor("ab\ncd".into()),
+                    DrawCommand::Style(DrawStyle::Default),
+                    DrawCommand::TextUnderCursor("\nef".into()),
+                ],
+            ))
+            .await?;
+
+        let committed = renderer.commit_frame().expect("frame should commit");
+        assert!(committed.ends_with("ab\x1b[0m\n\x1b[48;2;255;0;0mcd\x1b[0m\nef"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compound_styles_are_one_sequence() {
+        use crate::DrawStyle;
+
+        assert_eq!(
+            "\x1b[1;38;2;255;0;0m",
+            super::style_sequence(&DrawStyle::Compound {
+                foreground: Some(0xff0000),
+                background: None,
+                bold: true,
+                italic: false,
+                underline: false,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_only_changed_cells_are_redrawn() -> Result<()> {
+        use crate::Ansi;
+
+        let mut renderer = TerminalRenderer::new();
+        renderer.set_synchronized_output(false);
+
+        let first = render_frame(&mut renderer, 'a').await?;
+        assert!(first.contains("henol world"));
+        assert!(first.ends_with(&format!("a{}", Ansi::CursorPosition(1, 10))));
+
+        // Only the last character changed.
+        assert_eq!(
+            format!(
+                "{}b{}",
+                Ansi::CursorPosition(0, 10),
+                Ansi::CursorPosition(1, 10)
+            ),
+            render_frame(&mut renderer, 'b').await?
+        );
+
+        // Nothing changed.
+        assert_eq!("", render_frame(&mut renderer, 'b').await?);
+
+        Ok(())
+    }
+
+    /// Render ten lines of text and then `last`, returning the output.
+    async fn render_frame(renderer: &mut TerminalRenderer, last: char) -> Result<String> {
+        let mut text = "henol world\n".repeat(10);
+        text.push(last);
+
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 0, y: 0 },
+                    DrawCommand::TextUnderCursor(text),
+                ],
+            ))
+            .await?;
+
+        Ok(renderer.commit_frame().unwrap_or_default())
+    }
+
+    #[tokio::test]
+    async fn test_terminal_modes_are_only_switched_when_they_change() -> Result<()> {
+        use crate::test::SharedBuffer;
+
+        let buffer = SharedBuffer::default();
+        let mut renderer = TerminalRenderer::with_writer(Box::new(buffer.clone()));
+        renderer.set_synchronized_output(false);
+        let commands = vec![
+            DrawCommand::BracketedPaste(true),
+            DrawCommand::MouseReporting(true),
+            DrawCommand::AlternateScreen(true),
+        ];
+
+        renderer.render_one((0, commands.clone())).await?;
+        assert!(buffer
+            .contents()
+            .starts_with("\x1b[?2004h\x1b[?1000;1006h\x1b[?1049h\x1b[2J"));
+
+        buffer.0.lock().unwrap().clear();
+        renderer.render_one((0, commands)).await?;
+        assert!(!buffer.contents().contains("\x1b[?"));
+
+        buffer.0.lock().unwrap().clear();
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MouseReporting(false),
+                    DrawCommand::AlternateScreen(false),
+                ],
+            ))
+            .await?;
+        assert!(buffer.contents().starts_with("\x1b[?1000;1006l\x1b[?1049l"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rendered_text_can_be_read_back() -> Result<()> {
+        let mut renderer = TerminalRenderer::with_writer(Box::new(std::io::sink()));
+        renderer.set_diffing(false);
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((
+                0,
+                vec![
+                    DrawCommand::MoveCursorAbsolute { x: 2, y: 1 },
+                    DrawCommand::TextUnderCursor("henol world".into()),
+                ],
+            ))
+            .await?;
+        renderer.end_frame().await?;
+
+        assert_eq!("henol world", renderer.read_string(2, 1, 11).await?);
+        assert_eq!((13, 1), renderer.cursor());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_relative_frames_are_read_back_where_they_were_drawn() -> Result<()> {
+        let mut renderer = TerminalRenderer::with_writer(Box::new(std::io::sink()));
+        renderer.set_diffing(false);
+        let frame = |text: &str| {
+            (
+                0,
+                vec![
+                    DrawCommand::MoveCursorRelative { x: 1, y: 1 },
+                    DrawCommand::TextUnderCursor(text.into()),
+                ],
+            )
+        };
+
+        // Both frames start from the saved cursor, ie. (0, 0), on screen.
+        for text in ["henol", "world"] {
+            renderer.begin_frame().await?;
+            renderer.render_one(frame(text)).await?;
+            renderer.end_frame().await?;
+        }
+
+        assert_eq!("world", renderer.read_string(1, 1, 5).await?);
+        assert_eq!("", renderer.read_string(6, 2, 5).await?.trim());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_output_goes_to_the_writer() -> Result<()> {
+        use crate::test::SharedBuffer;
+
+        let buffer = SharedBuffer::default();
+        let mut renderer = TerminalRenderer::with_writer(Box::new(buffer.clone()));
+        renderer.set_synchronized_output(false);
+        renderer.set_diffing(false);
+
+        renderer.begin_frame().await?;
+        renderer
+            .render_one((0, vec![DrawCommand::TextUnderCursor("henol".into())]))
+            .await?;
+        // Nothing is written until the frame is committed.
+        assert!(buffer.0.lock().unwrap().is_empty());
+        renderer.end_frame().await?;
+        renderer.move_cursor(1, 2).await?;
+
+        let output = buffer.contents();
+        assert_eq!(
+            format!(
+                "{}henol{}",
+                crate::Ansi::SaveCursorPosition,
+                crate::Ansi::CursorPosition(1, 2)
+            ),
+            output
+        );
+
+        Ok(())
+    }
+}
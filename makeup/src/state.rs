@@ -0,0 +1,73 @@
+//! Preserving components' runtime state across hot-reloads.
+//!
+//! Components opt in with [`Component::state_snapshot`] and
+//! [`Component::restore_state`]. Component keys are generated afresh for
+//! every instance, so snapshots of a tree are keyed by each component's path
+//! from the root instead, ie. the index of each child along the way. A
+//! reloaded tree of the same shape gets its state back where it left off.
+
+use std::collections::HashMap;
+
+use eyre::Result;
+
+use crate::Component;
+
+/// The runtime state of a component tree, keyed by each component's path
+/// from the root. The root's path is empty.
+pub type StateSnapshot = HashMap<Vec<usize>, serde_json::Value>;
+
+/// Snapshot the state of every component in the tree that opts in.
+pub fn snapshot_tree<M: std::fmt::Debug + Send + Sync + Clone>(
+    root: &dyn Component<Message = M>,
+) -> StateSnapshot {
+    let mut snapshot = HashMap::new();
+    snapshot_recursive(root, &mut vec![], &mut snapshot);
+    snapshot
+}
+
+fn snapshot_recursive<M: std::fmt::Debug + Send + Sync + Clone>(
+    component: &dyn Component<Message = M>,
+    path: &mut Vec<usize>,
+    snapshot: &mut StateSnapshot,
+) {
+    if let Some(state) = component.state_snapshot() {
+        snapshot.insert(path.clone(), state);
+    }
+
+    if let Some(children) = component.children() {
+        for (i, child) in children.into_iter().enumerate() {
+            path.push(i);
+            snapshot_recursive(child.as_ref(), path, snapshot);
+            path.pop();
+        }
+    }
+}
+
+/// Restore a [`snapshot_tree`] into a tree, ex. one rebuilt after a reload.
+/// Components at paths without state are left as they are.
+pub fn restore_tree<M: std::fmt::Debug + Send + Sync + Clone>(
+    root: &mut dyn Component<Message = M>,
+    snapshot: &StateSnapshot,
+) -> Result<()> {
+    restore_recursive(root, &mut vec![], snapshot)
+}
+
+fn restore_recursive<M: std::fmt::Debug + Send + Sync + Clone>(
+    component: &mut dyn Component<Message = M>,
+    path: &mut Vec<usize>,
+    snapshot: &StateSnapshot,
+) -> Result<()> {
+    if let Some(state) = snapshot.get(path) {
+        component.restore_state(state.clone())?;
+    }
+
+    if let Some(children) = component.children_mut() {
+        for (i, child) in children.into_iter().enumerate() {
+            path.push(i);
+            restore_recursive(child.as_mut(), path, snapshot)?;
+            path.pop();
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use crate::components::EchoText;
-use crate::DrawCommand;
+use crate::{Dimension, Dimensions, DrawCommand};
 
 use eyre::Result;
 
@@ -118,38 +118,90 @@ pub struct VisualDiff {
 
 impl VisualDiff {
     pub async fn new(diff: &DrawCommandDiff) -> Result<Self> {
-        use crate::render::Renderer;
-
-        async fn read_lines(renderer: &dyn Renderer) -> Vec<String> {
-            let mut out = vec![];
-
-            for i in 0..renderer.dimensions().1 {
-                let line = renderer
-                    .read_string(0, i, renderer.dimensions().0 - 1)
-                    .await
-                    .unwrap()
-                    .trim_end()
-                    .to_string();
-                out.push(line);
+        use crate::render::memory::OverflowBehaviour;
+        use crate::render::{MemoryRenderer, Renderer};
+
+        /// Renderer dimensions that can hold where the commands move the
+        /// cursor to. Text only counts one column per character, so the
+        /// renderers grow to fit wide characters and tabs.
+        fn content_dimensions(commands: &[DrawCommand]) -> Dimensions {
+            let (mut x, mut y) = (0i64, 0i64);
+            let (mut max_x, mut max_y) = (0i64, 0i64);
+
+            let put = |c: char, x: &mut i64, y: &mut i64| {
+                if c == '\n' {
+                    *x = 0;
+                    *y += 1;
+                } else {
+                    *x += 1;
+                }
+            };
+
+            for command in commands {
+                match command {
+                    DrawCommand::TextUnderCursor(text) => {
+                        for c in text.chars() {
+                            put(c, &mut x, &mut y);
+                            max_x = max_x.max(x);
+                            max_y = max_y.max(y);
+                        }
+                    }
+                    DrawCommand::CharUnderCursor(c) => put(*c, &mut x, &mut y),
+                    DrawCommand::MoveCursorRelative { x: dx, y: dy } => {
+                        x += dx;
+                        y += dy;
+                    }
+                    DrawCommand::MoveCursorAbsolute { x: ax, y: ay } => {
+                        x = *ax as i64;
+                        y = *ay as i64;
+                    }
+                    _ => {}
+                }
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
             }
 
+            // Writing a character bounds-checks the column after it, and a
+            // newline bounds-checks the row after it.
+            (max_x as Dimension + 2, max_y as Dimension + 2)
+        }
+
+        fn read_lines(renderer: &MemoryRenderer) -> Result<Vec<String>> {
+            let width = renderer.dimensions().0;
+            let height = renderer
+                .grid()
+                .keys()
+                .map(|(_, y)| y + 1)
+                .max()
+                .unwrap_or(0);
+
+            let mut out: Vec<String> = renderer
+                .read_region(0, 0, width, height)?
+                .into_iter()
+                .map(|line| line.trim_end().to_string())
+                .collect();
+
             while out.last().map(|s| s.is_empty()).unwrap_or(false) {
                 out.pop();
             }
 
-            out
+            Ok(out)
         }
 
-        let mut expected_renderer = crate::render::MemoryRenderer::new(128, 128);
-        let mut actual_renderer = crate::render::MemoryRenderer::new(128, 128);
+        let (width, height) = content_dimensions(&diff.expected);
+        let mut expected_renderer = MemoryRenderer::new(width, height);
+        expected_renderer.set_overflow_behaviour(OverflowBehaviour::Grow);
+        let (width, height) = content_dimensions(&diff.actual);
+        let mut actual_renderer = MemoryRenderer::new(width, height);
+        actual_renderer.set_overflow_behaviour(OverflowBehaviour::Grow);
 
         expected_renderer
-            .render(&[(0, diff.expected.clone())])
+            .render_one((0, diff.expected.clone()))
             .await?;
-        actual_renderer.render(&[(0, diff.actual.clone())]).await?;
+        actual_renderer.render_one((0, diff.actual.clone())).await?;
 
-        let expected_lines = read_lines(&expected_renderer).await;
-        let actual_lines = read_lines(&actual_renderer).await;
+        let expected_lines = read_lines(&expected_renderer)?;
+        let actual_lines = read_lines(&actual_renderer)?;
 
         let expected_text = expected_lines.join("\n");
         let actual_text = actual_lines.join("\n");
@@ -328,6 +380,7 @@ mod tests {
     use async_trait::async_trait;
     use eyre::Result;
 
+    use super::DrawCommandDiff;
     use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
     use crate::test::{assert_renders_many, static_text};
     use crate::{Component, Dimensions, DrawCommand};
@@ -402,4 +455,43 @@ mod tests {
 
         __do_test().await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_visual_diff_output_is_stable() -> Result<()> {
+        let diff = DrawCommandDiff::new(
+            vec![
+                static_text!("line 1\n"),
+                static_text!("line 2\n"),
+                static_text!("line 3\n"),
+            ],
+            vec![
+                static_text!("line 1    \n"),
+                static_text!("lime 2\n"),
+                static_text!("line 3 and more\n"),
+                DrawCommand::MoveCursorAbsolute { x: 4, y: 5 },
+                static_text!("line 6"),
+            ],
+        );
+        let diff = diff.into_visual_diff().await?;
+
+        assert!(diff.is_different());
+        assert_eq!(
+            "test ui did not match expected output!!!\n\nvisual diff:\n\n----------------\n\nexpected:\n\nline 1\nline 2\nline 3\n\n----------------\n\nactual:\n\nline 1\nlime 2\nline 3 and more\n\n\n    line 6\n\n----------------\n\ndiff:\n\n\u{1b}[0mline 1\u{1b}[0m\n\u{1b}[0mli\u{1b}[48;2;255;0;0mm\u{1b}[0m\u{1b}[0me 2\u{1b}[0m\n\u{1b}[0mline 3\u{1b}[48;2;255;0;0m and more\u{1b}[0m\u{1b}[0m\u{1b}[0m\n\u{1b}[48;2;255;0;0m\u{1b}[0m\u{1b}[0m\n\u{1b}[48;2;255;0;0m\u{1b}[0m\u{1b}[0m\n\u{1b}[48;2;255;0;0m    line 6\u{1b}[0m\u{1b}[0m\n\n----------------\n",
+            diff.rendered_diff
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_visual_diff_handles_wide_text_and_tabs() -> Result<()> {
+        let diff = DrawCommandDiff::new(vec![static_text!("日本語")], vec![static_text!("a\tb")]);
+        let diff = diff.into_visual_diff().await?;
+
+        assert!(diff.is_different());
+        assert!(diff.rendered_diff.contains("日本語"));
+        assert!(diff.rendered_diff.contains("a       b"));
+
+        Ok(())
+    }
 }
@@ -102,6 +102,31 @@ macro_rules! __THIS_IS_NOT_PUBLIC_DO_NOT_CALL_make_test_ui {
 #[doc(inline)]
 pub use __THIS_IS_NOT_PUBLIC_DO_NOT_CALL_make_test_ui as make_test_ui;
 
+/// A writer that can be cloned and read back from, ex. to capture what a
+/// writer-backed renderer wrote.
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct SharedBuffer(pub std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl SharedBuffer {
+    /// Everything written so far.
+    pub fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).expect("output is UTF-8")
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 #[doc(hidden)]
 pub fn fake_render_ctx() -> crate::component::RenderContext {
     crate::component::RenderContext {
@@ -112,5 +137,27 @@ pub fn fake_render_ctx() -> crate::component::RenderContext {
         cursor: (0, 0),
         dimensions: (0, 0),
         focus: 0,
+        blink_on: true,
+        reduced_motion: false,
+        app_focused: true,
+        resized: false,
+        path: vec![],
+        key_hints: vec![],
     }
 }
+
+/// Render `component` once to a `width`x`height` grid and return what was
+/// drawn, one line per row, with trailing whitespace and blank rows trimmed.
+///
+/// Unlike [`assert_renders_one`], this doesn't need an async runtime, so
+/// simple render assertions can be plain `#[test]`s.
+#[cfg(test)]
+pub fn render_sync<M: std::fmt::Debug + Send + Sync + Clone + 'static>(
+    component: &mut dyn crate::Component<Message = M>,
+    width: crate::Dimension,
+    height: crate::Dimension,
+) -> eyre::Result<String> {
+    let (text, _overflowed) =
+        futures_executor::block_on(crate::util::render_to_buffer(component, width, height))?;
+    Ok(text)
+}
@@ -14,18 +14,55 @@ use tokio::sync::{Mutex, RwLock};
 use tokio::time::Instant;
 
 use crate::component::{
-    DrawCommandBatch, Key, MakeupMessage, MessageSender, RenderContext, UpdateContext,
+    DrawCommandBatch, Key, MakeupMessage, MessageSender, RawComponentMessage, RenderContext,
+    UpdateContext,
 };
 use crate::input::{InputFrame, TerminalInput};
-use crate::post_office::PostOffice;
-use crate::{
-    Ansi, Component, Coordinates, Dimensions, DisplayEraseMode, DrawCommand, Input, Renderer,
-};
+use crate::post_office::{MessageKind, PostOffice};
+use crate::render::clip::{clip_commands, ClipRect};
+use crate::render::TerminalGuard;
+use crate::{Component, Coordinates, Dimensions, DrawCommand, Input, Renderer};
 
 #[derive(Debug, Clone)]
 pub enum UiControlMessage {
     MoveFocus(Key),
+    /// Move focus to the next component that
+    /// [accepts focus](Component::accepts_focus), in tree order, wrapping
+    /// around to the first. Sent for Tab.
+    FocusNext,
+    /// Move focus to the previous component that accepts focus, wrapping
+    /// around to the last. Sent for Shift-Tab.
+    FocusPrevious,
     StopRendering,
+    /// Render the next frame, even if nothing seems to have changed. Only
+    /// needed for [`MUI::render_on_demand`].
+    RequestRender,
+}
+
+/// What a key bound with [`MUI::bind`] does when it's pressed.
+#[derive(Debug, Clone)]
+pub enum Binding<M> {
+    /// Send a control message to the UI, ex. [`UiControlMessage::StopRendering`].
+    Control(UiControlMessage),
+    /// Send a message to the component with the given key.
+    Message(Key, M),
+}
+
+impl<M> From<UiControlMessage> for Binding<M> {
+    fn from(message: UiControlMessage) -> Self {
+        Self::Control(message)
+    }
+}
+
+/// Where a [`MUI`] draws. See [`MuiBuilder::screen`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScreenMode {
+    /// Draw in the terminal's normal screen, below whatever's already there.
+    #[default]
+    Inline,
+    /// Switch to the terminal's alternate screen while rendering, leaving the
+    /// normal screen as it was once the UI stops.
+    Alternate,
 }
 
 #[derive(Debug)]
@@ -36,7 +73,21 @@ pub enum RenderState {
 
 pub type RwLocked<T> = Arc<RwLock<T>>;
 
+/// The frames per second the UI aims to render at by default. See
+/// [`MUI::set_fps_target`].
 pub const FPS_TARGET: u64 = 60;
+/// How many components are visited during a single update or render pass
+/// before yielding back to the async runtime. See [`MUI::set_yield_interval`].
+pub const DEFAULT_YIELD_INTERVAL: usize = 256;
+/// The base interval of the UI's global ticker. See [`MUI::set_tick_interval`].
+pub const DEFAULT_TICK_INTERVAL: Duration = Duration::from_millis(10);
+/// The most base intervals the global ticker catches up on in one update.
+/// Any more that have elapsed, ex. while the machine was suspended, are
+/// dropped.
+const MAX_TICKS_PER_UPDATE: u64 = 4;
+/// How often blinking components toggle between visible and hidden. See
+/// [`MUI::set_blink_interval`].
+pub const DEFAULT_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 pub const ONE_SECOND_IN_MICROS: u128 = Duration::from_secs(1).as_micros();
 
 /// A makeup UI. Generally used with [`crate::render::TerminalRenderer`].
@@ -57,6 +108,7 @@ pub struct MUI<
     input_rx: Arc<Mutex<UnboundedReceiver<InputFrame>>>,
     input: I,
     done: Arc<Mutex<bool>>,
+    screen: ScreenMode,
 }
 
 impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M, I> {
@@ -75,30 +127,70 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
             input_rx: Arc::new(Mutex::new(input_rx)),
             input,
             done: Arc::new(Mutex::new(false)),
+            screen: ScreenMode::default(),
         })
     }
 
+    /// Render this MUI in a loop, forever, in the [`ScreenMode`] it was
+    /// built with. See [`MUI::render`].
+    pub async fn run(&'a self) -> Result<RenderState> {
+        self.render(self.screen == ScreenMode::Alternate).await
+    }
+
     /// Render this MUI in a loop, forever. This will:
     /// - Move the cursor to (0, 0)
     /// - Enter alternate screen mode
     /// - Clear the screen
     /// - Update components by applying any `Mailbox`es
     /// - Render the UI
+    ///
     /// The MUI will attempt to render at 60fps, sleeping as needed to stay at
-    /// the frame target.
+    /// the frame target. A `SIGTERM` or `SIGINT` stops rendering the same way
+    /// as [`MUI::shutdown`], so that the terminal is restored rather than left
+    /// in raw mode. Once rendering stops, those signals are handled however
+    /// they were before it started, so by default they terminate the program
+    /// again. When rendering to the alternate `screen`, a
+    /// [`TerminalGuard`] restores the terminal the renderer draws on even if
+    /// rendering fails or panics.
     pub async fn render(&'a self, screen: bool) -> Result<RenderState> {
+        self.render_loops(screen).await
+    }
+
+    /// [`MUI::render`], but only rendering frames when something might have
+    /// changed, rather than every frame, to save CPU in mostly-static UIs.
+    ///
+    /// A frame is rendered after input, after any message is sent to a
+    /// component, including the [`MakeupMessage::TimerTick`]s that animated
+    /// components ask for, after the blink clock toggles, and after
+    /// [`UpdateContext::request_render`] or [`MUI::invalidate`]. Components
+    /// that change on their own without any of those must request a render.
+    ///
+    /// [`UpdateContext::request_render`]: crate::component::UpdateContext::request_render
+    pub async fn render_on_demand(&'a self, screen: bool) -> Result<RenderState> {
+        self.ui.lock().await.on_demand = true;
+        self.render_loops(screen).await
+    }
+
+    async fn render_loops(&'a self, screen: bool) -> Result<RenderState> {
         {
             let done = self.done.lock().await;
             if *done {
                 return Ok(RenderState::Stopped);
             }
         }
-        if screen {
-            // Enter alternate screen
-            print!("\x1b[?1049h");
-            // Clear screen
-            print!("{}", Ansi::EraseInDisplay(DisplayEraseMode::All));
-        }
+        // Held until the terminal is restored, so that a signal can't kill the
+        // program before then.
+        let signal_handlers = SignalHandlers::claim();
+        let shutdown = shutdown_signal()?;
+        let mut guard = if screen {
+            self.enter_alt_screen().await?;
+            // Restoring goes to the same terminal as rendering, which isn't
+            // always stdout.
+            let terminal = self.renderer.read().await.terminal()?;
+            terminal.map(|terminal| TerminalGuard::for_terminal(terminal, true))
+        } else {
+            None
+        };
 
         let mut last_frame_time = None;
         let mut last_fps: f64 = 0f64;
@@ -139,7 +231,11 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
             })
         };
 
-        let frame_target = Duration::from_micros((ONE_SECOND_IN_MICROS as u64) / FPS_TARGET);
+        let done_for_signals = self.done.clone();
+        let signal_handle = tokio::spawn(async move {
+            shutdown.await;
+            *done_for_signals.lock().await = true;
+        });
 
         'run_loop: loop {
             tokio::select! {
@@ -161,10 +257,13 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
                 ) => {
                     let currently_exiting = match render_res {
                         Ok((false, elapsed)) => {
-                            if let Some(duration) = frame_target.checked_sub(elapsed) {
+                            let sleep = self.ui.lock().await.pacer.sleep_for(elapsed, Instant::now());
+                            if let Some(duration) = sleep {
                                 tokio::time::sleep(duration).await;
                             } else {
-                                // log::warn!("Frame took too long to render: {:?}", elapsed);
+                                // Uncapped, or the frame took too long to
+                                // render, but give input a chance either way.
+                                tokio::task::yield_now().await;
                             }
                             false
                         }
@@ -198,23 +297,37 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
                     dimensions,
                     // Default values, these are filled in by the inner render method.
                     focus: 0,
+                    blink_on: true,
+                    reduced_motion: false,
+                    app_focused: true,
+                    resized: false,
+                    path: vec![],
+                    key_hints: vec![],
                 })
                 .await?;
                 input_handle.abort();
+                signal_handle.abort();
+                self.ui.lock().await.stop().await?;
                 break 'run_loop;
             }
         }
 
-        if screen {
-            // Leave alternate screen
-            print!("\x1b[?1049l");
+        if let Some(guard) = &mut guard {
+            guard.restore()?;
         }
+        drop(signal_handlers);
 
         self.flush_renderer().await?;
         Ok(RenderState::Stopped)
     }
 
     async fn update_loop(&'a self) -> Result<()> {
+        {
+            let mut ui = self.ui.lock().await;
+            let ticks = ui.ticker.elapsed_ticks(Instant::now());
+            ui.tick(ticks).await;
+        }
+
         let mut pending_input = vec![];
         let mut rx = self.input_rx.lock().await;
 
@@ -252,6 +365,14 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         dimensions: &Dimensions,
     ) -> Result<(bool, Duration)> {
         let start = Instant::now();
+        {
+            let mut ui = self.ui.lock().await;
+            if ui.on_demand && !ui.dirty {
+                return Ok((ui.exiting, Duration::ZERO));
+            }
+            ui.dirty = false;
+        }
+
         let mut render_context = RenderContext {
             last_frame_time: *last_frame_time,
             frame_counter: *frame_counter,
@@ -261,6 +382,12 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
             dimensions: *dimensions,
             // Default values, these are filled in by the inner render method.
             focus: 0,
+            blink_on: true,
+            reduced_motion: false,
+            app_focused: true,
+            resized: false,
+            path: vec![],
+            key_hints: vec![],
         };
 
         let currently_exiting = match self.render_frame(&mut render_context).await {
@@ -278,8 +405,9 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         *last_frame_time = Some(elapsed);
         *effective_fps = (ONE_SECOND_IN_MICROS as f64) / (elapsed.as_micros() as f64);
         *frame_counter += 1;
-        *last_fps = if *effective_fps as u64 > FPS_TARGET {
-            FPS_TARGET as f64
+        let fps_target = self.ui.lock().await.pacer.fps();
+        *last_fps = if fps_target > 0 && *effective_fps as u64 > fps_target {
+            fps_target as f64
         } else {
             *effective_fps
         };
@@ -299,6 +427,12 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         Ok(())
     }
 
+    /// Stop rendering after the current frame, tearing the UI down as if
+    /// the process had been sent a `SIGTERM`.
+    pub async fn shutdown(&self) {
+        *self.done.lock().await = true;
+    }
+
     pub async fn render_once(&'a self) -> Result<RenderState> {
         let mut ctx = {
             let renderer = self.renderer.read().await;
@@ -310,6 +444,12 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
                 cursor: renderer.cursor(),
                 dimensions: renderer.dimensions(),
                 focus: 0,
+                blink_on: true,
+                reduced_motion: false,
+                app_focused: true,
+                resized: false,
+                path: vec![],
+                key_hints: vec![],
             }
         };
 
@@ -328,8 +468,9 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         let commands = ui.render(ctx).await?;
 
         let mut renderer = self.renderer.write().await;
+        renderer.begin_frame().await?;
         renderer.render(&commands).await?;
-        renderer.flush().await?;
+        renderer.end_frame().await?;
 
         Ok(ui.exiting)
     }
@@ -362,6 +503,25 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         ui.send_makeup(key, message).await;
     }
 
+    /// Send a message to every component in the tree, ex. to tell them all
+    /// that the theme has changed.
+    pub async fn broadcast(&self, message: M) {
+        let ui = self.ui.lock().await;
+        let mut post_office = ui.post_office.write().await;
+        for key in UI::get_all_child_keys(ui.root) {
+            post_office.send(key, message.clone());
+        }
+    }
+
+    /// Send a makeup message to every component in the tree.
+    pub async fn broadcast_makeup(&self, message: MakeupMessage) {
+        let ui = self.ui.lock().await;
+        let mut post_office = ui.post_office.write().await;
+        for key in UI::get_all_child_keys(ui.root) {
+            post_office.send_makeup(key, message.clone());
+        }
+    }
+
     /// Send a message to the UI.
     pub async fn send_control(&self, message: UiControlMessage) {
         let ui = self.ui.lock().await;
@@ -380,7 +540,143 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
         renderer.read_at_cursor(count).await
     }
 
-    #[cfg(test)]
+    /// Advance the global ticker by one base interval, delivering
+    /// [`MakeupMessage::TimerTick`]s to any components that are due one. This
+    /// is done automatically by [`MUI::render`], but is useful when driving the
+    /// UI manually.
+    pub async fn tick(&self) {
+        let mut ui = self.ui.lock().await;
+        ui.tick(1).await;
+    }
+
+    /// Set the base interval of the global ticker. Components asking for
+    /// ticks via [`Component::tick_interval`] are ticked on multiples of
+    /// this interval.
+    pub async fn set_tick_interval(&self, interval: Duration) {
+        let mut ui = self.ui.lock().await;
+        ui.ticker.interval = interval;
+    }
+
+    /// Set how often blinking components toggle between visible and hidden.
+    /// The blink clock is driven by the global ticker.
+    pub async fn set_blink_interval(&self, interval: Duration) {
+        let mut ui = self.ui.lock().await;
+        ui.blink_interval = interval;
+    }
+
+    /// Aim to render `fps` frames per second. Defaults to [`FPS_TARGET`]. A
+    /// target of 0 renders as fast as possible.
+    pub async fn set_fps_target(&self, fps: u64) {
+        let mut ui = self.ui.lock().await;
+        ui.pacer.target = frame_duration(fps);
+    }
+
+    /// The frames per second the UI aims to render at when it isn't idle, or
+    /// 0 if it's uncapped.
+    pub async fn fps_target(&self) -> u64 {
+        let ui = self.ui.lock().await;
+        ui.pacer.fps()
+    }
+
+    /// Drop to `fps` frames per second once there's been no input for
+    /// `after`, to save CPU while nobody's interacting with the UI. The
+    /// framerate returns to the target on the next input or
+    /// [`MUI::invalidate`]. Disabled by default.
+    pub async fn set_idle_framerate(&self, fps: u64, after: Duration) {
+        let mut ui = self.ui.lock().await;
+        ui.pacer.idle = Some((frame_duration(fps), after));
+    }
+
+    /// Enter the alternate screen and clear it, redrawing everything on the
+    /// next frame. [`MUI::render`] does this itself when rendering to the
+    /// alternate screen.
+    pub async fn enter_alt_screen(&self) -> Result<()> {
+        self.renderer
+            .write()
+            .await
+            .set_alternate_screen(true)
+            .await?;
+        self.invalidate().await;
+        Ok(())
+    }
+
+    /// Leave the alternate screen, going back to whatever was on the normal
+    /// screen before it was entered.
+    pub async fn leave_alt_screen(&self) -> Result<()> {
+        self.renderer
+            .write()
+            .await
+            .set_alternate_screen(false)
+            .await
+    }
+
+    /// Leave the alternate screen, run `f`, and enter it again, ex. to hand
+    /// the terminal to an external editor for a while. Returns what `f` does.
+    pub async fn suspend<T>(&self, f: impl FnOnce() -> T) -> Result<T> {
+        self.leave_alt_screen().await?;
+        let out = f();
+        self.enter_alt_screen().await?;
+        Ok(out)
+    }
+
+    /// Stop rendering at the idle framerate, if the UI is idle, as if there
+    /// had been input, and render the next frame even if rendering on
+    /// demand. See [`MUI::set_idle_framerate`] and
+    /// [`MUI::render_on_demand`].
+    pub async fn invalidate(&self) {
+        let mut ui = self.ui.lock().await;
+        ui.pacer.activity(Instant::now());
+        ui.dirty = true;
+    }
+
+    /// Set whether motion should be reduced. When enabled, blinking
+    /// components are always visible, and animated components stand still.
+    pub async fn set_reduced_motion(&self, reduced_motion: bool) {
+        let mut ui = self.ui.lock().await;
+        ui.reduced_motion = reduced_motion;
+    }
+
+    /// Set the order that the kinds of message are delivered to components in
+    /// within a frame. See [`PostOffice::set_delivery_order`].
+    pub async fn set_delivery_order(&self, order: Vec<MessageKind>) {
+        let ui = self.ui.lock().await;
+        ui.post_office.write().await.set_delivery_order(order);
+    }
+
+    /// Bind `keypress` globally, so that pressing it does what `binding` says
+    /// no matter which component has focus. Bound keys are checked before
+    /// input is mailed to the focused component, and never reach it. Binding
+    /// a key again replaces its binding.
+    ///
+    /// Keys held with Ctrl can be bound as ex.
+    /// `Keypress::Modified(Modifiers::CTRL, Box::new(Keypress::Char('q')))`,
+    /// which also matches the control character the terminal sends for them.
+    /// Some of those are read as other keys first, so these never match:
+    /// - Ctrl+A and Ctrl+E, read as [`Keypress::Home`] and [`Keypress::End`]
+    /// - Ctrl+H, read as [`Keypress::Backspace`]
+    /// - Ctrl+I, read as [`Keypress::Tab`]
+    /// - Ctrl+J and Ctrl+M, read as [`Keypress::Return`]
+    /// - Ctrl+C, which interrupts input instead
+    ///
+    /// Bind the keys they're read as instead.
+    pub async fn bind(&self, keypress: Keypress, binding: impl Into<Binding<M>>) {
+        let mut ui = self.ui.lock().await;
+        let binding = binding.into();
+        match ui.keymap.iter_mut().find(|(bound, _)| *bound == keypress) {
+            Some((_, existing)) => *existing = binding,
+            None => ui.keymap.push((keypress, binding)),
+        }
+    }
+
+    /// Set how many components are visited during a single update or render
+    /// pass before yielding back to the async runtime. Very large component
+    /// trees can otherwise starve other tasks, ex. input and timers, for the
+    /// entire pass. `0` disables yielding.
+    pub async fn set_yield_interval(&self, interval: usize) {
+        let mut ui = self.ui.lock().await;
+        ui.yield_interval = interval;
+    }
+
     pub(crate) fn renderer(&self) -> &RwLocked<Box<dyn Renderer>> {
         &self.renderer
     }
@@ -392,13 +688,306 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone, I: Input + 'static> MUI<'a, M
     }
 }
 
+/// Configures and builds a [`MUI`], as an alternative to [`MUI::new`] and
+/// its setters. An input must be given; the renderer defaults to a
+/// [`TerminalRenderer`](crate::render::TerminalRenderer).
+///
+/// ```rust,no_run
+/// # use makeup::components::EchoText;
+/// # use makeup::input::TerminalInput;
+/// # use makeup::render::MemoryRenderer;
+/// # use makeup::ui::{MuiBuilder, ScreenMode};
+/// # async fn example() -> eyre::Result<()> {
+/// let mut root = EchoText::<()>::new("henol world");
+/// let mui = MuiBuilder::new(&mut root)
+///     .renderer(Box::new(MemoryRenderer::new(80, 24)))
+///     .input(TerminalInput::new().await?)
+///     .screen(ScreenMode::Alternate)
+///     .fps_target(30)
+///     .build()
+///     .await?;
+/// mui.run().await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct MuiBuilder<
+    'a,
+    M: std::fmt::Debug + Send + Sync + Clone + 'static,
+    I: Input + 'static = TerminalInput,
+> {
+    root: &'a mut dyn Component<Message = M>,
+    renderer: Option<Box<dyn Renderer>>,
+    input: Option<I>,
+    screen: ScreenMode,
+    fps: u64,
+    idle_framerate: Option<(u64, Duration)>,
+    tick_interval: Duration,
+    blink_interval: Duration,
+    reduced_motion: bool,
+    delivery_order: Option<Vec<MessageKind>>,
+    yield_interval: usize,
+}
+
+impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> MuiBuilder<'a, M> {
+    pub fn new(root: &'a mut dyn Component<Message = M>) -> Self {
+        Self {
+            root,
+            renderer: None,
+            input: None,
+            screen: ScreenMode::default(),
+            fps: FPS_TARGET,
+            idle_framerate: None,
+            tick_interval: DEFAULT_TICK_INTERVAL,
+            blink_interval: DEFAULT_BLINK_INTERVAL,
+            reduced_motion: false,
+            delivery_order: None,
+            yield_interval: DEFAULT_YIELD_INTERVAL,
+        }
+    }
+}
+
+impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static, I: Input + 'static>
+    MuiBuilder<'a, M, I>
+{
+    pub fn renderer(mut self, renderer: Box<dyn Renderer>) -> Self {
+        self.renderer = Some(renderer);
+        self
+    }
+
+    pub fn input<J: Input + 'static>(self, input: J) -> MuiBuilder<'a, M, J> {
+        MuiBuilder {
+            root: self.root,
+            renderer: self.renderer,
+            input: Some(input),
+            screen: self.screen,
+            fps: self.fps,
+            idle_framerate: self.idle_framerate,
+            tick_interval: self.tick_interval,
+            blink_interval: self.blink_interval,
+            reduced_motion: self.reduced_motion,
+            delivery_order: self.delivery_order,
+            yield_interval: self.yield_interval,
+        }
+    }
+
+    /// Where [`MUI::run`] draws.
+    pub fn screen(mut self, screen: ScreenMode) -> Self {
+        self.screen = screen;
+        self
+    }
+
+    /// See [`MUI::set_fps_target`].
+    pub fn fps_target(mut self, fps: u64) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// See [`MUI::set_idle_framerate`].
+    pub fn idle_framerate(mut self, fps: u64, after: Duration) -> Self {
+        self.idle_framerate = Some((fps, after));
+        self
+    }
+
+    /// See [`MUI::set_tick_interval`].
+    pub fn tick_interval(mut self, interval: Duration) -> Self {
+        self.tick_interval = interval;
+        self
+    }
+
+    /// See [`MUI::set_blink_interval`].
+    pub fn blink_interval(mut self, interval: Duration) -> Self {
+        self.blink_interval = interval;
+        self
+    }
+
+    /// See [`MUI::set_reduced_motion`].
+    pub fn reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.reduced_motion = reduced_motion;
+        self
+    }
+
+    /// See [`MUI::set_delivery_order`].
+    pub fn delivery_order(mut self, order: Vec<MessageKind>) -> Self {
+        self.delivery_order = Some(order);
+        self
+    }
+
+    /// See [`MUI::set_yield_interval`].
+    pub fn yield_interval(mut self, interval: usize) -> Self {
+        self.yield_interval = interval;
+        self
+    }
+
+    /// Build the MUI. Fails if no input was given.
+    pub async fn build(self) -> Result<MUI<'a, M, I>> {
+        let input = self
+            .input
+            .ok_or_else(|| eyre::eyre!("an input is needed to build a MUI"))?;
+        let renderer = self
+            .renderer
+            .unwrap_or_else(|| Box::new(crate::render::TerminalRenderer::new()));
+
+        let mut mui = MUI::new(self.root, renderer, input)?;
+        mui.screen = self.screen;
+        mui.set_fps_target(self.fps).await;
+        if let Some((fps, after)) = self.idle_framerate {
+            mui.set_idle_framerate(fps, after).await;
+        }
+        mui.set_tick_interval(self.tick_interval).await;
+        mui.set_blink_interval(self.blink_interval).await;
+        mui.set_reduced_motion(self.reduced_motion).await;
+        if let Some(order) = self.delivery_order {
+            mui.set_delivery_order(order).await;
+        }
+        mui.set_yield_interval(self.yield_interval).await;
+
+        Ok(mui)
+    }
+}
+
+/// Listen for the process to be asked to stop, ex. by `kill`. The returned
+/// future resolves when it is.
+#[cfg(unix)]
+fn shutdown_signal() -> Result<impl std::future::Future<Output = ()>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = signal(SignalKind::terminate())?;
+    let mut interrupt = signal(SignalKind::interrupt())?;
+    Ok(async move {
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = interrupt.recv() => {}
+        }
+    })
+}
+
+/// Listen for the process to be asked to stop, ex. by Ctrl+C. The returned
+/// future resolves when it is.
+#[cfg(not(unix))]
+fn shutdown_signal() -> Result<impl std::future::Future<Output = ()>> {
+    Ok(async {
+        let _ = tokio::signal::ctrl_c().await;
+    })
+}
+
+/// The signals that [`shutdown_signal`] listens for.
+#[cfg(unix)]
+const SHUTDOWN_SIGNALS: [libc::c_int; 2] = [libc::SIGTERM, libc::SIGINT];
+
+/// How the [`SHUTDOWN_SIGNALS`] are handled, shared by every render.
+#[cfg(unix)]
+static SIGNAL_ACTIONS: std::sync::Mutex<SignalActions> = std::sync::Mutex::new(SignalActions {
+    listeners: 0,
+    previous: None,
+    installed: None,
+});
+
+#[cfg(unix)]
+struct SignalActions {
+    /// How many renders are listening for the signals.
+    listeners: usize,
+    /// How the signals were handled before any render listened for them.
+    previous: Option<[libc::sigaction; 2]>,
+    /// The handlers `tokio::signal` installed, put aside while no render is
+    /// listening.
+    installed: Option<[libc::sigaction; 2]>,
+}
+
+/// Marks a render as listening for the [`SHUTDOWN_SIGNALS`] until dropped.
+///
+/// `tokio::signal` handlers are installed once and never removed, which
+/// would stop the signals from terminating the program after rendering. So
+/// once no render is listening, the signals are handled however they were
+/// before, and `tokio`'s handlers are put back for the next render.
+struct SignalHandlers;
+
+impl SignalHandlers {
+    fn claim() -> Self {
+        #[cfg(unix)]
+        {
+            let mut actions = SIGNAL_ACTIONS.lock().unwrap();
+            if actions.listeners == 0 {
+                actions.previous =
+                    Some(SHUTDOWN_SIGNALS.map(|signal| swap_signal_action(signal, None)));
+                if let Some(installed) = &actions.installed {
+                    for (signal, action) in SHUTDOWN_SIGNALS.iter().zip(installed) {
+                        swap_signal_action(*signal, Some(action));
+                    }
+                }
+            }
+            actions.listeners += 1;
+        }
+
+        Self
+    }
+}
+
+impl Drop for SignalHandlers {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            let mut actions = SIGNAL_ACTIONS.lock().unwrap();
+            actions.listeners -= 1;
+            if actions.listeners == 0 {
+                if let Some(previous) = actions.previous.take() {
+                    let mut installed = previous;
+                    for ((signal, action), installed) in
+                        SHUTDOWN_SIGNALS.iter().zip(&previous).zip(&mut installed)
+                    {
+                        *installed = swap_signal_action(*signal, Some(action));
+                    }
+                    actions.installed = Some(installed);
+                }
+            }
+        }
+    }
+}
+
+/// Handle `signal` with `action`, if any, returning how it was handled
+/// before.
+#[cfg(unix)]
+fn swap_signal_action(signal: libc::c_int, action: Option<&libc::sigaction>) -> libc::sigaction {
+    // Safety: `sigaction` is plain data that the call overwrites, and only
+    // fails for invalid signals.
+    #[allow(unsafe_code)]
+    unsafe {
+        let mut previous: libc::sigaction = std::mem::zeroed();
+        libc::sigaction(
+            signal,
+            action.map_or(std::ptr::null(), |action| action as *const _),
+            &mut previous,
+        );
+        previous
+    }
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 struct UI<'a, M: std::fmt::Debug + Send + Sync + Clone> {
     root: &'a mut dyn Component<Message = M>,
     post_office: RwLocked<PostOffice<M>>,
     focus: Key,
+    /// The dimensions of the character grid as of the last update.
+    dimensions: Dimensions,
     exiting: bool,
+    stopped: bool,
+    yield_interval: usize,
+    ticker: Ticker,
+    blink_interval: Duration,
+    blink_on: bool,
+    reduced_motion: bool,
+    /// Whether or not the terminal has focus, as last reported by it.
+    app_focused: bool,
+    /// Whether the character grid has been resized since the last render.
+    resized: bool,
+    /// Whether frames are only rendered when `dirty`.
+    on_demand: bool,
+    /// Whether anything might have changed since the last render.
+    dirty: bool,
+    /// Global keybindings. See [`MUI::bind`].
+    keymap: Vec<(Keypress, Binding<M>)>,
+    pacer: FramePacer,
     #[derivative(Debug = "ignore")]
     taffy: Taffy,
     #[derivative(Debug = "ignore")]
@@ -421,7 +1010,20 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
             root,
             post_office: Arc::new(RwLock::new(PostOffice::new())),
             focus: focus_key,
+            dimensions: render_dimensions,
             exiting: false,
+            stopped: false,
+            yield_interval: DEFAULT_YIELD_INTERVAL,
+            ticker: Ticker::new(DEFAULT_TICK_INTERVAL),
+            blink_interval: DEFAULT_BLINK_INTERVAL,
+            blink_on: true,
+            reduced_motion: false,
+            app_focused: true,
+            resized: false,
+            on_demand: false,
+            dirty: true,
+            keymap: vec![],
+            pacer: FramePacer::new(frame_duration(FPS_TARGET)),
             taffy,
             taffy_lookup,
             _phantom: std::marker::PhantomData,
@@ -436,36 +1038,12 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
     ) -> Result<()> {
         taffy_lookup.clear();
         taffy.clear();
-        let (width, height) = if let Some((w, h)) = root.dimensions()? {
-            (Dimension::Points(w as f32), Dimension::Points(h as f32))
-        } else {
-            (Dimension::Auto, Dimension::Auto)
-        };
-
-        let style = if let Some(style) = root.style() {
-            style
-        } else {
-            Style::default()
-        };
-
-        let root_node = taffy.new_leaf(Style {
-            size: Size {
-                // TODO: Overflow???
-                width,
-                height,
-            },
-            ..style
-        })?;
+        let root_node = taffy.new_leaf(Self::layout_style(root)?)?;
         taffy_lookup.insert(root.key(), root_node);
 
         if let Some(children) = root.children() {
             for child in children {
-                Self::build_component_tree_recursive(
-                    root_node,
-                    child.as_ref(),
-                    taffy,
-                    taffy_lookup,
-                )?;
+                Self::build_component_tree_recursive(root_node, child, taffy, taffy_lookup)?;
             }
         }
 
@@ -486,36 +1064,37 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
         taffy: &mut Taffy,
         taffy_lookup: &mut HashMap<Key, Node>,
     ) -> Result<()> {
-        let (width, height) = if let Some((w, h)) = component.dimensions()? {
-            (Dimension::Points(w as f32), Dimension::Points(h as f32))
-        } else {
-            (Dimension::Auto, Dimension::Auto)
-        };
-        let style = if let Some(style) = component.style() {
-            style
-        } else {
-            Style::default()
-        };
-        let node = taffy.new_leaf(Style {
-            size: Size {
-                // TODO: Overflow???
-                width,
-                height,
-            },
-            ..style
-        })?;
+        let node = taffy.new_leaf(Self::layout_style(component)?)?;
         taffy_lookup.insert(component.key(), node);
         taffy.add_child(parent_node, node)?;
 
         if let Some(children) = component.children() {
             for child in children {
-                Self::build_component_tree_recursive(node, child.as_ref(), taffy, taffy_lookup)?;
+                Self::build_component_tree_recursive(node, child, taffy, taffy_lookup)?;
             }
         }
 
         Ok(())
     }
 
+    /// The flexbox style of the given component. Explicit
+    /// [`Component::dimensions`] take precedence over the size in
+    /// [`Component::style`].
+    fn layout_style(component: &dyn Component<Message = M>) -> Result<Style> {
+        let style = component.style().unwrap_or_default();
+        let size = if let Some((w, h)) = component.dimensions()? {
+            Size {
+                // TODO: Overflow???
+                width: Dimension::Points(w as f32),
+                height: Dimension::Points(h as f32),
+            }
+        } else {
+            style.size
+        };
+
+        Ok(Style { size, ..style })
+    }
+
     pub(self) async fn update(
         &mut self,
         pending_input: &[Keypress],
@@ -523,20 +1102,85 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
     ) -> Result<bool> {
         let mut post_office = self.post_office.write().await;
 
+        // Bound keys are handled before anything else, so that ex. a key
+        // bound to stop rendering stops this frame.
+        let mut unbound_input = Vec::with_capacity(pending_input.len());
+        for keypress in pending_input {
+            match self.binding_for(keypress) {
+                Some(Binding::Control(message)) => post_office.send_control(message.clone()),
+                Some(Binding::Message(key, message)) => post_office.send(*key, message.clone()),
+                None => unbound_input.push(keypress.clone()),
+            }
+        }
+        let pending_input = &unbound_input[..];
+
+        let mut focus = self.focus;
         for message in post_office.ui_mailbox() {
             match message {
                 UiControlMessage::MoveFocus(key) => {
-                    self.focus = *key;
+                    focus = *key;
+                }
+                UiControlMessage::FocusNext => {
+                    focus = Self::traverse_focus(self.root, focus, true);
+                }
+                UiControlMessage::FocusPrevious => {
+                    focus = Self::traverse_focus(self.root, focus, false);
                 }
                 UiControlMessage::StopRendering => {
                     self.exiting = true;
                 }
+                UiControlMessage::RequestRender => {
+                    self.dirty = true;
+                }
             }
         }
         post_office.clear_ui_mailbox();
 
-        Self::mail_pending_input(pending_input, &mut post_office, self.focus);
+        if focus != self.focus {
+            post_office.send_makeup(self.focus, MakeupMessage::Blur);
+            post_office.send_makeup(focus, MakeupMessage::Focus);
+            let blurred = std::mem::replace(&mut self.focus, focus);
+            Self::call_focus_hooks(
+                self.root,
+                &mut post_office,
+                self.post_office.clone(),
+                blurred,
+                focus,
+                render_dimensions,
+            )
+            .await?;
+        }
+
+        if render_dimensions != self.dimensions {
+            self.dimensions = render_dimensions;
+            self.resized = true;
+            for key in Self::get_all_child_keys(self.root) {
+                post_office.send_makeup(key, MakeupMessage::Resize(render_dimensions));
+            }
+        }
+
+        if !pending_input.is_empty() {
+            self.pacer.activity(Instant::now());
+        }
+
+        // Terminal focus changes are the UI's business, not the focused
+        // component's.
+        let mut keypresses = Vec::with_capacity(pending_input.len());
+        for keypress in pending_input {
+            match keypress {
+                Keypress::FocusGained => self.app_focused = true,
+                Keypress::FocusLost => self.app_focused = false,
+                keypress => keypresses.push(keypress.clone()),
+            }
+        }
+        Self::mail_pending_input(&keypresses, &mut post_office, self.focus);
+        // Including input, focus changes and resizes.
+        if post_office.take_sent_count() > 0 {
+            self.dirty = true;
+        }
+        post_office.sort_mailboxes();
         let taffy_lookup = &mut self.taffy_lookup;
+        let mut yielder = Yielder::new(self.yield_interval);
         Self::update_recursive(
             taffy_lookup,
             render_dimensions,
@@ -544,6 +1188,15 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
             &mut post_office,
             self.focus,
             self.post_office.clone(),
+            &mut yielder,
+        )
+        .await?;
+        Self::bubble_keypresses(
+            self.root,
+            &mut post_office,
+            self.post_office.clone(),
+            self.focus,
+            render_dimensions,
         )
         .await?;
 
@@ -572,40 +1225,133 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
     // TODO: Figure out parallel rendering
     pub(self) async fn render(&mut self, ctx: &mut RenderContext) -> Result<Vec<DrawCommandBatch>> {
         ctx.focus = self.focus;
-        let draw_commands =
-            Self::render_recursive(&self.taffy_lookup, &self.taffy, self.root, ctx).await?;
+        ctx.blink_on = self.blink_on || self.reduced_motion;
+        ctx.reduced_motion = self.reduced_motion;
+        ctx.app_focused = self.app_focused;
+        ctx.resized = std::mem::take(&mut self.resized);
+        ctx.path.clear();
+        ctx.key_hints = Self::focused_key_hints(self.root, self.focus).unwrap_or_default();
+        let mut yielder = Yielder::new(self.yield_interval);
+        let mut draw_commands = Self::render_recursive(
+            &self.taffy_lookup,
+            &self.taffy,
+            self.root,
+            ctx,
+            (0, 0),
+            None,
+            &mut yielder,
+        )
+        .await?;
+
+        let mut z_indices = HashMap::new();
+        Self::collect_z_indices(self.root, 0, &mut z_indices);
+        // Stable, so that tree order is kept within each z-index.
+        draw_commands.sort_by_key(|(key, _)| z_indices.get(key).copied().unwrap_or_default());
+
         Ok(draw_commands)
     }
 
+    /// The z-index each component draws at, ie. its own
+    /// [`Component::z_index`] added to its parent's.
+    fn collect_z_indices(
+        component: &dyn Component<Message = M>,
+        parent_z_index: i32,
+        z_indices: &mut HashMap<Key, i32>,
+    ) {
+        let z_index = parent_z_index + component.z_index();
+        z_indices.insert(component.key(), z_index);
+
+        if let Some(children) = component.children() {
+            for child in children {
+                Self::collect_z_indices(child.as_ref(), z_index, z_indices);
+            }
+        }
+    }
+
+    /// Render the given component and its children. Layout locations are
+    /// relative to the parent, so `origin` is the absolute location of the
+    /// component's parent. Everything is clipped to `clip`, if present.
     #[async_recursion]
     async fn render_recursive(
         taffy_lookup: &HashMap<Key, Node>,
         taffy: &Taffy,
         component: &dyn Component<Message = M>,
-        ctx: &RenderContext,
+        ctx: &mut RenderContext,
+        origin: Coordinates,
+        clip: Option<ClipRect>,
+        yielder: &mut Yielder,
     ) -> Result<Vec<DrawCommandBatch>> {
+        yielder.visit().await;
         let mut draw_commands = vec![];
 
-        let component_location =
-            taffy
-                .layout(*taffy_lookup.get(&component.key()).unwrap_or_else(|| {
-                    panic!("component {} not found in lookup!?", component.key())
-                }))?
-                .location;
+        let layout =
+            taffy.layout(*taffy_lookup.get(&component.key()).unwrap_or_else(|| {
+                panic!("component {} not found in lookup!?", component.key())
+            }))?;
+        let component_location = layout.location;
+        let location = (
+            origin.0 + component_location.x as u64,
+            origin.1 + component_location.y as u64,
+        );
 
         draw_commands.push((
             component.key(),
             vec![DrawCommand::MoveCursorAbsolute {
-                x: component_location.x as u64,
-                y: component_location.y as u64,
+                x: location.0,
+                y: location.1,
             }],
         ));
-        draw_commands.push(component.render(ctx).await?);
+        let own_clip = ClipRect {
+            x: location.0,
+            y: location.1,
+            width: layout.size.width as u64,
+            height: layout.size.height as u64,
+        };
+        let (key, commands) = component.render(ctx).await?;
+        let dimensions = component.dimensions()?;
+        #[cfg(debug_assertions)]
+        Self::warn_if_oversized(component, dimensions, &commands, location);
+
+        // Components can't draw outside of the dimensions they declare, so
+        // they can't spill into their siblings. Components without any are
+        // sized by their children, so they're left alone.
+        let commands_clip = match dimensions {
+            Some((width, height)) => {
+                let declared = ClipRect {
+                    x: location.0,
+                    y: location.1,
+                    width,
+                    height,
+                };
+                Some(clip.map_or(declared, |clip| clip.intersect(&declared)))
+            }
+            None => clip,
+        };
+        let commands = match &commands_clip {
+            Some(clip) => clip_commands(commands, location, clip),
+            None => commands,
+        };
+        draw_commands.push((key, commands));
+
+        let child_clip = if component.clips_children() {
+            Some(clip.map_or(own_clip, |clip| clip.intersect(&own_clip)))
+        } else {
+            clip
+        };
 
         if let Some(children) = component.children() {
+            ctx.path.push(component.key());
             for child in children {
-                let mut child_draw_commands =
-                    Self::render_recursive(taffy_lookup, taffy, child.as_ref(), ctx).await?;
+                let mut child_draw_commands = Self::render_recursive(
+                    taffy_lookup,
+                    taffy,
+                    child,
+                    ctx,
+                    location,
+                    child_clip,
+                    yielder,
+                )
+                .await?;
                 let component_location = taffy
                     .layout(*taffy_lookup.get(&child.key()).unwrap_or_else(|| {
                         panic!("child component {} not found in lookup!?", child.key())
@@ -614,46 +1360,198 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
                 draw_commands.push((
                     child.key(),
                     vec![DrawCommand::MoveCursorAbsolute {
-                        x: component_location.x as u64,
-                        y: component_location.y as u64,
+                        x: location.0 + component_location.x as u64,
+                        y: location.1 + component_location.y as u64,
                     }],
                 ));
                 draw_commands.append(&mut child_draw_commands);
             }
+            ctx.path.pop();
         }
 
         Ok(draw_commands)
     }
 
-    fn mail_pending_input(
-        pending_input: &[Keypress],
-        post_office: &mut PostOffice<M>,
-        focused_component: Key,
-    ) {
-        for keypress in pending_input {
-            post_office.send_makeup(focused_component, MakeupMessage::Keypress(keypress.clone()));
+    /// Run every component's [`Component::on_stop`] hook. Only runs once, no
+    /// matter how many times it's called.
+    pub(self) async fn stop(&mut self) -> Result<()> {
+        if self.stopped {
+            return Ok(());
         }
+        self.stopped = true;
+
+        Self::stop_recursive(self.root).await
     }
 
     #[async_recursion]
-    async fn update_recursive(
-        _taffy_lookup: &mut HashMap<Key, Node>,
-        render_dimensions: Dimensions,
-        component: &mut dyn Component<Message = M>,
-        post_office: &mut PostOffice<M>,
-        focus: Key,
-        post_office_lock: RwLocked<PostOffice<M>>,
-    ) -> Result<()> {
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
-
-        let mut pending_update = UpdateContext {
-            post_office: &mut *post_office,
-            sender: MessageSender::new(tx.clone(), focus),
-            focus,
-            dimensions: render_dimensions,
-        };
+    async fn stop_recursive(component: &mut dyn Component<Message = M>) -> Result<()> {
+        component.on_stop().await?;
 
-        // TODO: Update subtrees only
+        if let Some(children) = component.children_mut() {
+            for child in children {
+                Self::stop_recursive(child).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Advance the global ticker by `ticks` base intervals, mailing
+    /// [`MakeupMessage::TimerTick`]s to every component whose tick interval
+    /// elapsed along the way, and advancing the blink clock.
+    pub(self) async fn tick(&mut self, ticks: u64) {
+        if ticks == 0 {
+            return;
+        }
+
+        let mut subscribers = vec![];
+        Self::collect_tick_intervals(self.root, &mut subscribers);
+
+        let mut post_office = self.post_office.write().await;
+        {
+            let subscriptions = post_office.subscriptions();
+            let timers = &mut subscriptions.lock().unwrap().timers;
+            if !timers.is_empty() {
+                // Components that have left the tree don't need ticks.
+                let keys = Self::get_all_child_keys(self.root);
+                timers.retain(|_, (key, _)| keys.contains(key));
+                subscribers.extend(timers.values().copied());
+            }
+        }
+        for _ in 0..ticks {
+            self.ticker.advance();
+            for (key, interval) in &subscribers {
+                if self.ticker.is_due(*interval) {
+                    post_office.send_makeup(*key, MakeupMessage::TimerTick(*interval));
+                }
+            }
+            if self.ticker.is_due(self.blink_interval) {
+                self.blink_on = !self.blink_on;
+                self.dirty |= !self.reduced_motion;
+            }
+        }
+    }
+
+    /// Warn when a component draws more than the dimensions it declares, as
+    /// whatever's outside of them is clipped, and it's likely been laid out
+    /// too small. Drawing less than declared is fine, ex. padding.
+    #[cfg(debug_assertions)]
+    fn warn_if_oversized(
+        component: &dyn Component<Message = M>,
+        dimensions: Option<Dimensions>,
+        commands: &[DrawCommand],
+        location: Coordinates,
+    ) {
+        let Some((width, height)) = dimensions else {
+            return;
+        };
+        let (rendered_width, rendered_height) =
+            crate::render::bounds::rendered_size(commands, location);
+        if rendered_width > width || rendered_height > height {
+            log::warn!(
+                "component {} declares dimensions {width}x{height}, but renders \
+                 {rendered_width}x{rendered_height}: {component:?}",
+                component.key(),
+            );
+        }
+    }
+
+    /// The key hints of the component with the `focus` key, if it's in the
+    /// tree.
+    fn focused_key_hints(
+        component: &dyn Component<Message = M>,
+        focus: Key,
+    ) -> Option<Vec<(String, String)>> {
+        if component.key() == focus {
+            return Some(component.key_hints());
+        }
+
+        component
+            .children()?
+            .into_iter()
+            .find_map(|child| Self::focused_key_hints(child.as_ref(), focus))
+    }
+
+    fn collect_tick_intervals(
+        component: &dyn Component<Message = M>,
+        subscribers: &mut Vec<(Key, Duration)>,
+    ) {
+        if let Some(interval) = component.tick_interval() {
+            subscribers.push((component.key(), interval));
+        }
+
+        if let Some(children) = component.children() {
+            for child in children {
+                Self::collect_tick_intervals(child, subscribers);
+            }
+        }
+    }
+
+    /// What the given keypress is bound to, if anything. A control character
+    /// matches its Ctrl+letter binding, unless it was read as another key.
+    /// See [`MUI::bind`].
+    fn binding_for(&self, keypress: &Keypress) -> Option<&Binding<M>> {
+        let ctrl_letter = match keypress {
+            Keypress::Char(c @ '\x01'..='\x1a') => Some(Keypress::Modified(
+                makeup_console::Modifiers::CTRL,
+                Box::new(Keypress::Char((b'a' + *c as u8 - 1) as char)),
+            )),
+            _ => None,
+        };
+
+        self.keymap
+            .iter()
+            .find(|(bound, _)| bound == keypress || Some(bound) == ctrl_letter.as_ref())
+            .map(|(_, binding)| binding)
+    }
+
+    fn mail_pending_input(
+        pending_input: &[Keypress],
+        post_office: &mut PostOffice<M>,
+        focused_component: Key,
+    ) {
+        for keypress in pending_input {
+            post_office.send_makeup(focused_component, MakeupMessage::Keypress(keypress.clone()));
+        }
+    }
+
+    #[async_recursion]
+    async fn update_recursive(
+        _taffy_lookup: &mut HashMap<Key, Node>,
+        render_dimensions: Dimensions,
+        component: &mut dyn Component<Message = M>,
+        post_office: &mut PostOffice<M>,
+        focus: Key,
+        post_office_lock: RwLocked<PostOffice<M>>,
+        yielder: &mut Yielder,
+    ) -> Result<()> {
+        yielder.visit().await;
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        // Ancestors have had their chance to take Tab and Shift-Tab by now,
+        // so any left move focus instead of reaching the focused component.
+        if component.key() == focus {
+            let traversals = post_office.take_keypresses(focus, |keypress| {
+                matches!(keypress, Keypress::Tab | Keypress::ShiftTab)
+            });
+            for keypress in traversals {
+                post_office.send_control(match keypress {
+                    Keypress::Tab => UiControlMessage::FocusNext,
+                    _ => UiControlMessage::FocusPrevious,
+                });
+            }
+        }
+
+        let sender =
+            MessageSender::with_subscriptions(tx.clone(), focus, post_office.subscriptions());
+        let mut pending_update = UpdateContext {
+            post_office: &mut *post_office,
+            sender,
+            focus,
+            dimensions: render_dimensions,
+        };
+
+        // TODO: Update subtrees only
         // let old_keys = Self::get_all_child_keys(component);
         (*component).update(&mut pending_update).await?;
         // let new_keys = Self::get_all_child_keys(component);
@@ -666,10 +1564,151 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
         //     // TODO: Recursively add all new children to taffy tree
         // }
 
-        let lock_clone = post_office_lock.clone();
+        Self::forward_sent_messages(rx, post_office_lock.clone());
+
+        if let Some(children) = component.children_mut() {
+            for child in children {
+                Self::update_recursive(
+                    _taffy_lookup,
+                    render_dimensions,
+                    child,
+                    post_office,
+                    focus,
+                    post_office_lock.clone(),
+                    yielder,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Call [`Component::on_blur`] on the component with the `blurred` key,
+    /// and then [`Component::on_focus`] on the one with the `focused` key.
+    async fn call_focus_hooks(
+        root: &mut dyn Component<Message = M>,
+        post_office: &mut PostOffice<M>,
+        post_office_lock: RwLocked<PostOffice<M>>,
+        blurred: Key,
+        focused: Key,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        if let Some(component) = Self::find_mut(root, blurred) {
+            let sender =
+                MessageSender::with_subscriptions(tx.clone(), focused, post_office.subscriptions());
+            let mut ctx = UpdateContext {
+                post_office: &mut *post_office,
+                sender,
+                focus: focused,
+                dimensions,
+            };
+            component.on_blur(&mut ctx).await?;
+        }
+        if let Some(component) = Self::find_mut(root, focused) {
+            let sender =
+                MessageSender::with_subscriptions(tx.clone(), focused, post_office.subscriptions());
+            let mut ctx = UpdateContext {
+                post_office: &mut *post_office,
+                sender,
+                focus: focused,
+                dimensions,
+            };
+            component.on_focus(&mut ctx).await?;
+        }
+        Self::forward_sent_messages(rx, post_office_lock);
+
+        Ok(())
+    }
+
+    /// Offer the keypresses that the focused component didn't handle to each
+    /// of its ancestors in turn, nearest first, by updating them again with
+    /// just those keypresses in their mailboxes. Stops once every keypress
+    /// has been handled.
+    async fn bubble_keypresses(
+        root: &mut dyn Component<Message = M>,
+        post_office: &mut PostOffice<M>,
+        post_office_lock: RwLocked<PostOffice<M>>,
+        focus: Key,
+        dimensions: Dimensions,
+    ) -> Result<()> {
+        let mut keypresses = Self::take_unhandled_keypresses(post_office, focus);
+        let mut ancestors = vec![];
+        if keypresses.is_empty() || !Self::collect_ancestor_keys(root, focus, &mut ancestors) {
+            post_office.clear_unhandled_keypresses();
+            return Ok(());
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        for key in ancestors.into_iter().rev() {
+            if keypresses.is_empty() {
+                break;
+            }
+            let Some(component) = Self::find_mut(root, key) else {
+                break;
+            };
+            for keypress in keypresses {
+                post_office.send_makeup(key, MakeupMessage::Keypress(keypress));
+            }
+            let sender =
+                MessageSender::with_subscriptions(tx.clone(), focus, post_office.subscriptions());
+            let mut ctx = UpdateContext {
+                post_office: &mut *post_office,
+                sender,
+                focus,
+                dimensions,
+            };
+            component.update(&mut ctx).await?;
+            keypresses = Self::take_unhandled_keypresses(post_office, key);
+        }
+        Self::forward_sent_messages(rx, post_office_lock);
+        post_office.clear_unhandled_keypresses();
+
+        Ok(())
+    }
+
+    /// The keypresses that the component with the given key didn't match in
+    /// [`check_mail!`](crate::check_mail), or left unread in its mailbox.
+    fn take_unhandled_keypresses(post_office: &mut PostOffice<M>, key: Key) -> Vec<Keypress> {
+        let mut keypresses = post_office.take_unhandled_keypresses(key);
+        keypresses.extend(post_office.take_keypresses(key, |_| true));
+        keypresses
+    }
+
+    /// Collect the keys of the ancestors of the component with the given key,
+    /// root first. Returns whether the component was found.
+    fn collect_ancestor_keys(
+        component: &dyn Component<Message = M>,
+        key: Key,
+        ancestors: &mut Vec<Key>,
+    ) -> bool {
+        if component.key() == key {
+            return true;
+        }
+
+        ancestors.push(component.key());
+        if let Some(children) = component.children() {
+            for child in children {
+                if Self::collect_ancestor_keys(child.as_ref(), key, ancestors) {
+                    return true;
+                }
+            }
+        }
+        ancestors.pop();
+
+        false
+    }
+
+    /// Deliver the messages components send during an update, once the post
+    /// office is free.
+    fn forward_sent_messages(
+        mut rx: UnboundedReceiver<(Key, RawComponentMessage<M>)>,
+        post_office: RwLocked<PostOffice<M>>,
+    ) {
         tokio::spawn(async move {
             while let Some((id, message)) = rx.recv().await {
-                let mut post_office = lock_clone.write().await;
+                let mut post_office = post_office.write().await;
                 match message {
                     Either::Left(left) => {
                         post_office.send(id, left);
@@ -680,32 +1719,61 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
                 }
             }
         });
+    }
 
-        if let Some(children) = component.children_mut() {
+    /// The component with the given key, if it's in the tree.
+    fn find_mut(
+        component: &mut dyn Component<Message = M>,
+        key: Key,
+    ) -> Option<&mut dyn Component<Message = M>> {
+        if component.key() == key {
+            return Some(component);
+        }
+
+        component
+            .children_mut()?
+            .into_iter()
+            .find_map(|child| Self::find_mut(child.as_mut(), key))
+    }
+
+    /// The key of the component that accepts focus after (or, if not
+    /// `forwards`, before) the one with the `focus` key, in tree order,
+    /// wrapping around. If nothing accepts focus, focus stays put.
+    fn traverse_focus(component: &dyn Component<Message = M>, focus: Key, forwards: bool) -> Key {
+        let mut order = vec![];
+        Self::collect_focusable_keys(component, &mut order);
+        if order.is_empty() {
+            return focus;
+        }
+
+        let next = match (order.iter().position(|key| *key == focus), forwards) {
+            (Some(i), true) => (i + 1) % order.len(),
+            (Some(i), false) => (i + order.len() - 1) % order.len(),
+            (None, true) => 0,
+            (None, false) => order.len() - 1,
+        };
+        order[next]
+    }
+
+    fn collect_focusable_keys(component: &dyn Component<Message = M>, keys: &mut Vec<Key>) {
+        if component.accepts_focus() {
+            keys.push(component.key());
+        }
+
+        if let Some(children) = component.children() {
             for child in children {
-                Self::update_recursive(
-                    _taffy_lookup,
-                    render_dimensions,
-                    child.as_mut(),
-                    post_office,
-                    focus,
-                    post_office_lock.clone(),
-                )
-                .await?;
+                Self::collect_focusable_keys(child.as_ref(), keys);
             }
         }
-
-        Ok(())
     }
 
-    #[allow(unused)]
     fn get_all_child_keys(component: &dyn Component<Message = M>) -> HashSet<Key> {
         let mut keys = HashSet::new();
         keys.insert(component.key());
 
         if let Some(children) = component.children() {
             for child in children {
-                keys.extend(Self::get_all_child_keys(child.as_ref()));
+                keys.extend(Self::get_all_child_keys(child));
             }
         }
 
@@ -733,14 +1801,174 @@ impl<'a, M: std::fmt::Debug + Send + Sync + Clone + 'static> UI<'a, M> {
     }
 }
 
+/// A single UI-wide timer. Instead of every animated component running its own
+/// timer task, the ticker fires at a base interval and works out which
+/// components are due a [`MakeupMessage::TimerTick`].
+#[derive(Debug)]
+struct Ticker {
+    interval: Duration,
+    ticks: u64,
+    last_tick: Option<Instant>,
+}
+
+impl Ticker {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            ticks: 0,
+            last_tick: None,
+        }
+    }
+
+    /// How many base intervals have elapsed since the last call, up to
+    /// [`MAX_TICKS_PER_UPDATE`].
+    fn elapsed_ticks(&mut self, now: Instant) -> u64 {
+        let Some(last_tick) = self.last_tick else {
+            self.last_tick = Some(now);
+            return 0;
+        };
+        if self.interval.is_zero() {
+            return 0;
+        }
+
+        let elapsed = now.duration_since(last_tick).as_nanos() / self.interval.as_nanos();
+        if elapsed > MAX_TICKS_PER_UPDATE as u128 {
+            // After a stall, ex. the machine being suspended, catching up
+            // would flood every subscriber with ticks, so the rest are
+            // dropped.
+            self.last_tick = Some(now);
+            return MAX_TICKS_PER_UPDATE;
+        }
+
+        let ticks = elapsed as u64;
+        self.last_tick = Some(last_tick + self.interval * ticks as u32);
+        ticks
+    }
+
+    /// Advance by one base interval.
+    fn advance(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// Whether the given interval elapsed during the most recent base
+    /// interval.
+    fn is_due(&self, interval: Duration) -> bool {
+        let interval = interval.as_nanos();
+        if interval == 0 || self.ticks == 0 {
+            return false;
+        }
+
+        let before = self.interval.as_nanos() * (self.ticks - 1) as u128;
+        let after = self.interval.as_nanos() * self.ticks as u128;
+        after / interval > before / interval
+    }
+}
+
+/// How long each frame takes at `fps`. 0 is uncapped, so frames take no time
+/// at all.
+fn frame_duration(fps: u64) -> Duration {
+    if fps == 0 {
+        return Duration::ZERO;
+    }
+
+    Duration::from_micros((ONE_SECOND_IN_MICROS as u64) / fps)
+}
+
+/// Works out how long each frame should take. Normally that's the frame
+/// target, but if an idle framerate is set, frames get longer once there's
+/// been no activity for a while.
+#[derive(Debug)]
+struct FramePacer {
+    target: Duration,
+    /// The idle frame duration, and how long without activity until it's
+    /// used.
+    idle: Option<(Duration, Duration)>,
+    last_activity: Instant,
+}
+
+impl FramePacer {
+    fn new(target: Duration) -> Self {
+        Self {
+            target,
+            idle: None,
+            last_activity: Instant::now(),
+        }
+    }
+
+    fn activity(&mut self, now: Instant) {
+        self.last_activity = now;
+    }
+
+    /// The target as frames per second, or 0 if it's uncapped.
+    fn fps(&self) -> u64 {
+        if self.target.is_zero() {
+            return 0;
+        }
+
+        (ONE_SECOND_IN_MICROS / self.target.as_micros()) as u64
+    }
+
+    /// How long to sleep after a frame that took `elapsed` to render, if at
+    /// all.
+    fn sleep_for(&self, elapsed: Duration, now: Instant) -> Option<Duration> {
+        self.frame_target(now)
+            .checked_sub(elapsed)
+            .filter(|duration| !duration.is_zero())
+    }
+
+    fn frame_target(&self, now: Instant) -> Duration {
+        match self.idle {
+            Some((idle_target, after))
+                if now.saturating_duration_since(self.last_activity) >= after =>
+            {
+                idle_target.max(self.target)
+            }
+            _ => self.target,
+        }
+    }
+}
+
+/// Counts components visited during a single update or render pass, and
+/// periodically yields to the async runtime so that large trees don't starve
+/// other tasks.
+#[derive(Debug)]
+struct Yielder {
+    interval: usize,
+    visited: usize,
+}
+
+impl Yielder {
+    fn new(interval: usize) -> Self {
+        Self {
+            interval,
+            visited: 0,
+        }
+    }
+
+    async fn visit(&mut self) {
+        self.visited += 1;
+        if self.interval > 0 && self.visited >= self.interval {
+            self.visited = 0;
+            tokio::task::yield_now().await;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::component::{DrawCommandBatch, Key, MakeupUpdate, RenderContext};
-    use crate::components::EchoText;
-    use crate::input::TerminalInput;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::component::{
+        DrawCommandBatch, Key, MakeupMessage, MakeupUpdate, RenderContext, SubscriptionId,
+    };
+    use crate::components::{Container, EchoText, Spinner};
+    use crate::input::{InputFrame, TerminalInput};
+    use crate::post_office::MessageKind;
     use crate::render::MemoryRenderer;
-    use crate::ui::UiControlMessage;
-    use crate::{check_mail, Component, Dimensions, DrawCommand, MUI};
+    use crate::ui::{RenderState, UiControlMessage};
+    use crate::{check_mail, Component, Dimensions, DrawCommand, Input, MUI};
 
     use async_trait::async_trait;
     use eyre::Result;
@@ -860,4 +2088,1304 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_large_tree_yields_and_renders() -> Result<()> {
+        let children: Vec<Box<dyn Component<Message = ()>>> = (0..1000)
+            .map(|i| {
+                Box::new(EchoText::<()>::new(format!("{}", i % 10)))
+                    as Box<dyn Component<Message = ()>>
+            })
+            .collect();
+        let mut root = Container::new(children);
+
+        let renderer = MemoryRenderer::new(1024, 1);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+        ui.set_yield_interval(7).await;
+        ui.update(&[]).await?;
+        ui.render_once().await?;
+
+        let expected: String = (0..1000).map(|i| format!("{}", i % 10)).collect();
+        ui.move_cursor(0, 0).await?;
+        assert_eq!(expected, ui.read_at_cursor(1000).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ticker_drives_spinners_at_their_own_intervals() -> Result<()> {
+        let steps: Vec<char> = "01234567".chars().collect();
+        let mut root = Container::<()>::new(vec![
+            Box::new(Spinner::new("a", steps.clone(), Duration::from_millis(10))),
+            Box::new(Spinner::new("b", steps.clone(), Duration::from_millis(20))),
+            Box::new(Spinner::new("c", steps, Duration::from_millis(30))),
+        ]);
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+        ui.set_tick_interval(Duration::from_millis(10)).await;
+
+        for _ in 0..6 {
+            ui.tick().await;
+            ui.update(&[]).await?;
+        }
+        ui.render_once().await?;
+
+        ui.move_cursor(0, 0).await?;
+        assert_eq!("6 a3 b2 c".to_string(), ui.read_at_cursor(9).await?);
+
+        Ok(())
+    }
+
+    /// Counts the ticks from an interval it subscribes to on its first
+    /// update, until it's sent `false`.
+    #[derive(Debug)]
+    struct SubscribingComponent {
+        key: Key,
+        subscription: Option<SubscriptionId>,
+        ticks: usize,
+    }
+
+    #[async_trait]
+    impl Component for SubscribingComponent {
+        type Message = bool;
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            if self.subscription.is_none() {
+                self.subscription = Some(
+                    ctx.sender
+                        .subscribe_interval(self.key, Duration::from_millis(30)),
+                );
+            }
+
+            check_mail!(
+                self,
+                ctx,
+                match _ {
+                    MakeupMessage::TimerTick(_) => {
+                        self.ticks += 1;
+                    }
+                    false => {
+                        if let Some(id) = self.subscription {
+                            ctx.sender.unsubscribe(id);
+                        }
+                    }
+                }
+            );
+
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![DrawCommand::TextUnderCursor(self.ticks.to_string())])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((4, 1)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interval_subscriptions_tick_until_unsubscribed() -> Result<()> {
+        let mut root = SubscribingComponent {
+            key: crate::component::generate_key(),
+            subscription: None,
+            ticks: 0,
+        };
+        let key = root.key;
+        let ui = crate::test::make_test_ui!(&mut root, 8, 1);
+        ui.set_tick_interval(Duration::from_millis(10)).await;
+        ui.update(&[]).await?;
+
+        // 90ms.
+        for _ in 0..9 {
+            ui.tick().await;
+            ui.update(&[]).await?;
+        }
+        ui.render_once().await?;
+        ui.move_cursor(0, 0).await?;
+        assert_eq!("3", ui.read_at_cursor(1).await?);
+
+        ui.send(key, false).await;
+        ui.update(&[]).await?;
+        for _ in 0..9 {
+            ui.tick().await;
+            ui.update(&[]).await?;
+        }
+        ui.render_once().await?;
+        ui.move_cursor(0, 0).await?;
+        assert_eq!("3", ui.read_at_cursor(1).await?);
+
+        Ok(())
+    }
+
+    #[derive(Debug, Clone)]
+    struct NoInput;
+
+    #[async_trait]
+    impl Input for NoInput {
+        async fn next_frame(&self) -> Result<InputFrame> {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            Ok(InputFrame::Empty)
+        }
+    }
+
+    #[derive(Debug)]
+    struct StoppableComponent {
+        key: Key,
+        stops: Arc<AtomicUsize>,
+        children: Vec<Box<dyn Component<Message = ()>>>,
+    }
+
+    #[async_trait]
+    impl Component for StoppableComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            Some(self.children.iter().collect())
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            Some(self.children.iter_mut().collect())
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((1, 1)))
+        }
+
+        async fn on_stop(&mut self) -> Result<()> {
+            self.stops.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_on_stop_is_called_once() -> Result<()> {
+        let root_stops = Arc::new(AtomicUsize::new(0));
+        let child_stops = Arc::new(AtomicUsize::new(0));
+        let mut root = StoppableComponent {
+            key: crate::component::generate_key(),
+            stops: root_stops.clone(),
+            children: vec![Box::new(StoppableComponent {
+                key: crate::component::generate_key(),
+                stops: child_stops.clone(),
+                children: vec![],
+            })],
+        };
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let ui = MUI::new(&mut root, Box::new(renderer), NoInput)?;
+        ui.send_control(UiControlMessage::StopRendering).await;
+        assert!(matches!(ui.render(false).await?, RenderState::Stopped));
+        assert!(matches!(ui.render(false).await?, RenderState::Stopped));
+
+        assert_eq!(1, root_stops.load(Ordering::SeqCst));
+        assert_eq!(1, child_stops.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_tears_the_ui_down() -> Result<()> {
+        let stops = Arc::new(AtomicUsize::new(0));
+        let mut root = StoppableComponent {
+            key: crate::component::generate_key(),
+            stops: stops.clone(),
+            children: vec![],
+        };
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let ui = MUI::new(&mut root, Box::new(renderer), NoInput)?;
+        let (state, ()) = tokio::join!(ui.render(false), async {
+            // Let it render a few frames first.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            ui.shutdown().await;
+        });
+        assert!(matches!(state?, RenderState::Stopped));
+        assert_eq!(1, stops.load(Ordering::SeqCst));
+
+        Ok(())
+    }
+
+    /// Set in the child process that
+    /// `test_signals_stop_the_program_after_rendering` runs itself in.
+    #[cfg(unix)]
+    const SIGNAL_TEST_CHILD: &str = "MAKEUP_SIGNAL_TEST_CHILD";
+
+    #[cfg(unix)]
+    fn raise(signal: libc::c_int) {
+        // Safety: Only ever called with valid signals.
+        #[allow(unsafe_code)]
+        unsafe {
+            libc::raise(signal);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signals_stop_the_program_after_rendering() -> Result<()> {
+        use std::os::unix::process::ExitStatusExt;
+
+        if std::env::var_os(SIGNAL_TEST_CHILD).is_none() {
+            // Signals go to the whole process, so run this test in a child of
+            // its own.
+            let status = std::process::Command::new(std::env::current_exe()?)
+                .args([
+                    "--exact",
+                    "ui::tests::test_signals_stop_the_program_after_rendering",
+                ])
+                .env(SIGNAL_TEST_CHILD, "1")
+                .stdout(std::process::Stdio::null())
+                .status()?;
+            assert_eq!(Some(libc::SIGINT), status.signal());
+
+            return Ok(());
+        }
+
+        tokio::runtime::Runtime::new()?.block_on(async {
+            let mut root = EchoText::<()>::new("signals");
+            let renderer = MemoryRenderer::new(128, 128);
+            let ui = MUI::new(&mut root, Box::new(renderer), NoInput)?;
+            let (state, ()) = tokio::join!(ui.render(false), async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                raise(libc::SIGTERM);
+            });
+            assert!(matches!(state?, RenderState::Stopped));
+
+            Ok::<_, eyre::Report>(())
+        })?;
+
+        // Nothing is listening for it any more, so this terminates the child.
+        raise(libc::SIGINT);
+        panic!("SIGINT was handled after rendering stopped");
+    }
+
+    #[derive(Debug)]
+    struct FocusableComponent {
+        key: Key,
+        received: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Component for FocusableComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            use crate::component::MakeupMessage;
+            let mut received = self.received.lock().unwrap();
+            check_mail!(
+                self,
+                ctx,
+                match _ {
+                    MakeupMessage::Focus => {
+                        received.push("focus".into());
+                    }
+                    MakeupMessage::Blur => {
+                        received.push("blur".into());
+                    }
+                    MakeupMessage::Resize((w, h)) => {
+                        received.push(format!("resize {w}x{h}"));
+                    }
+                }
+            );
+
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((1, 1)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_focus_blur_and_resize_are_mailed() -> Result<()> {
+        let first = Arc::new(std::sync::Mutex::new(vec![]));
+        let second = Arc::new(std::sync::Mutex::new(vec![]));
+        let first_key = crate::component::generate_key();
+        let second_key = crate::component::generate_key();
+        let mut root = Container::<()>::new(vec![
+            Box::new(FocusableComponent {
+                key: first_key,
+                received: first.clone(),
+            }),
+            Box::new(FocusableComponent {
+                key: second_key,
+                received: second.clone(),
+            }),
+        ]);
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+
+        ui.send_control(UiControlMessage::MoveFocus(first_key))
+            .await;
+        ui.update(&[]).await?;
+        assert_eq!(vec!["focus".to_string()], *first.lock().unwrap());
+
+        ui.send_control(UiControlMessage::MoveFocus(second_key))
+            .await;
+        ui.update(&[]).await?;
+        assert_eq!(vec!["focus", "blur"], *first.lock().unwrap());
+        assert_eq!(vec!["focus"], *second.lock().unwrap());
+
+        // Moving focus to where it already is isn't a change.
+        ui.send_control(UiControlMessage::MoveFocus(second_key))
+            .await;
+        ui.update(&[]).await?;
+        assert_eq!(vec!["focus"], *second.lock().unwrap());
+
+        ui.renderer().write().await.set_width(64);
+        ui.update(&[]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(
+            vec!["focus", "blur", "resize 64x128"],
+            *first.lock().unwrap()
+        );
+        assert_eq!(vec!["focus", "resize 64x128"], *second.lock().unwrap());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resizes_are_mailed_before_the_next_render() -> Result<()> {
+        let mut root = Container::<()>::new(vec![]);
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        let mut ctx = crate::test::fake_render_ctx();
+        ui.update(&[]).await?;
+        ui.render_commands(&mut ctx).await?;
+        assert!(!ctx.resized);
+
+        ui.renderer().write().await.set_width(32);
+        ui.update(&[]).await?;
+        {
+            let ui = ui.ui.lock().await;
+            let post_office = ui.post_office.read().await;
+            assert!(matches!(
+                post_office.mailbox(ui.root).unwrap().as_slice(),
+                [either::Either::Right(MakeupMessage::Resize((32, 4)))]
+            ));
+        }
+
+        // Only the first frame after the resize knows about it.
+        ui.render_commands(&mut ctx).await?;
+        assert!(ctx.resized);
+        ui.update(&[]).await?;
+        ui.render_commands(&mut ctx).await?;
+        assert!(!ctx.resized);
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct HookedComponent {
+        key: Key,
+        name: &'static str,
+        log: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl Component for HookedComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((1, 1)))
+        }
+
+        fn accepts_focus(&self) -> bool {
+            true
+        }
+
+        async fn on_focus(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            assert_eq!(self.key, ctx.focus);
+            self.log
+                .lock()
+                .unwrap()
+                .push(format!("focus {}", self.name));
+            Ok(())
+        }
+
+        async fn on_blur(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            self.log.lock().unwrap().push(format!("blur {}", self.name));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_focus_hooks_are_called_in_order() -> Result<()> {
+        use makeup_console::Keypress;
+
+        let log = Arc::new(std::sync::Mutex::new(vec![]));
+        let first_key = crate::component::generate_key();
+        let mut root = Container::<()>::new(vec![
+            Box::new(HookedComponent {
+                key: first_key,
+                name: "first",
+                log: log.clone(),
+            }),
+            Box::new(HookedComponent {
+                key: crate::component::generate_key(),
+                name: "second",
+                log: log.clone(),
+            }),
+        ]);
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+
+        ui.send_control(UiControlMessage::MoveFocus(first_key))
+            .await;
+        ui.update(&[]).await?;
+        assert_eq!(vec!["focus first"], *log.lock().unwrap());
+
+        ui.update(&[Keypress::Tab]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(
+            vec!["focus first", "blur first", "focus second"],
+            *log.lock().unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct OrderRecordingComponent {
+        key: Key,
+        received: Vec<String>,
+    }
+
+    #[async_trait]
+    impl Component for OrderRecordingComponent {
+        type Message = String;
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            use crate::component::MakeupMessage;
+            use makeup_console::Keypress;
+            check_mail!(
+                self,
+                ctx,
+                match _ {
+                    MakeupMessage::Keypress(Keypress::Char(c)) => {
+                        self.received.push(format!("key {c}"));
+                    }
+                    MakeupMessage::TimerTick(_) => {
+                        self.received.push("tick".into());
+                    }
+                    message => {
+                        self.received.push(message.clone());
+                    }
+                }
+            );
+
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((1, 1)))
+        }
+
+        fn tick_interval(&self) -> Option<Duration> {
+            Some(Duration::from_millis(10))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_delivered_in_order_of_kind() -> Result<()> {
+        use makeup_console::Keypress;
+
+        let mut root = OrderRecordingComponent {
+            key: crate::component::generate_key(),
+            received: vec![],
+        };
+        let key = root.key();
+
+        {
+            let renderer = MemoryRenderer::new(128, 128);
+            let input = TerminalInput::new().await?;
+            let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+            ui.set_tick_interval(Duration::from_millis(10)).await;
+
+            // Sent in the opposite order to the one they're delivered in.
+            ui.send(key, "user".into()).await;
+            ui.tick().await;
+            ui.update(&[Keypress::Char('a')]).await?;
+
+            ui.set_delivery_order(vec![MessageKind::User, MessageKind::Timer])
+                .await;
+            ui.send(key, "user".into()).await;
+            ui.tick().await;
+            ui.update(&[Keypress::Char('b')]).await?;
+        }
+
+        assert_eq!(
+            vec!["key a", "tick", "user", "user", "tick", "key b"],
+            root.received
+        );
+
+        Ok(())
+    }
+
+    #[derive(Debug)]
+    struct PathRecordingComponent {
+        key: Key,
+        path: Arc<std::sync::Mutex<Vec<Key>>>,
+    }
+
+    #[async_trait]
+    impl Component for PathRecordingComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            *self.path.lock().unwrap() = ctx.path.clone();
+            self.batch(vec![DrawCommand::TextUnderCursor(format!(
+                "{}{}",
+                "  ".repeat(ctx.path.len()),
+                ctx.path.len()
+            ))])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((8, 1)))
+        }
+    }
+
+    /// Draws more than the dimensions it declares.
+    #[derive(Debug)]
+    struct OversizedComponent {
+        key: Key,
+    }
+
+    #[async_trait]
+    impl Component for OversizedComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![
+                DrawCommand::TextUnderCursor("abcdef".into()),
+                DrawCommand::MoveCursorRelative { x: -6, y: 1 },
+                DrawCommand::TextUnderCursor("ghijkl".into()),
+            ])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((3, 1)))
+        }
+    }
+
+    #[test]
+    fn test_components_are_clipped_to_their_dimensions() -> Result<()> {
+        let mut root = Container::<()>::new(vec![
+            Box::new(OversizedComponent {
+                key: crate::component::generate_key(),
+            }),
+            Box::new(EchoText::new("!")),
+        ]);
+
+        assert_eq!("abc!", crate::test::render_sync(&mut root, 16, 4)?);
+
+        Ok(())
+    }
+
+    /// Collects the warnings logged by every test.
+    #[cfg(debug_assertions)]
+    struct WarningLogger;
+
+    #[cfg(debug_assertions)]
+    static WARNINGS: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(vec![]);
+
+    #[cfg(debug_assertions)]
+    impl log::Log for WarningLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                WARNINGS.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn test_oversized_components_are_warned_about() -> Result<()> {
+        let _ = log::set_logger(&WarningLogger);
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let key = crate::component::generate_key();
+        let mut root = Container::<()>::new(vec![
+            Box::new(OversizedComponent { key }),
+            Box::new(EchoText::new("!")),
+        ]);
+        crate::test::render_sync(&mut root, 16, 4)?;
+
+        let warnings = WARNINGS.lock().unwrap();
+        let expected = format!("component {key} declares dimensions 3x1, but renders 6x2");
+        assert!(warnings
+            .iter()
+            .any(|warning| warning.starts_with(&expected)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_render_context_carries_ancestor_path() -> Result<()> {
+        let path = Arc::new(std::sync::Mutex::new(vec![]));
+        let inner = Container::<()>::new(vec![Box::new(PathRecordingComponent {
+            key: crate::component::generate_key(),
+            path: path.clone(),
+        })]);
+        let inner_key = inner.key();
+        let mut root = Container::<()>::new(vec![Box::new(inner)]);
+        let root_key = root.key();
+
+        let renderer = MemoryRenderer::new(128, 128);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+        let mut ctx = crate::test::fake_render_ctx();
+        let commands = ui.render_commands(&mut ctx).await?;
+
+        assert_eq!(vec![root_key, inner_key], *path.lock().unwrap());
+        assert!(commands
+            .iter()
+            .any(|(_, commands)| commands.contains(&DrawCommand::TextUnderCursor("    2".into()))));
+        // The path is unwound once the walk is done.
+        assert!(ctx.path.is_empty());
+
+        Ok(())
+    }
+
+    /// Logs every keypress it's sent, and handles only Escape.
+    #[derive(Debug)]
+    struct EscapeHandler {
+        children: Vec<Box<dyn Component<Message = ()>>>,
+        log: Arc<std::sync::Mutex<Vec<makeup_console::Keypress>>>,
+        key: Key,
+    }
+
+    #[async_trait]
+    impl Component for EscapeHandler {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            Some(self.children.iter().collect())
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            Some(self.children.iter_mut().collect())
+        }
+
+        async fn update(&mut self, ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            use makeup_console::Keypress;
+
+            if let Some(mailbox) = ctx.post_office.mailbox(self) {
+                for message in mailbox {
+                    if let either::Either::Right(MakeupMessage::Keypress(keypress)) = message {
+                        self.log.lock().unwrap().push(keypress.clone());
+                    }
+                }
+            }
+            check_mail!(
+                self,
+                ctx,
+                match _ {
+                    MakeupMessage::Keypress(Keypress::Escape) => {}
+                }
+            );
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unhandled_keypresses_bubble_up() -> Result<()> {
+        use crate::components::List;
+        use makeup_console::Keypress;
+
+        let log = Arc::new(std::sync::Mutex::new(vec![]));
+        let list = List::<()>::new(vec!["one".into(), "two".into()], 0, |_| ());
+        let list_key = list.key();
+        let mut root = EscapeHandler {
+            children: vec![Box::new(Container::new(vec![Box::new(list)]))],
+            log: log.clone(),
+            key: crate::component::generate_key(),
+        };
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        ui.update(&[Keypress::Tab]).await?;
+        ui.update(&[]).await?;
+        assert_eq!(list_key, ui.focus().await);
+        log.lock().unwrap().clear();
+
+        // The list handles Down but not Escape, and the container in between
+        // handles neither.
+        ui.update(&[Keypress::Down, Keypress::Escape]).await?;
+        assert_eq!(vec![Keypress::Escape], *log.lock().unwrap());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tab_moves_focus_between_inputs() -> Result<()> {
+        use crate::components::TextInput;
+        use makeup_console::Keypress;
+
+        let first = TextInput::<()>::new("a");
+        let second = TextInput::<()>::new("b");
+        let (first_key, second_key) = (first.key(), second.key());
+        let mut root = Container::<()>::column(vec![Box::new(first), Box::new(second)]);
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+
+        // Focus moves at the start of the next update.
+        let press = |keypress| {
+            let ui = &ui;
+            async move {
+                ui.update(&[keypress]).await?;
+                ui.update(&[]).await?;
+                eyre::Ok(ui.focus().await)
+            }
+        };
+        // From the container, which doesn't accept focus, to the first input.
+        assert_eq!(first_key, press(Keypress::Tab).await?);
+        assert_eq!(second_key, press(Keypress::Tab).await?);
+        assert_eq!(first_key, press(Keypress::Tab).await?);
+        assert_eq!(second_key, press(Keypress::ShiftTab).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_siblings_draw_from_their_own_origins() -> Result<()> {
+        let mut root = Container::<()>::row(vec![
+            Box::new(EchoText::<()>::new("ab\ncd")),
+            Box::new(EchoText::<()>::new("ef\ngh")),
+        ]);
+        let ui = crate::test::make_test_ui!(&mut root, 8, 4);
+        ui.render_once().await?;
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .unwrap();
+
+        // The first sibling's relative moves don't carry over into the
+        // second's, so neither draws over the other.
+        assert_eq!(vec!["abef", "cdgh"], renderer.read_region(0, 0, 4, 2)?);
+
+        Ok(())
+    }
+
+    /// Draws at the given z-index.
+    #[derive(Debug)]
+    struct LayeredComponent {
+        key: Key,
+        z_index: i32,
+    }
+
+    #[async_trait]
+    impl Component for LayeredComponent {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![DrawCommand::TextUnderCursor(self.z_index.to_string())])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(Some((3, 1)))
+        }
+
+        fn z_index(&self) -> i32 {
+            self.z_index
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batches_are_ordered_by_z_index() -> Result<()> {
+        let layers: Vec<LayeredComponent> = [0, 10, -5]
+            .into_iter()
+            .map(|z_index| LayeredComponent {
+                key: crate::component::generate_key(),
+                z_index,
+            })
+            .collect();
+        let keys: Vec<Key> = layers.iter().map(|layer| layer.key).collect();
+        let mut root = Container::<()>::new(
+            layers
+                .into_iter()
+                .map(|layer| Box::new(layer) as Box<dyn Component<Message = ()>>)
+                .collect(),
+        );
+        let ui = crate::test::make_test_ui!(&mut root, 8, 4);
+
+        let batches = ui
+            .render_commands(&mut crate::test::fake_render_ctx())
+            .await?;
+        let mut order: Vec<Key> = batches
+            .iter()
+            .map(|(key, _)| *key)
+            .filter(|key| keys.contains(key))
+            .collect();
+        // Each component's batch comes after the cursor move to its origin.
+        order.dedup();
+        assert_eq!(vec![keys[2], keys[0], keys[1]], order);
+
+        Ok(())
+    }
+
+    /// Draws its text without declaring any dimensions.
+    #[derive(Debug)]
+    struct UnsizedText {
+        text: String,
+        key: Key,
+    }
+
+    #[async_trait]
+    impl Component for UnsizedText {
+        type Message = ();
+
+        fn children(&self) -> Option<Vec<&Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        fn children_mut(
+            &mut self,
+        ) -> Option<Vec<&mut Box<dyn Component<Message = Self::Message>>>> {
+            None
+        }
+
+        async fn update(&mut self, _ctx: &mut MakeupUpdate<Self>) -> Result<()> {
+            Ok(())
+        }
+
+        async fn render(&self, _ctx: &RenderContext) -> Result<DrawCommandBatch> {
+            self.batch(vec![DrawCommand::TextUnderCursor(self.text.clone())])
+        }
+
+        fn key(&self) -> Key {
+            self.key
+        }
+
+        fn dimensions(&self) -> Result<Option<Dimensions>> {
+            Ok(None)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unclipped_newlines_return_to_the_first_column() -> Result<()> {
+        let mut root = Container::<()>::row(vec![
+            Box::new(EchoText::<()>::new("ab")),
+            Box::new(UnsizedText {
+                text: "cd\nef".into(),
+                key: crate::component::generate_key(),
+            }),
+        ]);
+        let ui = crate::test::make_test_ui!(&mut root, 8, 4);
+        ui.render_once().await?;
+        let renderer = ui.renderer().read().await;
+        let renderer = (**renderer)
+            .as_any()
+            .downcast_ref::<MemoryRenderer>()
+            .unwrap();
+
+        // Nothing clips the second component's text, so it isn't drawn from
+        // its left edge after the newline.
+        assert_eq!(vec!["abcd", "ef  "], renderer.read_region(0, 0, 4, 2)?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_terminal_focus_is_tracked() -> Result<()> {
+        use makeup_console::Keypress;
+
+        let mut root = EchoText::<()>::new("henol");
+        let renderer = MemoryRenderer::new(128, 128);
+        let input = TerminalInput::new().await?;
+        let ui = MUI::new(&mut root, Box::new(renderer), input)?;
+
+        let mut ctx = crate::test::fake_render_ctx();
+        ui.update(&[Keypress::FocusLost, Keypress::Char('a')])
+            .await?;
+        ui.render_commands(&mut ctx).await?;
+        assert!(!ctx.app_focused);
+
+        ui.update(&[Keypress::FocusGained]).await?;
+        ui.render_commands(&mut ctx).await?;
+        assert!(ctx.app_focused);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bound_keys_stop_rendering() -> Result<()> {
+        use makeup_console::{Keypress, Modifiers};
+
+        let mut root = EchoText::<()>::new("henol");
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        let ctrl_q = Keypress::Modified(Modifiers::CTRL, Box::new(Keypress::Char('q')));
+        ui.bind(ctrl_q, UiControlMessage::StopRendering).await;
+
+        ui.update(&[Keypress::Char('q')]).await?;
+        assert!(!*ui.done.lock().await);
+
+        // What the terminal sends for Ctrl+Q.
+        ui.update(&[Keypress::Char('\x11')]).await?;
+        assert!(*ui.done.lock().await);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_control_characters_read_as_other_keys_dont_match_ctrl_bindings() -> Result<()> {
+        use makeup_console::{Keypress, Modifiers};
+
+        let mut root = EchoText::<()>::new("henol");
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        let ctrl_a = Keypress::Modified(Modifiers::CTRL, Box::new(Keypress::Char('a')));
+        ui.bind(ctrl_a, UiControlMessage::StopRendering).await;
+
+        // What the terminal sends for Ctrl+A is read as Home.
+        ui.update(&[Keypress::Home]).await?;
+        assert!(!*ui.done.lock().await);
+
+        ui.bind(Keypress::Home, UiControlMessage::StopRendering)
+            .await;
+        ui.update(&[Keypress::Home]).await?;
+        assert!(*ui.done.lock().await);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_builder_configures_the_ui() -> Result<()> {
+        use super::{MuiBuilder, ScreenMode};
+
+        let mut root = EchoText::<()>::new("henol");
+        let ui = MuiBuilder::new(&mut root)
+            .renderer(Box::new(MemoryRenderer::new(16, 4)))
+            .input(NoInput)
+            .screen(ScreenMode::Alternate)
+            .fps_target(30)
+            .reduced_motion(true)
+            .build()
+            .await?;
+
+        assert_eq!(30, ui.fps_target().await);
+        assert_eq!(ScreenMode::Alternate, ui.screen);
+        let mut ctx = crate::test::fake_render_ctx();
+        ui.render_commands(&mut ctx).await?;
+        assert!(ctx.reduced_motion);
+
+        // There's no default input.
+        let mut root = EchoText::<()>::new("henol");
+        assert!(MuiBuilder::new(&mut root).build().await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rendering_on_demand_skips_unchanged_frames() -> Result<()> {
+        use makeup_console::Keypress;
+
+        let mut root = EchoText::<()>::new("henol");
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        ui.ui.lock().await.on_demand = true;
+
+        // Returns the frame counter after rendering, if the frame was.
+        let render = |mut frame_counter: u128| {
+            let ui = &ui;
+            async move {
+                ui.render_loop(
+                    &mut None,
+                    &mut frame_counter,
+                    &mut 0.0,
+                    &mut 0.0,
+                    &(0, 0),
+                    &(16, 4),
+                )
+                .await?;
+                eyre::Ok(frame_counter)
+            }
+        };
+
+        // The first frame is always rendered.
+        assert_eq!(1, render(0).await?);
+        ui.update(&[]).await?;
+        assert_eq!(1, render(1).await?);
+
+        ui.update(&[Keypress::Char('a')]).await?;
+        assert_eq!(2, render(1).await?);
+        assert_eq!(2, render(2).await?);
+
+        ui.invalidate().await;
+        assert_eq!(3, render(2).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_suspending_leaves_and_reenters_the_alternate_screen() -> Result<()> {
+        use crate::render::TerminalRenderer;
+        use crate::test::SharedBuffer;
+
+        let mut root = EchoText::<()>::new("henol");
+        let buffer = SharedBuffer::default();
+        let renderer = TerminalRenderer::with_writer(Box::new(buffer.clone()));
+        let ui = MUI::new(&mut root, Box::new(renderer), TerminalInput::new().await?)?;
+
+        ui.enter_alt_screen().await?;
+        assert_eq!("\x1b[?1049h\x1b[2J", buffer.contents());
+        buffer.0.lock().unwrap().clear();
+
+        let out = ui
+            .suspend(|| {
+                // The normal screen is back while `f` runs.
+                assert_eq!("\x1b[?1049l", buffer.contents());
+                42
+            })
+            .await?;
+        assert_eq!(42, out);
+        assert_eq!("\x1b[?1049l\x1b[?1049h\x1b[2J", buffer.contents());
+
+        buffer.0.lock().unwrap().clear();
+        ui.leave_alt_screen().await?;
+        assert_eq!("\x1b[?1049l", buffer.contents());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fps_target_sets_the_frame_duration() -> Result<()> {
+        let mut root = EchoText::<()>::new("henol");
+        let ui = crate::test::make_test_ui!(&mut root, 16, 4);
+        assert_eq!(super::FPS_TARGET, ui.fps_target().await);
+
+        ui.set_fps_target(4).await;
+        assert_eq!(4, ui.fps_target().await);
+        {
+            let ui = ui.ui.lock().await;
+            let now = tokio::time::Instant::now();
+            assert_eq!(
+                Some(Duration::from_millis(240)),
+                ui.pacer.sleep_for(Duration::from_millis(10), now)
+            );
+            // A frame that's too slow doesn't sleep at all.
+            assert_eq!(None, ui.pacer.sleep_for(Duration::from_secs(1), now));
+        }
+
+        // Uncapped.
+        ui.set_fps_target(0).await;
+        assert_eq!(0, ui.fps_target().await);
+        let ui = ui.ui.lock().await;
+        assert_eq!(
+            None,
+            ui.pacer
+                .sleep_for(Duration::ZERO, tokio::time::Instant::now())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ticker_drops_backlogs() {
+        use super::{Ticker, MAX_TICKS_PER_UPDATE};
+
+        let mut ticker = Ticker::new(Duration::from_millis(10));
+        let start = tokio::time::Instant::now();
+        assert_eq!(0, ticker.elapsed_ticks(start));
+        assert_eq!(2, ticker.elapsed_ticks(start + Duration::from_millis(25)));
+
+        // A suspend.
+        let later = start + Duration::from_secs(60 * 60 * 24 * 365);
+        assert_eq!(MAX_TICKS_PER_UPDATE, ticker.elapsed_ticks(later));
+        assert_eq!(0, ticker.elapsed_ticks(later + Duration::from_millis(5)));
+        assert_eq!(1, ticker.elapsed_ticks(later + Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn test_frame_target_lengthens_when_idle() {
+        use super::{frame_duration, FramePacer};
+
+        let mut pacer = FramePacer::new(frame_duration(60));
+        let start = pacer.last_activity;
+        assert_eq!(
+            frame_duration(60),
+            pacer.frame_target(start + Duration::from_secs(60))
+        );
+
+        pacer.idle = Some((frame_duration(4), Duration::from_secs(5)));
+        assert_eq!(
+            frame_duration(60),
+            pacer.frame_target(start + Duration::from_secs(4))
+        );
+        assert_eq!(
+            frame_duration(4),
+            pacer.frame_target(start + Duration::from_secs(5))
+        );
+
+        // Activity ramps straight back up.
+        pacer.activity(start + Duration::from_secs(6));
+        assert_eq!(
+            frame_duration(60),
+            pacer.frame_target(start + Duration::from_secs(7))
+        );
+        assert_eq!(
+            frame_duration(4),
+            pacer.frame_target(start + Duration::from_secs(11))
+        );
+    }
 }
@@ -0,0 +1,112 @@
+use async_trait::async_trait;
+use eyre::Result;
+
+use crate::input::InputFrame;
+use crate::render::memory::{OverflowBehaviour, WIDE_CONTINUATION};
+use crate::render::MemoryRenderer;
+use crate::{Component, Dimension, Input, MUI};
+
+/// Which edges of the buffer [`render_to_buffer`] cut content off at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Overflowed {
+    /// Content ran past the right edge.
+    pub horizontal: bool,
+    /// Content ran past the bottom edge.
+    pub vertical: bool,
+}
+
+impl Overflowed {
+    /// Whether anything was cut off at all.
+    pub fn any(&self) -> bool {
+        self.horizontal || self.vertical
+    }
+}
+
+/// Render `component` once into a buffer of at most `max_width`x`max_height`
+/// cells, and return what was drawn, one line per row with trailing
+/// whitespace and blank rows trimmed. Whatever doesn't fit is truncated, and
+/// reported in the returned [`Overflowed`], ex. so that a popup can be
+/// enlarged or given a scrollbar.
+pub async fn render_to_buffer<M: std::fmt::Debug + Send + Sync + Clone + 'static>(
+    component: &mut dyn Component<Message = M>,
+    max_width: Dimension,
+    max_height: Dimension,
+) -> Result<(String, Overflowed)> {
+    // Draw everything, and then see what falls outside of the buffer.
+    let (width, height) = component.dimensions()?.unwrap_or((0, 0));
+    let mut renderer = MemoryRenderer::new(width.max(max_width), height.max(max_height));
+    renderer.set_overflow_behaviour(OverflowBehaviour::Grow);
+
+    let ui = MUI::new(component, Box::new(renderer), NoInput)?;
+    ui.render_once().await?;
+
+    let renderer = ui.renderer().read().await;
+    let grid = (**renderer)
+        .as_any()
+        .downcast_ref::<MemoryRenderer>()
+        .expect("renderer is a MemoryRenderer")
+        .grid();
+
+    let mut overflowed = Overflowed::default();
+    for (x, y) in grid.keys() {
+        overflowed.horizontal |= *x >= max_width;
+        overflowed.vertical |= *y >= max_height;
+    }
+
+    let mut lines = vec![];
+    for y in 0..max_height {
+        let line: String = (0..max_width)
+            .map(|x| grid.get(&(x, y)).map_or(' ', |(c, _)| *c))
+            .filter(|c| *c != WIDE_CONTINUATION)
+            .collect();
+        lines.push(line.trim_end().to_string());
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    Ok((lines.join("\n"), overflowed))
+}
+
+/// Rendering into a buffer never reads input.
+#[derive(Debug, Clone)]
+struct NoInput;
+
+#[async_trait]
+impl Input for NoInput {
+    async fn next_frame(&self) -> Result<InputFrame> {
+        Ok(InputFrame::End)
+    }
+
+    async fn poll(&self) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_to_buffer, Overflowed};
+    use crate::components::EchoText;
+
+    use eyre::Result;
+
+    #[tokio::test]
+    async fn test_oversized_content_is_truncated() -> Result<()> {
+        let mut root = EchoText::<()>::new("henol world\nthis is\na popup");
+        let (text, overflowed) = render_to_buffer(&mut root, 8, 2).await?;
+        assert_eq!("henol wo\nthis is", text);
+        assert_eq!(
+            Overflowed {
+                horizontal: true,
+                vertical: true,
+            },
+            overflowed
+        );
+
+        let (text, overflowed) = render_to_buffer(&mut root, 16, 4).await?;
+        assert_eq!("henol world\nthis is\na popup", text);
+        assert!(!overflowed.any());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+/// Bonus for a match at the start of a word.
+const WORD_START_BONUS: i64 = 8;
+
+/// Bonus for a match directly after the previous one.
+const CONSECUTIVE_BONUS: i64 = 4;
+
+/// Fuzzy-match `needle` against `haystack`. Every character of the needle
+/// must appear in the haystack, in order, ignoring case; if it doesn't,
+/// `None` is returned.
+///
+/// Otherwise, returns the score of the best match and the indices of the
+/// haystack characters (not bytes) it matched, for highlighting. Matches at
+/// the start of a word and runs of consecutive matches score higher, and gaps
+/// between matches score lower. An empty needle matches everything with a
+/// score of 0.
+pub fn fuzzy_match(needle: &str, haystack: &str) -> Option<(i64, Vec<usize>)> {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = needle.chars().map(lower).collect();
+    let original: Vec<char> = haystack.chars().collect();
+    let haystack: Vec<char> = original.iter().copied().map(lower).collect();
+
+    if needle.is_empty() {
+        return Some((0, vec![]));
+    }
+
+    let bonus = |j: usize| {
+        if j == 0 || !original[j - 1].is_alphanumeric() {
+            1 + WORD_START_BONUS
+        } else {
+            1
+        }
+    };
+
+    // best[i][j] is the best score for needle[..=i] with needle[i] matched at
+    // haystack[j], and where needle[i - 1] was matched.
+    let mut best: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; haystack.len()]; needle.len()];
+    for (j, c) in haystack.iter().enumerate() {
+        if *c == needle[0] {
+            best[0][j] = Some((bonus(j), j));
+        }
+    }
+
+    for i in 1..needle.len() {
+        for j in 0..haystack.len() {
+            if haystack[j] != needle[i] {
+                continue;
+            }
+
+            best[i][j] = (0..j)
+                .filter_map(|k| {
+                    let gap = (j - k - 1) as i64;
+                    let adjacency = if gap == 0 { CONSECUTIVE_BONUS } else { -gap };
+                    best[i - 1][k].map(|(score, _)| (score + bonus(j) + adjacency, k))
+                })
+                // Prefer the earliest of equally good matches.
+                .max_by_key(|(score, k)| (*score, std::cmp::Reverse(*k)));
+        }
+    }
+
+    let last = needle.len() - 1;
+    let (score, mut j) = best[last]
+        .iter()
+        .enumerate()
+        .filter_map(|(j, entry)| entry.map(|(score, _)| (score, j)))
+        .max_by_key(|(score, j)| (*score, std::cmp::Reverse(*j)))?;
+
+    let mut indices = vec![0; needle.len()];
+    for i in (0..needle.len()).rev() {
+        indices[i] = j;
+        if let Some((_, previous)) = best[i][j] {
+            j = previous;
+        }
+    }
+
+    Some((score, indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    #[test]
+    fn test_fuzzy_match_ranks() {
+        let score = |needle, haystack| fuzzy_match(needle, haystack).map(|(score, _)| score);
+
+        assert_eq!(Some(0), score("", "anything"));
+        assert_eq!(None, score("xyz", "foobar"));
+        assert_eq!(None, score("bf", "foobar"));
+        assert!(score("FB", "foobar").is_some());
+
+        // A smaller gap beats a larger one.
+        assert!(score("fb", "foobar") > score("fb", "fizzbuzz"));
+        // Word starts beat matches in the middle of a word.
+        assert!(score("fb", "foo bar") > score("fb", "foobar"));
+        // Consecutive matches beat scattered ones.
+        assert!(score("fil", "open file") > score("fil", "fast inline"));
+    }
+
+    #[test]
+    fn test_fuzzy_match_indices() {
+        assert_eq!(Some(vec![0, 3]), indices("fb", "foobar"));
+        assert_eq!(Some(vec![0, 4]), indices("fb", "fizzbuzz"));
+        // The word start is preferred over the earlier `p`.
+        assert_eq!(Some(vec![6, 7]), indices("pa", "split pane"));
+        // Indices are of characters, not bytes.
+        assert_eq!(Some(vec![2]), indices("b", "💄 b"));
+    }
+
+    fn indices(needle: &str, haystack: &str) -> Option<Vec<usize>> {
+        fuzzy_match(needle, haystack).map(|(_, indices)| indices)
+    }
+}
@@ -1,3 +1,9 @@
+pub mod buffer;
+pub mod fuzzy;
+pub mod selection;
+
+pub use buffer::{render_to_buffer, Overflowed};
+
 /// Downcast any type into [`std::any::Any`].
 pub trait AsAny {
     fn as_any(&self) -> &dyn std::any::Any;
@@ -0,0 +1,123 @@
+/// The selected index into a list of `len` items, for components that move a
+/// selection up and down, so that they all handle the ends the same way.
+///
+/// An empty list has nothing selected, but [`Selection::index`] still reads
+/// as 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Selection {
+    index: usize,
+    len: usize,
+}
+
+impl Selection {
+    /// Select the first of `len` items.
+    pub fn new(len: usize) -> Self {
+        Self { index: 0, len }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Select the item at `index`, or the last item if it's past the end.
+    pub fn select(&mut self, index: usize) {
+        self.index = index;
+        self.clamp();
+    }
+
+    /// Change the number of items, keeping the selection if it's still in
+    /// the list, or selecting the last item otherwise.
+    pub fn set_len(&mut self, len: usize) {
+        self.len = len;
+        self.clamp();
+    }
+
+    /// Select the next item. Past the last item, `wrap` selects the first,
+    /// and otherwise the selection stays put.
+    pub fn next(&mut self, wrap: bool) {
+        if self.index + 1 < self.len {
+            self.index += 1;
+        } else if wrap {
+            self.index = 0;
+        }
+    }
+
+    /// Select the previous item. Before the first item, `wrap` selects the
+    /// last, and otherwise the selection stays put.
+    pub fn prev(&mut self, wrap: bool) {
+        if self.index > 0 {
+            self.index -= 1;
+        } else if wrap {
+            self.index = self.len.saturating_sub(1);
+        }
+    }
+
+    /// Move the selection by `delta` items, ex. a page, stopping at either
+    /// end rather than wrapping.
+    pub fn page(&mut self, delta: isize) {
+        self.index = self.index.saturating_add_signed(delta);
+        self.clamp();
+    }
+
+    /// Pull the selection back into the list, if it's past the end.
+    pub fn clamp(&mut self) {
+        self.index = self.index.min(self.len.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Selection;
+
+    #[test]
+    fn test_wrapping_at_both_ends() {
+        let mut selection = Selection::new(3);
+        selection.prev(true);
+        assert_eq!(2, selection.index());
+        selection.next(true);
+        assert_eq!(0, selection.index());
+
+        selection.prev(false);
+        assert_eq!(0, selection.index());
+        selection.select(2);
+        selection.next(false);
+        assert_eq!(2, selection.index());
+    }
+
+    #[test]
+    fn test_selection_is_clamped() {
+        let mut selection = Selection::new(5);
+        selection.select(10);
+        assert_eq!(4, selection.index());
+
+        selection.set_len(2);
+        assert_eq!(1, selection.index());
+
+        selection.set_len(0);
+        assert_eq!(0, selection.index());
+        selection.next(true);
+        selection.prev(true);
+        assert_eq!(0, selection.index());
+    }
+
+    #[test]
+    fn test_paging_stops_at_the_ends() {
+        let mut selection = Selection::new(10);
+        selection.page(4);
+        assert_eq!(4, selection.index());
+        selection.page(100);
+        assert_eq!(9, selection.index());
+        selection.page(-3);
+        assert_eq!(6, selection.index());
+        selection.page(-100);
+        assert_eq!(0, selection.index());
+    }
+}